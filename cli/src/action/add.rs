@@ -6,7 +6,7 @@ use thiserror::Error;
 use crate::cmd::matcher::{add::AddMatcher, MainMatcher, Matcher};
 #[cfg(all(feature = "tomb", target_os = "linux"))]
 use crate::util::tomb;
-use crate::util::{cli, edit, error, stdin, sync};
+use crate::util::{cli, edit, error, stdin, sync, template};
 
 /// Add secret action.
 pub struct Add<'a> {
@@ -53,7 +53,9 @@ impl<'a> Add<'a> {
 
         let mut plaintext = Plaintext::empty();
 
-        if matcher_add.stdin() {
+        if let Some(name) = matcher_add.template() {
+            plaintext = template::render(&store, name, &matcher_add.fields(), None, &matcher_main)?;
+        } else if matcher_add.stdin() {
             plaintext = stdin::read_plaintext(!matcher_main.quiet())?;
         } else if !matcher_add.empty() {
             if let Some(changed) = edit::edit(&plaintext).map_err(Err::Edit)? {
@@ -82,8 +84,7 @@ impl<'a> Add<'a> {
         }
 
         // Encrypt and write changed plaintext
-        // TODO: select proper recipients (use from current file?)
-        let recipients = store.recipients()?;
+        let recipients = store.recipients_for(&path)?;
         crate::crypto::context(&matcher_main)?
             .encrypt_file(&recipients, plaintext, &path)
             .map_err(Err::Write)?;
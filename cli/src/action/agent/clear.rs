@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::agent;
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    agent::{clear::ClearMatcher, AgentMatcher},
+    MainMatcher, Matcher,
+};
+
+/// An agent clear action.
+pub struct Clear<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Clear<'a> {
+    /// Construct a new clear action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the clear action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_agent = AgentMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_clear = ClearMatcher::with(self.cmd_matches).unwrap();
+
+        let mut client = agent::Client::connect(&agent::socket_path()).map_err(Err::Connect)?;
+        client.clear().map_err(Err::Clear)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Agent cache cleared");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("agent is not running")]
+    Connect(#[source] anyhow::Error),
+
+    #[error("failed to clear agent cache")]
+    Clear(#[source] anyhow::Error),
+}
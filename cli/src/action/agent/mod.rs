@@ -0,0 +1,53 @@
+pub mod clear;
+pub mod run;
+#[cfg(target_os = "linux")]
+pub mod start;
+pub mod status;
+pub mod stop;
+
+use anyhow::Result;
+use clap::ArgMatches;
+
+use crate::cmd::matcher::{agent::AgentMatcher, Matcher};
+
+/// Agent management action.
+pub struct Agent<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Agent<'a> {
+    /// Construct a new agent action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the agent action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_agent = AgentMatcher::with(self.cmd_matches).unwrap();
+
+        if matcher_agent.cmd_run().is_some() {
+            return run::Run::new(self.cmd_matches).invoke();
+        }
+
+        #[cfg(target_os = "linux")]
+        if matcher_agent.cmd_start().is_some() {
+            return start::Start::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_agent.cmd_stop().is_some() {
+            return stop::Stop::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_agent.cmd_status().is_some() {
+            return status::Status::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_agent.cmd_clear().is_some() {
+            return clear::Clear::new(self.cmd_matches).invoke();
+        }
+
+        // Unreachable, clap will print help for missing sub command instead
+        unreachable!()
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::agent;
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    agent::{run::RunMatcher, AgentMatcher},
+    MainMatcher, Matcher,
+};
+
+/// An agent run action.
+pub struct Run<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Run<'a> {
+    /// Construct a new run action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the run action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_agent = AgentMatcher::with(self.cmd_matches).unwrap();
+        let matcher_run = RunMatcher::with(self.cmd_matches).unwrap();
+
+        let socket = agent::socket_path();
+        if agent::Client::connect(&socket).is_ok() {
+            return Err(Err::Running.into());
+        }
+        if !matcher_main.quiet() {
+            eprintln!("Agent listening on: {}", socket.display());
+        }
+
+        agent::serve(&socket, Duration::from_secs(matcher_run.ttl())).map_err(Err::Serve)?;
+
+        if matcher_main.verbose() {
+            eprintln!("Agent stopped");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("agent is already running")]
+    Running,
+
+    #[error("failed to run agent")]
+    Serve(#[source] anyhow::Error),
+}
@@ -0,0 +1,94 @@
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{agent, systemd_bin};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    agent::{start::StartMatcher, AgentMatcher},
+    MainMatcher, Matcher,
+};
+
+/// Name of the systemd user unit running the agent.
+const AGENT_UNIT: &str = "prs-agent.service";
+
+/// Time to wait for the agent to come up after starting.
+const START_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// An agent start action.
+pub struct Start<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Start<'a> {
+    /// Construct a new start action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the start action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_agent = AgentMatcher::with(self.cmd_matches).unwrap();
+        let matcher_start = StartMatcher::with(self.cmd_matches).unwrap();
+
+        let socket = agent::socket_path();
+        if agent::Client::connect(&socket).is_ok() {
+            if !matcher_main.quiet() {
+                eprintln!("Agent is already running");
+            }
+            return Ok(());
+        }
+
+        // Run agent in transient systemd user service
+        let exe = std::env::current_exe().map_err(Err::Exe)?;
+        let ttl = format!("{}s", matcher_start.ttl());
+        systemd_bin::systemd_user_cmd_service(
+            "prs decryption cache agent",
+            AGENT_UNIT,
+            &[
+                exe.to_str().ok_or(Err::ExeUtf8)?,
+                "agent",
+                "--quiet",
+                "run",
+                "--ttl",
+                &ttl,
+            ],
+        )
+        .map_err(Err::Start)?;
+
+        // Wait for agent to accept connections
+        let mut waited = Duration::ZERO;
+        while agent::Client::connect(&socket).is_err() {
+            if waited >= START_TIMEOUT {
+                return Err(Err::NotStarted.into());
+            }
+            thread::sleep(Duration::from_millis(100));
+            waited += Duration::from_millis(100);
+        }
+
+        if !matcher_main.quiet() {
+            eprintln!("Agent started");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to determine current executable")]
+    Exe(#[source] std::io::Error),
+
+    #[error("current executable path contains invalid UTF-8")]
+    ExeUtf8,
+
+    #[error("failed to start agent as systemd user service")]
+    Start(#[source] anyhow::Error),
+
+    #[error("agent did not start, see: journalctl --user -u prs-agent")]
+    NotStarted,
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::agent;
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    agent::{status::StatusMatcher, AgentMatcher},
+    Matcher,
+};
+use crate::util::time;
+
+/// An agent status action.
+pub struct Status<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Status<'a> {
+    /// Construct a new status action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the status action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let _matcher_agent = AgentMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_status = StatusMatcher::with(self.cmd_matches).unwrap();
+
+        let socket = agent::socket_path();
+        let mut client = match agent::Client::connect(&socket) {
+            Ok(client) => client,
+            Err(_) => {
+                println!("Running: no");
+                return Ok(());
+            }
+        };
+        let status = client.status().map_err(Err::Status)?;
+
+        println!("Running: yes");
+        println!("Cached secrets: {}", status.entries);
+        println!(
+            "Cache time: {}",
+            time::format_duration(status.ttl.as_secs() as u32)
+        );
+        println!("Socket: {}", socket.display());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to query agent status")]
+    Status(#[source] anyhow::Error),
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::agent;
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    agent::{stop::StopMatcher, AgentMatcher},
+    MainMatcher, Matcher,
+};
+
+/// An agent stop action.
+pub struct Stop<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Stop<'a> {
+    /// Construct a new stop action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the stop action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_agent = AgentMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_stop = StopMatcher::with(self.cmd_matches).unwrap();
+
+        let mut client = agent::Client::connect(&agent::socket_path()).map_err(Err::Connect)?;
+        client.stop().map_err(Err::Stop)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Agent stopped");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("agent is not running")]
+    Connect(#[source] anyhow::Error),
+
+    #[error("failed to stop agent")]
+    Stop(#[source] anyhow::Error),
+}
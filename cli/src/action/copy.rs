@@ -1,6 +1,6 @@
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{crypto::prelude::*, Store};
+use prs_lib::Store;
 use thiserror::Error;
 
 use crate::cmd::matcher::{copy::CopyMatcher, MainMatcher, Matcher};
@@ -42,9 +42,10 @@ impl<'a> Copy<'a> {
 
         secret::print_name(matcher_copy.query(), &secret, &store, matcher_main.quiet());
 
-        let mut plaintext = crate::crypto::context(&matcher_main)?
-            .decrypt_file(&secret.path)
-            .map_err(Err::Read)?;
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let mut plaintext =
+            crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+                .map_err(Err::Read)?;
 
         // Trim plaintext to property or first line
         if let Some(property) = matcher_copy.property() {
@@ -1,8 +1,12 @@
 use std::fs;
+use std::path::Path;
 
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{Secret, Store};
+use prs_lib::{
+    crypto::{self, prelude::*},
+    Recipients, Secret, Store,
+};
 use thiserror::Error;
 
 use crate::cmd::matcher::{duplicate::DuplicateMatcher, MainMatcher, Matcher};
@@ -69,8 +73,21 @@ impl<'a> Duplicate<'a> {
             }
         }
 
-        // Copy secret
-        fs::copy(&secret.path, path).map_err(Err::Copy)?;
+        // Copy secret, re-encrypt if destination uses different recipients
+        match recipients_if_changed(&store, &secret, &path)? {
+            Some(recipients) => {
+                let mut context = crate::crypto::context(&matcher_main)?;
+                let plaintext =
+                    crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+                        .map_err(Err::Read)?;
+                context
+                    .encrypt_file(&recipients, plaintext, &path)
+                    .map_err(Err::Write)?;
+            }
+            None => {
+                fs::copy(&secret.path, path).map_err(Err::Copy)?;
+            }
+        }
 
         // Finalize sync
         if !matcher_duplicate.no_sync() {
@@ -92,6 +109,42 @@ impl<'a> Duplicate<'a> {
     }
 }
 
+/// Get the recipients for a secret destination if they differ from the secret's recipients.
+///
+/// Returns `None` if the secret at `dest` would use the same recipients, or if the secret is an
+/// alias which does not need re-encryption.
+pub(crate) fn recipients_if_changed(
+    store: &Store,
+    secret: &Secret,
+    dest: &Path,
+) -> Result<Option<Recipients>> {
+    // Aliases are never re-encrypted
+    if fs::symlink_metadata(&secret.path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+    {
+        return Ok(None);
+    }
+
+    // Quick check, same GPG IDs file means same recipients
+    if crypto::store::store_recipients_file_for(store, &secret.path)
+        == crypto::store::store_recipients_file_for(store, dest)
+    {
+        return Ok(None);
+    }
+
+    let recipients = store.recipients_for(dest).map_err(Err::Recipients)?;
+    if store
+        .recipients_for(&secret.path)
+        .map_err(Err::Recipients)?
+        .has_same_keys(&recipients)
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(recipients))
+}
+
 #[derive(Debug, Error)]
 pub enum Err {
     #[error("failed to access password store")]
@@ -109,4 +162,13 @@ pub enum Err {
 
     #[error("failed to copy secret file")]
     Copy(#[source] std::io::Error),
+
+    #[error("failed to load recipients for secret")]
+    Recipients(#[source] anyhow::Error),
+
+    #[error("failed to read secret")]
+    Read(#[source] anyhow::Error),
+
+    #[error("failed to write re-encrypted secret")]
+    Write(#[source] anyhow::Error),
 }
@@ -50,7 +50,9 @@ impl<'a> Edit<'a> {
         secret::print_name(matcher_edit.query(), &secret, &store, matcher_main.quiet());
 
         let mut context = crate::crypto::context(&matcher_main)?;
-        let mut plaintext = context.decrypt_file(&secret.path).map_err(Err::Read)?;
+        let mut plaintext =
+            crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+                .map_err(Err::Read)?;
 
         if matcher_edit.stdin() {
             plaintext = stdin::read_plaintext(!matcher_main.quiet())?;
@@ -78,8 +80,7 @@ impl<'a> Edit<'a> {
         }
 
         // Encrypt and write changed plaintext
-        // TODO: select proper recipients (use from current file?)
-        let recipients = store.recipients()?;
+        let recipients = store.recipients_for(&secret.path)?;
         context
             .encrypt_file(&recipients, plaintext, &secret.path)
             .map_err(Err::Write)?;
@@ -1,9 +1,8 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
-use chbs::{config::BasicConfig, prelude::*};
 use clap::ArgMatches;
-use prs_lib::{crypto::prelude::*, Plaintext, Secret, Store};
+use prs_lib::{crypto::prelude::*, template::Placeholder, Plaintext, Secret, Store};
 use thiserror::Error;
 
 use crate::cmd::matcher::{generate::GenerateMatcher, MainMatcher, Matcher};
@@ -11,7 +10,7 @@ use crate::cmd::matcher::{generate::GenerateMatcher, MainMatcher, Matcher};
 use crate::util::clipboard;
 #[cfg(all(feature = "tomb", target_os = "linux"))]
 use crate::util::tomb;
-use crate::util::{cli, edit, error, pass, secret, select, stdin, sync};
+use crate::util::{cli, edit, error, pass, secret, select, stdin, sync, template};
 
 /// Generate secret action.
 pub struct Generate<'a> {
@@ -81,13 +80,31 @@ impl<'a> Generate<'a> {
         let mut context = crate::crypto::context(&matcher_main)?;
         let mut plaintext = generate_password(&matcher_generate);
 
+        // Fill in template, use generated secret for its default password placeholders
+        if let Some(name) = matcher_generate.template() {
+            let kinds: &[Placeholder] = if matcher_generate.passphrase() {
+                &[Placeholder::Password(None), Placeholder::Passphrase(None)]
+            } else {
+                &[Placeholder::Password(None)]
+            };
+            plaintext = template::render(
+                &store,
+                name,
+                &matcher_generate.fields(),
+                Some((kinds, &plaintext)),
+                &matcher_main,
+            )?;
+        }
+
         // If destination already exists, merge
         if let Some(dest) = &dest {
             // Ask whether to merge
             let exists = dest.0.is_file();
+            let overwrite = matcher_generate.template().is_some();
             if !matcher_main.force() && !matcher_generate.merge() && exists {
                 eprintln!("A secret at '{}' already exists", dest.0.display(),);
-                if !cli::prompt_yes("Merge?", Some(true), &matcher_main) {
+                let prompt = if overwrite { "Overwrite?" } else { "Merge?" };
+                if !cli::prompt_yes(prompt, Some(true), &matcher_main) {
                     if !matcher_main.quiet() {
                         eprintln!("No secret generated");
                     }
@@ -95,12 +112,12 @@ impl<'a> Generate<'a> {
                 }
             }
 
-            // Append existing secret except first line to new secret
-            if exists {
-                let existing = context
-                    .decrypt_file(&dest.0)
-                    .and_then(|p| p.except_first_line())
-                    .map_err(Err::Read)?;
+            // Append existing secret except first line to new secret, templates replace it
+            if exists && !overwrite {
+                let existing =
+                    crate::crypto::decrypt_secret(&mut context, &store, &dest.1, &matcher_main)
+                        .and_then(|p| p.except_first_line())
+                        .map_err(Err::Read)?;
                 if !existing.is_empty() {
                     plaintext.append(existing, true);
                 }
@@ -147,8 +164,7 @@ impl<'a> Generate<'a> {
 
         // Encrypt and write changed plaintext if we need to store
         if let Some(dest) = &dest {
-            // TODO: select proper recipients (use from current file?)
-            let recipients = store.recipients()?;
+            let recipients = store.recipients_for(&dest.0)?;
             context
                 .encrypt_file(&recipients, plaintext.clone(), &dest.0)
                 .map_err(Err::Write)?;
@@ -204,11 +220,7 @@ impl<'a> Generate<'a> {
 /// This generates a secure random password/passphrase based on user configuration.
 fn generate_password(matcher_generate: &GenerateMatcher) -> Plaintext {
     if matcher_generate.passphrase() {
-        let config = BasicConfig {
-            words: matcher_generate.length() as usize,
-            ..Default::default()
-        };
-        config.to_scheme().generate().into()
+        pass::generate_passphrase(matcher_generate.length())
     } else {
         pass::generate_password(matcher_generate.length())
     }
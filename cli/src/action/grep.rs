@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::ArgMatches;
 use prs_lib::{
-    crypto::{prelude::*, Context},
+    crypto::{prelude::*, Context, ContextPool},
     store::SecretIterConfig,
     Plaintext, Secret, Store,
 };
@@ -86,8 +86,10 @@ fn grep(
     matcher_main: &MainMatcher,
     matcher_grep: &GrepMatcher,
 ) -> Result<()> {
-    let mut context = crate::crypto::context(matcher_main)?;
+    let config = crate::crypto::config(matcher_main);
+    let jobs = matcher_grep.jobs()?;
     let (mut found, mut failed) = (0, 0);
+    let pattern_upper = pattern.to_uppercase();
 
     // Parse regex if enabled
     let regex = if matcher_grep.regex() {
@@ -99,16 +101,14 @@ fn grep(
     // Progress bar
     let pb = progress::progress_bar(secrets.len() as u64, matcher_main.quiet());
 
-    for secret in secrets.iter() {
+    // Grep secrets in parallel, handle results in order
+    let job = |context: &mut Context, secret: &Secret| match &regex {
+        Some(re) => grep_single_regex(context, secret, re),
+        None => grep_single(context, secret, &pattern_upper),
+    };
+    ContextPool::parallel(&config, jobs, secrets, job, |secret, result| {
         pb.set_message_trunc(&secret.name);
 
-        // Parse normally or with regex
-        let result = match &regex {
-            Some(re) => grep_single_regex(&mut context, secret, re),
-            None => grep_single(&mut context, secret, pattern),
-        };
-
-        // Grep single secret
         match result {
             Ok(true) => {
                 pb.println_always(&secret.name);
@@ -124,19 +124,21 @@ fn grep(
         pb.inc(1);
 
         // Stop after many failures
-        if failed > MAX_FAIL && !matcher_main.force() {
-            error::quit_error_msg(
-                format!("stopped after {failed} failures"),
-                ErrorHintsBuilder::from_matcher(matcher_main)
-                    .force(true)
-                    .build()
-                    .unwrap(),
-            );
-        }
-    }
+        failed <= MAX_FAIL || matcher_main.force()
+    })?;
 
     pb.finish_and_clear();
 
+    if failed > MAX_FAIL && !matcher_main.force() {
+        error::quit_error_msg(
+            format!("stopped after {failed} failures"),
+            ErrorHintsBuilder::from_matcher(matcher_main)
+                .force(true)
+                .build()
+                .unwrap(),
+        );
+    }
+
     if !matcher_main.quiet() {
         if found > 0 {
             eprintln!();
@@ -157,6 +159,8 @@ fn grep(
 }
 
 /// Grep a single secret.
+///
+/// The pattern must be uppercase.
 fn grep_single(context: &mut Context, secret: &Secret, pattern: &str) -> Result<bool> {
     let plaintext: Plaintext = context
         .decrypt_file(&secret.path)
@@ -166,10 +170,7 @@ fn grep_single(context: &mut Context, secret: &Secret, pattern: &str) -> Result<
         .to_uppercase()
         .into();
 
-    Ok(plaintext
-        .unsecure_to_str()
-        .unwrap()
-        .contains(&pattern.to_uppercase()))
+    Ok(plaintext.unsecure_to_str().unwrap().contains(pattern))
 }
 
 /// Grep a single secret using a regular expression.
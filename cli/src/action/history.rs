@@ -0,0 +1,94 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::Store;
+use thiserror::Error;
+
+use crate::cmd::matcher::{history::HistoryMatcher, MainMatcher, Matcher};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{secret, style};
+
+/// Secret history action.
+pub struct History<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> History<'a> {
+    /// Construct a new history action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the history action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_history = HistoryMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        if !sync.is_init() {
+            return Err(Err::NoSync.into());
+        }
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        let name = secret::select_name_with_history(&store, matcher_history.query())
+            .ok_or(Err::NoneSelected)?;
+        let history = sync.secret_history(&name).map_err(Err::History)?;
+        if history.is_empty() {
+            return Err(Err::NoHistory(name).into());
+        }
+
+        if !matcher_main.quiet() {
+            eprintln!("Secret: {name}");
+        }
+        for revision in history {
+            println!(
+                "{}  {}  {}  {}{}",
+                style::highlight(revision.short_hash()),
+                revision.date,
+                revision.author,
+                revision.message,
+                if revision.removed { " (removed)" } else { "" },
+            );
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("store does not keep history, sync is not initialized")]
+    NoSync,
+
+    #[error("no secret selected")]
+    NoneSelected,
+
+    #[error("failed to read secret history")]
+    History(#[source] anyhow::Error),
+
+    #[error("no history for secret: {0}")]
+    NoHistory(String),
+}
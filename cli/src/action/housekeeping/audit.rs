@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use thiserror::Error;
+
+use prs_lib::{crypto, store::FilterSecretIter, trash, Recipients, Secret, Store};
+
+use crate::action::inspect::{self, Drift};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::{
+    cmd::matcher::{
+        housekeeping::{audit::AuditMatcher, HousekeepingMatcher},
+        MainMatcher, Matcher,
+    },
+    util::{
+        error,
+        progress::{self, ProgressBarExt},
+    },
+};
+
+/// A housekeeping audit action.
+pub struct Audit<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Audit<'a> {
+    /// Construct a new audit action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the audit action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_housekeeping = HousekeepingMatcher::with(self.cmd_matches).unwrap();
+        let matcher_audit = AuditMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Include trashed secrets, they may still be readable by removed recipients
+        let mut secrets = store.secrets(matcher_audit.query());
+        let trashed = crate::action::trash::secrets(&store).map_err(Err::Store)?;
+        secrets.extend(FilterSecretIter::new(
+            trashed.into_iter(),
+            matcher_audit.query(),
+        ));
+        let drifted = drifted(&store, &secrets, &matcher_main)?;
+
+        // Print drifted recipients, one per line
+        for (secret, drift) in &drifted {
+            for key in &drift.missing {
+                println!("{}\t+\t{}", secret.name, key.fingerprint(false));
+            }
+            for (key_id, key) in &drift.extra {
+                match key {
+                    Some(key) => println!("{}\t-\t{}", secret.name, key.fingerprint(false)),
+                    None => println!("{}\t-\t{}", secret.name, key_id),
+                }
+            }
+        }
+
+        if !matcher_main.quiet() {
+            eprintln!(
+                "{} of {} secrets have drifted recipients",
+                drifted.len(),
+                secrets.len()
+            );
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+        if !drifted.is_empty() {
+            error::exit(1);
+        }
+
+        Ok(())
+    }
+}
+
+/// Find secrets with drifted recipients.
+///
+/// Compares the keys each secret is encrypted for with the recipients that apply to its path,
+/// without decrypting it. Trashed secrets are compared with the recipients of the path they were
+/// removed from. Returns the drifted secrets along with their drift.
+pub fn drifted(
+    store: &Store,
+    secrets: &[Secret],
+    matcher_main: &MainMatcher,
+) -> Result<Vec<(Secret, Drift)>> {
+    let mut context = crate::crypto::context(matcher_main)?;
+    let mut recipients: HashMap<PathBuf, Recipients> = HashMap::new();
+    let mut drifted = Vec::new();
+
+    // Progress bar
+    let pb = progress::progress_bar(secrets.len() as u64, matcher_main.quiet());
+
+    for secret in secrets {
+        pb.set_message_trunc(&secret.name);
+
+        // Load recipients for secret once per GPG IDs file
+        let path = trash::recipients_path(store, secret);
+        let ids_file = crypto::store::store_recipients_file_for(store, &path);
+        if !recipients.contains_key(&ids_file) {
+            let loaded = store.recipients_for(&path).map_err(Err::Store)?;
+            recipients.insert(ids_file.clone(), loaded);
+        }
+
+        let drift = inspect::drift(&mut context, &secret.path, &recipients[&ids_file])
+            .map_err(|err| Err::Audit(secret.name.clone(), err))?;
+        if drift.has_drift() {
+            drifted.push((secret.clone(), drift));
+        }
+
+        pb.inc(1);
+    }
+
+    pb.finish_and_clear();
+
+    Ok(drifted)
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to read recipients of secret: {0}")]
+    Audit(String, #[source] anyhow::Error),
+}
@@ -0,0 +1,211 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::ArgMatches;
+use thiserror::Error;
+
+use prs_lib::{
+    crypto,
+    store::{SecretIterConfig, SECRET_SUFFIX, SECRET_SUFFIX_ARMOR},
+    Secret, Store,
+};
+
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::{
+    cmd::matcher::{
+        housekeeping::{convert::ConvertMatcher, HousekeepingMatcher},
+        MainMatcher, Matcher,
+    },
+    util::{error, sync},
+};
+
+/// A housekeeping convert action.
+pub struct Convert<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Convert<'a> {
+    /// Construct a new convert action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the convert action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_housekeeping = HousekeepingMatcher::with(self.cmd_matches).unwrap();
+        let matcher_convert = ConvertMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        if store.proto() != crypto::Proto::Gpg {
+            return Err(Err::Proto.into());
+        }
+        let armor = match matcher_convert.armor() {
+            Some(armor) => armor,
+            None => store.options().map_err(Err::Options)?.armor,
+        };
+        let format = if armor { "ASCII armored" } else { "binary" };
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_convert.allow_dirty());
+        if !matcher_convert.no_sync() {
+            sync.prepare()?;
+        }
+
+        let secrets = unconverted(&store, armor, matcher_convert.query());
+        for secret in &secrets {
+            convert(&store, secret, armor)?;
+            if matcher_main.verbose() {
+                eprintln!("Converted: {}", secret.name);
+            }
+        }
+
+        // Finalize sync
+        if !matcher_convert.no_sync() && !secrets.is_empty() {
+            sync.finalize(format!("Convert secrets to {format} format"))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Converted {} secrets to {format} format", secrets.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Find GPG secrets that are not in the given format.
+///
+/// Aliases are skipped, they are updated when converting the secret they point to.
+pub(crate) fn unconverted(store: &Store, armor: bool, query: Option<String>) -> Vec<Secret> {
+    let config = SecretIterConfig {
+        find_files: true,
+        find_symlink_files: false,
+    };
+    let from = if armor {
+        SECRET_SUFFIX
+    } else {
+        SECRET_SUFFIX_ARMOR
+    };
+    store
+        .secret_iter_config(config)
+        .filter_name(query)
+        .filter(|secret| secret.path.to_string_lossy().ends_with(from))
+        .collect()
+}
+
+/// Convert a secret to the given format.
+///
+/// Writes the secret with the suffix of the new format, and removes the old file. Aliases to the
+/// secret are updated to point to the new file.
+fn convert(store: &Store, secret: &Secret, armor: bool) -> Result<()> {
+    let (from, to) = if armor {
+        (SECRET_SUFFIX, SECRET_SUFFIX_ARMOR)
+    } else {
+        (SECRET_SUFFIX_ARMOR, SECRET_SUFFIX)
+    };
+    let path = converted_path(&secret.path, from, to);
+
+    // Write in new format, ciphertext is armored based on the suffix
+    let ciphertext = crypto::read_ciphertext_file(&secret.path).map_err(Err::Read)?;
+    crypto::write_ciphertext_file(&path, ciphertext).map_err(Err::Write)?;
+
+    // Find aliases before removing the original
+    #[cfg(feature = "alias")]
+    let aliases = crate::action::remove::find_symlinks_to(store, secret);
+
+    fs::remove_file(&secret.path).map_err(Err::Remove)?;
+
+    // Point aliases to converted secret
+    #[cfg(feature = "alias")]
+    {
+        let target = Secret::from(store, path);
+        for alias in aliases {
+            let alias_path = converted_path(&alias.path, from, to);
+            fs::remove_file(&alias.path).map_err(Err::Remove)?;
+            crate::action::alias::create_alias(store, &target, &alias_path, &alias_path)
+                .map_err(|err| Err::Alias(err.into()))?;
+        }
+    }
+    #[cfg(not(feature = "alias"))]
+    let _ = store;
+
+    Ok(())
+}
+
+/// Replace the suffix of a secret path.
+///
+/// The path is returned as-is if it doesn't have the `from` suffix.
+fn converted_path(path: &Path, from: &str, to: &str) -> PathBuf {
+    let path_str = path.to_string_lossy();
+    match path_str.strip_suffix(from) {
+        Some(base) => PathBuf::from(format!("{base}{to}")),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Warn if there are GPG secrets not in the format set by the store options.
+///
+/// Suggests to convert them with the convert command.
+pub(crate) fn warn_unconverted(store: &Store) -> Result<()> {
+    if store.proto() != crypto::Proto::Gpg {
+        return Ok(());
+    }
+    let armor = store.options().map_err(Err::Options)?.armor;
+    let count = unconverted(store, armor, None).len();
+    if count > 0 {
+        error::print_warning(format!(
+            "{count} secrets are not in {} format, convert them with: {} housekeeping convert",
+            if armor { "ASCII armored" } else { "binary" },
+            crate::util::bin_name(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("only secrets encrypted with GPG can be converted")]
+    Proto,
+
+    #[error("failed to load store options")]
+    Options(#[source] anyhow::Error),
+
+    #[error("failed to read secret")]
+    Read(#[source] anyhow::Error),
+
+    #[error("failed to write converted secret")]
+    Write(#[source] anyhow::Error),
+
+    #[error("failed to remove original secret")]
+    Remove(#[source] std::io::Error),
+
+    #[cfg(feature = "alias")]
+    #[error("failed to update alias")]
+    Alias(#[source] anyhow::Error),
+}
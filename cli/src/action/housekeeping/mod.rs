@@ -1,3 +1,5 @@
+pub mod audit;
+pub mod convert;
 pub mod recrypt;
 pub mod run;
 pub mod sync_keys;
@@ -23,6 +25,14 @@ impl<'a> Housekeeping<'a> {
         // Create the command matcher
         let matcher_housekeeping = HousekeepingMatcher::with(self.cmd_matches).unwrap();
 
+        if matcher_housekeeping.audit().is_some() {
+            return audit::Audit::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_housekeeping.convert().is_some() {
+            return convert::Convert::new(self.cmd_matches).invoke();
+        }
+
         if matcher_housekeeping.recrypt().is_some() {
             return recrypt::Recrypt::new(self.cmd_matches).invoke();
         }
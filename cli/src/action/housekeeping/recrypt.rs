@@ -1,21 +1,29 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
 use anyhow::Result;
 use clap::ArgMatches;
 use thiserror::Error;
 
 use prs_lib::{
-    crypto::{self, prelude::*, Context},
-    Recipients, Secret, Store,
+    crypto::{self, prelude::*, Context, ContextPool},
+    store::{FilterSecretIter, SignPolicy},
+    sync::STORE_GIT_DIR,
+    trash, Recipients, Secret, Store,
 };
 
 #[cfg(all(feature = "tomb", target_os = "linux"))]
 use crate::util::tomb;
 use crate::{
+    cmd::arg::ArgJobs,
     cmd::matcher::{
         housekeeping::{recrypt::RecryptMatcher, HousekeepingMatcher},
         MainMatcher, Matcher,
     },
     util::{
-        self,
+        self, cli,
         error::{self, ErrorHintsBuilder},
         progress::{self, ProgressBarExt},
         style, sync,
@@ -25,6 +33,16 @@ use crate::{
 /// Maximum number of failures without forcing.
 const MAX_FAIL: usize = 4;
 
+/// File to track progress of re-encrypting secrets.
+///
+/// Lists the names of all secrets to re-encrypt prefixed with `-`, and appends the names of
+/// re-encrypted secrets prefixed with `+`. It is removed once all secrets are re-encrypted, and is
+/// used to resume an interrupted re-encryption. See `state_path` for its location.
+const STATE_FILE: &str = ".prs-recrypt";
+
+/// State file name inside the git directory of a synced store.
+const STATE_FILE_GIT: &str = "prs-recrypt";
+
 /// A housekeeping recrypt action.
 pub struct Recrypt<'a> {
     cmd_matches: &'a ArgMatches,
@@ -57,17 +75,52 @@ impl<'a> Recrypt<'a> {
         tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
 
         // Prepare sync
-        sync::ensure_ready(&sync, matcher_recrypt.allow_dirty());
-        if !matcher_recrypt.no_sync() {
+        // Allow dirty when resuming, the store has partially re-encrypted secrets
+        sync::ensure_ready(
+            &sync,
+            matcher_recrypt.allow_dirty() || matcher_recrypt.resume(),
+        );
+        if !matcher_recrypt.no_sync() && !matcher_recrypt.resume() {
             sync.prepare()?;
         }
 
         // Import new keys
         crypto::store::import_missing_keys_from_store(&store).map_err(Err::ImportRecipients)?;
 
-        let secrets = store.secrets(matcher_recrypt.query());
+        // Select secrets including trashed, take remaining secrets from state when resuming
+        let trashed = crate::action::trash::secrets(&store).map_err(Err::Store)?;
+        let mut secrets: Vec<Secret> = if matcher_recrypt.resume() {
+            let pending = state_pending(&store)
+                .map_err(Err::State)?
+                .ok_or(Err::NoResume)?;
+            store
+                .secret_iter()
+                .chain(trashed)
+                .filter(|secret| pending.contains(&secret.name))
+                .collect()
+        } else {
+            let query = matcher_recrypt.query();
+            FilterSecretIter::new(store.secret_iter().chain(trashed), query).collect()
+        };
+
+        // Only keep secrets with drifted recipients
+        if matcher_recrypt.only_drifted() {
+            secrets = super::audit::drifted(&store, &secrets, &matcher_main)?
+                .into_iter()
+                .map(|(secret, _)| secret)
+                .collect();
+            if secrets.is_empty() && !matcher_main.quiet() {
+                eprintln!("No secrets with drifted recipients");
+            }
+        }
 
-        recrypt(&store, &secrets, &matcher_main)?;
+        recrypt(
+            &store,
+            &secrets,
+            matcher_recrypt.jobs()?,
+            matcher_recrypt.resume(),
+            &matcher_main,
+        )?;
 
         // Finalize sync
         if !matcher_recrypt.no_sync() {
@@ -82,48 +135,164 @@ impl<'a> Recrypt<'a> {
     }
 }
 
-/// Re-encrypt all secrets in the given store.
-pub fn recrypt_all(store: &Store, matcher_main: &MainMatcher) -> Result<()> {
-    recrypt(store, &store.secrets(None), matcher_main)
+/// Re-encrypt all secrets using the GPG IDs file of the given store directory.
+///
+/// Secrets in sub directories having their own GPG IDs file are skipped.
+pub fn recrypt_dir(store: &Store, dir: &Path, matcher_main: &MainMatcher) -> Result<()> {
+    recrypt_dirs(store, &[dir], ArgJobs::default_jobs(), matcher_main)
 }
 
-/// Re-encrypt all given secrets.
-pub fn recrypt(store: &Store, secrets: &[Secret], matcher_main: &MainMatcher) -> Result<()> {
-    let mut context = crate::crypto::context(matcher_main)?;
-    let recipients = store.recipients().map_err(Err::Store)?;
+/// Re-encrypt all secrets the GPG IDs files of the given store directories apply to.
+pub fn recrypt_dirs<P: AsRef<Path>>(
+    store: &Store,
+    dirs: &[P],
+    jobs: usize,
+    matcher_main: &MainMatcher,
+) -> Result<()> {
+    let ids_files: Vec<PathBuf> = dirs
+        .iter()
+        .map(|dir| crypto::store::store_recipients_file_for(store, dir.as_ref()))
+        .collect();
+    let trashed = crate::action::trash::secrets(store).map_err(Err::Store)?;
+    let secrets: Vec<Secret> = store
+        .secret_iter()
+        .chain(trashed)
+        .filter(|secret| {
+            let path = trash::recipients_path(store, secret);
+            ids_files.contains(&crypto::store::store_recipients_file_for(store, &path))
+        })
+        .collect();
+    recrypt(store, &secrets, jobs, false, matcher_main)
+}
 
+/// Re-encrypt all given secrets.
+///
+/// Each secret is encrypted for the recipients that apply to its path, trashed secrets use the
+/// path they were removed from. Secrets are re-encrypted
+/// on the given number of worker threads. Progress is tracked in the store, so an interrupted
+/// re-encryption can be resumed.
+///
+/// Unless resuming or forcing, the user is asked before discarding the progress of a pending
+/// interrupted re-encryption.
+pub fn recrypt(
+    store: &Store,
+    secrets: &[Secret],
+    jobs: usize,
+    resume: bool,
+    matcher_main: &MainMatcher,
+) -> Result<()> {
+    let config = crate::crypto::config(matcher_main);
+    let policy = store.options().map_err(Err::Store)?.sign_secrets;
+    let force = matcher_main.force();
+    let mut recrypted = 0;
     let mut failed = Vec::new();
 
+    // Don't discard the progress of an interrupted re-encryption
+    if !resume && !force {
+        let pending = state_pending(store)
+            .map_err(Err::State)?
+            .unwrap_or_default();
+        if !pending.is_empty()
+            && !cli::prompt_yes(
+                &format!(
+                    "An interrupted re-encryption of {} secrets is pending, discard it?",
+                    pending.len(),
+                ),
+                Some(false),
+                matcher_main,
+            )
+        {
+            error::quit_error_msg(
+                "an interrupted re-encryption is pending",
+                ErrorHintsBuilder::from_matcher(matcher_main)
+                    .add_info(format!(
+                        "Use '{}' to continue it first",
+                        style::highlight(format!(
+                            "{} housekeeping recrypt --resume",
+                            util::bin_name()
+                        ))
+                    ))
+                    .force(true)
+                    .build()
+                    .unwrap(),
+            );
+        }
+    }
+
+    // Load recipients for secrets once per GPG IDs file
+    let mut recipients: HashMap<PathBuf, Recipients> = HashMap::new();
+    let paths: Vec<PathBuf> = secrets
+        .iter()
+        .map(|secret| trash::recipients_path(store, secret))
+        .collect();
+    let ids_files: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| crypto::store::store_recipients_file_for(store, path))
+        .collect();
+    for (path, ids_file) in paths.iter().zip(&ids_files) {
+        if !recipients.contains_key(ids_file) {
+            let loaded = store.recipients_for(path).map_err(Err::Store)?;
+            recipients.insert(ids_file.clone(), loaded);
+        }
+    }
+    let jobs_secrets: Vec<(&Secret, &Recipients)> = secrets
+        .iter()
+        .zip(&ids_files)
+        .map(|(secret, ids_file)| (secret, &recipients[ids_file]))
+        .collect();
+
+    let mut state = state_start(store, secrets).map_err(Err::State)?;
+
     // Progress bar
     let pb = progress::progress_bar(secrets.len() as u64, matcher_main.quiet());
 
-    for secret in secrets.iter() {
+    // Recrypt secrets in parallel, handle results in order
+    let job = |context: &mut Context, (secret, recipients): &(&Secret, &Recipients)| {
+        recrypt_single(context, secret, recipients, policy, force)
+    };
+    ContextPool::parallel(&config, jobs, &jobs_secrets, job, |(secret, _), result| {
         pb.set_message_trunc(&secret.name);
 
-        // Recrypt secret, show status, remember errors
-        if let Err(err) = recrypt_single(&mut context, secret, &recipients) {
-            error::print_error(err.context(format!("recrypting failed: {}", secret.name)));
-            failed.push(secret);
+        // Show status, track progress, remember errors
+        match result {
+            Ok(()) => {
+                recrypted += 1;
+                if let Err(err) = writeln!(state, "+ {}", secret.name) {
+                    error::print_error(Err::State(err).into());
+                }
+            }
+            Err(err) => {
+                error::print_error(err.context(format!("recrypting failed: {}", secret.name)));
+                failed.push(*secret);
+            }
         }
 
         pb.inc(1);
 
         // Stop after many failures
-        if failed.len() > MAX_FAIL && !matcher_main.force() {
-            error::quit_error_msg(
-                format!("stopped after {} failures", failed.len()),
-                ErrorHintsBuilder::from_matcher(matcher_main)
-                    .force(true)
-                    .build()
-                    .unwrap(),
-            );
-        }
-    }
+        failed.len() <= MAX_FAIL || matcher_main.force()
+    })?;
 
     pb.finish_and_clear();
 
+    if failed.len() > MAX_FAIL && !matcher_main.force() {
+        error::quit_error_msg(
+            format!("stopped after {} failures", failed.len()),
+            ErrorHintsBuilder::from_matcher(matcher_main)
+                .add_info(format!(
+                    "Use '{}' to continue",
+                    style::highlight(format!(
+                        "{} housekeeping recrypt --resume",
+                        util::bin_name()
+                    ))
+                ))
+                .force(true)
+                .build()
+                .unwrap(),
+        );
+    }
+
     // Show success message if any is recrypted
-    let recrypted = secrets.len() - failed.len();
     if !matcher_main.quiet() && recrypted > 0 {
         eprintln!("Re-encrypted {} of {} secrets", recrypted, secrets.len());
     }
@@ -150,7 +319,7 @@ pub fn recrypt(store: &Store, secrets: &[Secret], matcher_main: &MainMatcher) ->
                     } else if failed.len() == 1 {
                         format!(" {}", &failed[0].name)
                     } else {
-                        "".into()
+                        " --resume".into()
                     }
                 ))
             );
@@ -159,13 +328,104 @@ pub fn recrypt(store: &Store, secrets: &[Secret], matcher_main: &MainMatcher) ->
         error::exit(1);
     }
 
+    state_finish(store).map_err(Err::State)?;
+
     Ok(())
 }
 
+/// Get the path of the re-encryption state file.
+///
+/// This is kept in the git directory if sync is initialized so it is never committed. Otherwise it
+/// is kept in the store root, which is also listed in the git ignore file.
+fn state_path(store: &Store) -> PathBuf {
+    let git_dir = store.root.join(STORE_GIT_DIR);
+    if git_dir.is_dir() {
+        git_dir.join(STATE_FILE_GIT)
+    } else {
+        store.root.join(STATE_FILE)
+    }
+}
+
+/// Start tracking re-encryption progress for the given secrets.
+///
+/// Returns the state file to append re-encrypted secrets to.
+fn state_start(store: &Store, secrets: &[Secret]) -> io::Result<File> {
+    let mut file = File::create(state_path(store))?;
+    for secret in secrets {
+        writeln!(file, "- {}", secret.name)?;
+    }
+    file.flush()?;
+    OpenOptions::new().append(true).open(state_path(store))
+}
+
+/// Get the names of secrets remaining from an interrupted re-encryption.
+///
+/// Returns `None` if there is nothing to resume.
+fn state_pending(store: &Store) -> io::Result<Option<HashSet<String>>> {
+    let state = match fs::read_to_string(state_path(store)) {
+        Ok(state) => state,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let mut pending = HashSet::new();
+    for line in state.lines() {
+        if let Some(name) = line.strip_prefix("- ") {
+            pending.insert(name.to_string());
+        } else if let Some(name) = line.strip_prefix("+ ") {
+            pending.remove(name);
+        }
+    }
+    Ok(Some(pending))
+}
+
+/// Finish tracking re-encryption progress, remove the state file.
+fn state_finish(store: &Store) -> io::Result<()> {
+    match fs::remove_file(state_path(store)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
 /// Recrypt a single secret.
-fn recrypt_single(context: &mut Context, secret: &Secret, recipients: &Recipients) -> Result<()> {
+///
+/// If the store signs secrets, its signature is verified against the recipients first, see
+/// `crate::crypto::decrypt_secret`. With the require policy a secret not signed by a recipient
+/// fails unless forcing, so we never sign secrets forged by others.
+fn recrypt_single(
+    context: &mut Context,
+    secret: &Secret,
+    recipients: &Recipients,
+    policy: Option<SignPolicy>,
+    force: bool,
+) -> Result<()> {
     let path = &secret.path;
-    let plaintext = context.decrypt_file(path).map_err(Err::Read)?;
+    let ciphertext = crypto::read_ciphertext_file(path).map_err(Err::Read)?;
+    let plaintext = match policy {
+        None => context.decrypt(ciphertext).map_err(Err::Read)?,
+        Some(policy) => {
+            let fingerprints: Vec<String> = recipients
+                .keys()
+                .iter()
+                .map(|key| key.fingerprint(false))
+                .collect();
+            let (plaintext, untrusted) = crate::crypto::decrypt_check_signers(
+                context,
+                ciphertext,
+                &fingerprints,
+                &secret.name,
+            )
+            .map_err(Err::Read)?;
+            match untrusted {
+                Some(msg) if policy == SignPolicy::Require && !force => {
+                    return Err(Err::Untrusted(msg).into());
+                }
+                Some(msg) => error::print_warning(msg),
+                None => {}
+            }
+            plaintext
+        }
+    };
     context
         .encrypt_file(recipients, plaintext, path)
         .map_err(Err::Write)?;
@@ -190,4 +450,13 @@ pub enum Err {
 
     #[error("failed to import store recipients")]
     ImportRecipients(#[source] anyhow::Error),
+
+    #[error("failed to track re-encryption progress")]
+    State(#[source] io::Error),
+
+    #[error("no interrupted re-encryption to resume")]
+    NoResume,
+
+    #[error("{0}")]
+    Untrusted(String),
 }
@@ -1,9 +1,14 @@
 use std::fs::{self, OpenOptions};
 use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::Store;
+use prs_lib::{
+    crypto::{self, prelude::*},
+    Key, Recipients, Store,
+};
 use thiserror::Error;
 
 #[cfg(all(feature = "tomb", target_os = "linux"))]
@@ -16,11 +21,12 @@ const LINE_ENDING: &str = "\n";
 const LINE_ENDING: &str = "\r\n";
 
 use crate::{
+    cmd::arg::ArgJobs,
     cmd::matcher::{
         housekeeping::{run::RunMatcher, HousekeepingMatcher},
         MainMatcher, Matcher,
     },
-    util::sync,
+    util::{error, sync, time},
 };
 
 /// A housekeeping run action.
@@ -55,6 +61,24 @@ impl<'a> Run<'a> {
 
         housekeeping(&store, matcher_run.allow_dirty(), matcher_run.no_sync())?;
 
+        remove_expired_recipients(
+            &store,
+            matcher_run.allow_dirty(),
+            matcher_run.no_sync(),
+            &matcher_main,
+        )?;
+
+        empty_expired_trash(
+            &store,
+            matcher_run.allow_dirty(),
+            matcher_run.no_sync(),
+            &matcher_main,
+        )?;
+
+        warn_unusable_recipients(&store, Duration::from_secs(matcher_run.expiry_window()))?;
+
+        super::convert::warn_unconverted(&store)?;
+
         // Finalize tomb
         #[cfg(all(feature = "tomb", target_os = "linux"))]
         tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
@@ -92,6 +116,166 @@ pub(crate) fn housekeeping(store: &Store, allow_dirty: bool, no_sync: bool) -> R
     Ok(())
 }
 
+/// Remove recipients whose access has expired, and re-encrypt the affected secrets.
+///
+/// Recipients are not removed from a directory if all of its recipients have expired, as that
+/// would make its secrets inaccessible.
+fn remove_expired_recipients(
+    store: &Store,
+    allow_dirty: bool,
+    no_sync: bool,
+    matcher_main: &MainMatcher,
+) -> Result<()> {
+    // Find expired recipients in each store directory
+    let mut expired: Vec<(PathBuf, Recipients, Vec<Key>)> = vec![];
+    for file in crypto::store::store_expiry_files(store) {
+        let dir = file.parent().unwrap_or(&store.root).to_path_buf();
+        let fingerprints: Vec<String> = crypto::store::store_read_expiry_at(store, &dir)
+            .map_err(Err::Expiry)?
+            .into_iter()
+            .filter(|expiry| expiry.is_expired())
+            .map(|expiry| expiry.fingerprint)
+            .collect();
+        if fingerprints.is_empty() {
+            continue;
+        }
+
+        let recipients = store.recipients_for(&dir).map_err(Err::Recipients)?;
+        let keys: Vec<Key> = recipients
+            .keys()
+            .iter()
+            .filter(|key| {
+                fingerprints
+                    .iter()
+                    .any(|fp| crypto::util::fingerprints_equal(fp, key.fingerprint(false)))
+            })
+            .cloned()
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+        if keys.len() == recipients.keys().len() {
+            error::print_warning(format!(
+                "not removing expired recipients from {}, no recipients would be left",
+                dir.display(),
+            ));
+            continue;
+        }
+        expired.push((dir, recipients, keys));
+    }
+    if expired.is_empty() {
+        return Ok(());
+    }
+
+    let sync = store.sync();
+
+    // Prepare sync
+    sync::ensure_ready(&sync, allow_dirty);
+    if !no_sync {
+        sync.prepare()?;
+    }
+
+    // Remove recipients, re-encrypt secrets
+    for (dir, recipients, keys) in &mut expired {
+        recipients.remove_all(keys);
+        recipients.save_at(store, dir)?;
+    }
+    if prs_lib::store::can_decrypt(store) {
+        let dirs: Vec<&PathBuf> = expired.iter().map(|(dir, _, _)| dir).collect();
+        super::recrypt::recrypt_dirs(store, &dirs, ArgJobs::default_jobs(), matcher_main)
+            .map_err(Err::Recrypt)?;
+    } else if !matcher_main.quiet() {
+        crate::action::recipients::add::cannot_decrypt_show_recrypt_hints();
+    }
+
+    // Finalize sync
+    let keys: Vec<&Key> = expired.iter().flat_map(|(_, _, keys)| keys).collect();
+    if !no_sync {
+        sync.finalize(format!(
+            "Remove expired recipients {}",
+            keys.iter()
+                .map(|key| key.fingerprint(true))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ))?;
+    }
+
+    if !matcher_main.quiet() {
+        for key in keys {
+            eprintln!("Removed expired recipient: {key}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Permanently delete secrets that have been in the trash for longer than the store retention.
+fn empty_expired_trash(
+    store: &Store,
+    allow_dirty: bool,
+    no_sync: bool,
+    matcher_main: &MainMatcher,
+) -> Result<()> {
+    let retention = store.options().map_err(Err::Store)?.trash_retention();
+    let trash = store.trash();
+    let now = SystemTime::now();
+    let expired: Vec<_> = crate::action::trash::list_valid(&trash)
+        .map_err(Err::Trash)?
+        .into_iter()
+        .filter(|secret| secret.is_expired(retention, now))
+        .collect();
+    if expired.is_empty() {
+        return Ok(());
+    }
+
+    let sync = store.sync();
+
+    // Prepare sync
+    sync::ensure_ready(&sync, allow_dirty);
+    if !no_sync {
+        sync.prepare()?;
+    }
+
+    for secret in &expired {
+        trash.delete(secret).map_err(Err::Trash)?;
+    }
+
+    // Finalize sync
+    if !no_sync {
+        sync.finalize("Remove expired secrets from trash")?;
+    }
+
+    if !matcher_main.quiet() {
+        eprintln!(
+            "Removed {} expired secret{} from trash",
+            expired.len(),
+            if expired.len() == 1 { "" } else { "s" },
+        );
+    }
+
+    Ok(())
+}
+
+/// Warn about store recipients having unusable keys or keys expiring within the given window.
+fn warn_unusable_recipients(store: &Store, window: Duration) -> Result<()> {
+    let keys = crypto::store::store_load_all_keys(store).map_err(Err::Recipients)?;
+    for key in keys {
+        if let Err(err) = key.check_usable() {
+            error::print_warning(format!("recipient cannot be used ({err}): {key}"));
+        } else if key.expires_within(window) {
+            let remaining = key
+                .expiry_time()
+                .and_then(|expires| expires.duration_since(SystemTime::now()).ok())
+                .unwrap_or_default();
+            error::print_warning(format!(
+                "recipient expires in {}: {key}",
+                time::format_duration(remaining.as_secs() as u32),
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Set the password store directory permissions to a secure default.
 #[cfg(unix)]
 fn set_store_permissions(store: &Store) -> Result<(), std::io::Error> {
@@ -113,7 +297,15 @@ fn set_store_permissions(_store: &Store) -> Result<(), std::io::Error> {
 
 /// Set up the git ignore file.
 fn set_git_ignore(store: &Store) -> Result<(), std::io::Error> {
-    const ENTRIES: [&str; 6] = [".host", ".last", ".tty", ".uid", ".timer", "lost+found"];
+    const ENTRIES: [&str; 7] = [
+        ".host",
+        ".last",
+        ".tty",
+        ".uid",
+        ".timer",
+        "lost+found",
+        ".prs-recrypt",
+    ];
 
     let file = store.root.join(".gitignore");
 
@@ -182,9 +374,121 @@ pub enum Err {
     #[error("failed to prepare password store tomb for usage")]
     Tomb(#[source] anyhow::Error),
 
+    #[error("failed to load store recipients")]
+    Recipients(#[source] anyhow::Error),
+
+    #[error("failed to read recipient expiry dates")]
+    Expiry(#[source] anyhow::Error),
+
+    #[error("failed to re-encrypt secrets in store")]
+    Recrypt(#[source] anyhow::Error),
+
+    #[error("failed to remove expired secrets from trash")]
+    Trash(#[source] anyhow::Error),
+
     #[error("failed to set password store permissions")]
     Perms(#[source] std::io::Error),
 
     #[error("failed to set default .gitattributes")]
     GitAttributes(#[source] std::io::Error),
 }
+
+#[cfg(all(test, feature = "backend-mock"))]
+mod tests {
+    use std::path::Path;
+
+    use prs_lib::crypto::backend::mock::{self, fixture::TempStore};
+    use prs_lib::Plaintext;
+
+    use super::*;
+    use crate::cmd::handler::Handler;
+
+    /// Remove expired recipients from the given store, quietly and without syncing.
+    fn remove_expired(store: &Store) {
+        let matches = Handler::build().get_matches_from(["prs", "-q", "-I", "housekeeping", "run"]);
+        let matcher_main = MainMatcher::with(&matches).unwrap();
+        remove_expired_recipients(store, true, true, &matcher_main).unwrap();
+    }
+
+    /// Get the fingerprints of the recipients of the given directory.
+    fn recipients(store: &Store, dir: &Path) -> Vec<String> {
+        store
+            .recipients_for(dir)
+            .unwrap()
+            .keys()
+            .iter()
+            .map(|key| key.fingerprint(false))
+            .collect()
+    }
+
+    #[test]
+    fn remove_expired_recipient() {
+        let alice = mock::add_secret_key("housekeeping-alice");
+        let bob = mock::add_public_key("housekeeping-bob");
+        let carol = mock::add_public_key("housekeeping-carol");
+        let store = TempStore::new(&[alice.clone(), bob.clone(), carol.clone()]).unwrap();
+        let secret = store.add_secret("secret", Plaintext::from("a")).unwrap();
+        let past = crypto::store::parse_expiry_date("2020-01-01").unwrap();
+        let future = crypto::store::parse_expiry_date("9999-12-31").unwrap();
+        crypto::store::store_set_expiry_at(
+            &store,
+            &store.root,
+            &bob.fingerprint(false),
+            Some(past),
+        )
+        .unwrap();
+        crypto::store::store_set_expiry_at(
+            &store,
+            &store.root,
+            &carol.fingerprint(false),
+            Some(future),
+        )
+        .unwrap();
+
+        // Expired recipient is removed, and secrets are re-encrypted without it
+        remove_expired(&store);
+        assert_eq!(
+            recipients(&store, &store.root),
+            vec![alice.fingerprint(false), carol.fingerprint(false)],
+        );
+        let key_ids = crypto::context(&crypto::store::store_crypto_config(&store).unwrap())
+            .unwrap()
+            .encrypted_key_ids_file(&secret.path)
+            .unwrap();
+        assert_eq!(key_ids.len(), 2);
+        assert!(!key_ids
+            .iter()
+            .any(|id| bob.fingerprint(false).ends_with(id.as_str())));
+
+        // Expiry date of removed recipient is forgotten
+        let expiry = crypto::store::store_read_expiry_at(&store, &store.root).unwrap();
+        assert_eq!(expiry.len(), 1);
+        assert_eq!(expiry[0].fingerprint, carol.fingerprint(false));
+    }
+
+    #[test]
+    fn remove_expired_keeps_last_recipients() {
+        let alice = mock::add_secret_key("housekeeping-last-alice");
+        let bob = mock::add_public_key("housekeeping-last-bob");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+        let team = store.root.join("team");
+        crypto::store::store_save_keys_at(&store, &team, std::slice::from_ref(&bob)).unwrap();
+        let past = crypto::store::parse_expiry_date("2020-01-01").unwrap();
+        crypto::store::store_set_expiry_at(&store, &team, &bob.fingerprint(false), Some(past))
+            .unwrap();
+
+        // Directory is not left without recipients
+        remove_expired(&store);
+        assert_eq!(recipients(&store, &team), vec![bob.fingerprint(false)]);
+        assert_eq!(
+            crypto::store::store_read_expiry_at(&store, &team)
+                .unwrap()
+                .len(),
+            1,
+        );
+        assert_eq!(
+            recipients(&store, &store.root),
+            vec![alice.fingerprint(false)]
+        );
+    }
+}
@@ -63,9 +63,9 @@ impl<'a> SyncKeys<'a> {
                 .map_err(Err::ImportKeys)?;
         }
 
-        // Sync public key files in store
-        let recipients = store.recipients().map_err(Err::Load)?;
-        crypto::store::store_sync_public_key_files(&store, recipients.keys())?;
+        // Sync public key files in store, for recipients in all GPG IDs files
+        let keys = crypto::store::store_load_all_keys(&store).map_err(Err::Load)?;
+        crypto::store::store_sync_public_key_files(&store, &keys)?;
 
         // Finalize sync
         if !matcher_sync_keys.no_sync() {
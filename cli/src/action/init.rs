@@ -3,7 +3,10 @@ use std::path::Path;
 
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{crypto, Store};
+use prs_lib::{
+    crypto::{self, Proto},
+    Store,
+};
 use thiserror::Error;
 
 use crate::cmd::matcher::{init::InitMatcher, MainMatcher, Matcher};
@@ -24,12 +27,16 @@ impl<'a> Init<'a> {
     pub fn invoke(&self) -> Result<()> {
         // Create the command matchers
         let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
-        let _matcher_init = InitMatcher::with(self.cmd_matches).unwrap();
+        let matcher_init = InitMatcher::with(self.cmd_matches).unwrap();
 
         let path = shellexpand::full(&matcher_main.store())
             .map_err(Err::ExpandPath)?
             .to_string();
 
+        // Ensure protocol is supported by this build
+        let proto = matcher_init.proto();
+        crypto::context(&crypto::Config::from(proto)).map_err(Err::Proto)?;
+
         // Ensure store dir is free, then initialize
         util::fs::ensure_dir_free(Path::new(&path))?;
         fs::create_dir_all(&path).map_err(Err::Init)?;
@@ -37,6 +44,11 @@ impl<'a> Init<'a> {
         // Open new store
         let store = Store::open(&path).map_err(Err::Store)?;
 
+        // An empty age recipients file marks the store as age store
+        if proto == Proto::Age {
+            fs::write(crypto::store::store_age_recipients_file(&store), "").map_err(Err::Init)?;
+        }
+
         // Run housekeeping
         crate::action::housekeeping::run::housekeeping(&store, true, false)
             .map_err(Err::Housekeeping)?;
@@ -74,6 +86,9 @@ pub enum Err {
     #[error("failed to expand store path")]
     ExpandPath(#[source] shellexpand::LookupError<std::env::VarError>),
 
+    #[error("failed to initialize store, unsupported cryptography protocol")]
+    Proto(#[source] crypto::Err),
+
     #[error("failed to initialize store")]
     Init(#[source] std::io::Error),
 
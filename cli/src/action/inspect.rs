@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{prelude::*, Context},
+    Key, Recipients, Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{inspect::InspectMatcher, MainMatcher, Matcher};
+use crate::util::select;
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+
+/// Inspect secret action.
+pub struct Inspect<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Inspect<'a> {
+    /// Construct a new inspect action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the inspect action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_inspect = InspectMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        let secret = select::store_select_secret(&store, matcher_inspect.query())
+            .ok_or(Err::NoneSelected)?;
+        let recipients = store
+            .recipients_for(&secret.path)
+            .map_err(Err::Recipients)?;
+
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let drift = drift(&mut context, &secret.path, &recipients).map_err(Err::Inspect)?;
+
+        // List keys the secret is encrypted for, mark extra ones
+        for (key_id, key) in &drift.encrypted_to {
+            let is_extra = drift.extra.iter().any(|(other, _)| other == key_id);
+            let marker = if is_extra { '-' } else { ' ' };
+
+            match (key, matcher_main.quiet()) {
+                (Some(key), false) if !is_extra => println!("{marker} {key}"),
+                (Some(key), false) => println!("{marker} {key} (not a recipient)"),
+                (Some(key), true) => println!("{marker} {}", key.fingerprint(false)),
+                (None, false) => println!("{marker} {key_id} (unknown key, not a recipient)"),
+                (None, true) => println!("{marker} {key_id}"),
+            }
+        }
+
+        // List recipients the secret is not encrypted for
+        for key in &drift.missing {
+            if !matcher_main.quiet() {
+                println!("+ {key} (missing)");
+            } else {
+                println!("+ {}", key.fingerprint(false));
+            }
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+        Ok(())
+    }
+}
+
+/// Recipient drift of a secret.
+///
+/// Compares the keys a secret is encrypted for with the recipients that apply to it.
+pub(crate) struct Drift {
+    /// Key IDs the secret is encrypted for, along with their key if in the keychain.
+    pub encrypted_to: Vec<(String, Option<Key>)>,
+
+    /// Recipients the secret is not encrypted for.
+    pub missing: Vec<Key>,
+
+    /// Key IDs the secret is encrypted for that don't belong to a recipient.
+    pub extra: Vec<(String, Option<Key>)>,
+}
+
+impl Drift {
+    /// Check whether the secret has drifted from its recipients.
+    pub fn has_drift(&self) -> bool {
+        !self.missing.is_empty() || !self.extra.is_empty()
+    }
+}
+
+/// Determine recipient drift for the secret at the given path.
+///
+/// This reads the key IDs from the ciphertext without decrypting it.
+pub(crate) fn drift(context: &mut Context, path: &Path, recipients: &Recipients) -> Result<Drift> {
+    // Find keys for all key IDs the secret is encrypted for
+    let encrypted_to: Vec<(String, Option<Key>)> = context
+        .encrypted_key_ids_file(path)?
+        .into_iter()
+        .map(|key_id| {
+            let key = context.find_key_by_id(&key_id)?;
+            Ok((key_id, key))
+        })
+        .collect::<Result<_>>()?;
+
+    let missing = recipients
+        .keys()
+        .iter()
+        .filter(|key| {
+            !encrypted_to
+                .iter()
+                .filter_map(|(_, other)| other.as_ref())
+                .any(|other| other == *key)
+        })
+        .cloned()
+        .collect();
+    let extra = encrypted_to
+        .iter()
+        .filter(|(_, key)| match key {
+            Some(key) => !recipients.has_fingerprint(&key.fingerprint(false)),
+            None => true,
+        })
+        .cloned()
+        .collect();
+
+    Ok(Drift {
+        encrypted_to,
+        missing,
+        extra,
+    })
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("no secret selected")]
+    NoneSelected,
+
+    #[error("failed to load recipients for secret")]
+    Recipients(#[source] anyhow::Error),
+
+    #[error("failed to inspect recipients of secret")]
+    Inspect(#[source] anyhow::Error),
+}
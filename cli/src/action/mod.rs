@@ -1,4 +1,6 @@
 pub mod add;
+#[cfg(unix)]
+pub mod agent;
 #[cfg(feature = "alias")]
 pub mod alias;
 pub mod clone;
@@ -9,13 +11,17 @@ pub mod edit;
 pub mod generate;
 pub mod git;
 pub mod grep;
+pub mod history;
 pub mod housekeeping;
 pub mod init;
+pub mod inspect;
 pub mod internal;
 pub mod list;
 pub mod r#move;
 pub mod recipients;
 pub mod remove;
+pub mod restore;
+pub mod set;
 pub mod show;
 pub mod slam;
 pub mod sync;
@@ -23,3 +29,5 @@ pub mod sync;
 pub mod tomb;
 #[cfg(feature = "totp")]
 pub mod totp;
+pub mod trash;
+pub mod unset;
@@ -4,7 +4,7 @@ use std::path::Path;
 
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{Secret, Store};
+use prs_lib::{crypto::prelude::*, Secret, Store};
 use thiserror::Error;
 
 use crate::cmd::matcher::{r#move::MoveMatcher, MainMatcher, Matcher};
@@ -81,10 +81,22 @@ impl<'a> Move<'a> {
             update_alias_for_secret_to(&store, &secret, &new_secret);
         }
 
-        // Move secret
-        fs::rename(&secret.path, path)
-            .map(|_| ())
-            .map_err(Err::Move)?;
+        // Move secret, re-encrypt if destination uses different recipients
+        match super::duplicate::recipients_if_changed(&store, &secret, &path)? {
+            Some(recipients) => {
+                let mut context = crate::crypto::context(&matcher_main)?;
+                let plaintext =
+                    crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+                        .map_err(Err::Read)?;
+                context
+                    .encrypt_file(&recipients, plaintext, &path)
+                    .map_err(Err::Write)?;
+                fs::remove_file(&secret.path).map_err(Err::Move)?;
+            }
+            None => fs::rename(&secret.path, path)
+                .map(|_| ())
+                .map_err(Err::Move)?,
+        }
 
         super::remove::remove_empty_secret_dir(&secret);
 
@@ -205,6 +217,12 @@ pub enum Err {
     #[error("failed to move secret file")]
     Move(#[source] std::io::Error),
 
+    #[error("failed to read secret")]
+    Read(#[source] anyhow::Error),
+
+    #[error("failed to write re-encrypted secret")]
+    Write(#[source] anyhow::Error),
+
     #[cfg(feature = "alias")]
     #[error("failed to update alias")]
     UpdateAlias(#[source] std::io::Error),
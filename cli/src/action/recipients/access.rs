@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, prelude::*, Context},
+    Key, Secret, Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::{access::AccessMatcher, RecipientsMatcher},
+    MainMatcher, Matcher,
+};
+use crate::util::progress::{self, ProgressBarExt};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+
+/// A recipients access action.
+pub struct Access<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Access<'a> {
+    /// Construct a new access action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the access action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
+        let matcher_access = AccessMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let report = report(&store, &mut context, &matcher_main)?;
+
+        match matcher_access.key() {
+            // List secrets of a single key
+            Some(query) => {
+                let access = select(&report, query)?;
+                for name in &access.secrets {
+                    println!("{name}");
+                }
+                if access.secrets.is_empty() && !matcher_main.quiet() {
+                    eprintln!("Key cannot read any secret");
+                }
+            }
+
+            // List secrets of all keys
+            None => {
+                for access in &report {
+                    if matcher_main.quiet() {
+                        for name in &access.secrets {
+                            println!("{}\t{name}", access.id);
+                        }
+                        continue;
+                    }
+
+                    let mut status = vec![format!("{} secrets", access.secrets.len())];
+                    if !access.recipient {
+                        status.push("not a recipient".into());
+                    }
+                    match &access.key {
+                        Some(key) => println!("{key} ({})", status.join(", ")),
+                        None => println!("{} (unknown key, {})", access.id, status.join(", ")),
+                    }
+                    for name in &access.secrets {
+                        println!("    {name}");
+                    }
+                }
+            }
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+        Ok(())
+    }
+}
+
+/// Secrets a single key can read.
+struct KeyAccess {
+    /// Key fingerprint, or key ID if the key is not in the keychain.
+    id: String,
+
+    /// The key, if in the keychain.
+    key: Option<Key>,
+
+    /// Whether the key is a store recipient.
+    recipient: bool,
+
+    /// Names of secrets encrypted for this key.
+    secrets: Vec<String>,
+}
+
+/// Build an access report for all keys secrets in the store are encrypted for.
+///
+/// This reads the key IDs from the ciphertext of all secrets, without decrypting them. Store
+/// recipients that cannot read any secret are included as well.
+fn report(
+    store: &Store,
+    context: &mut Context,
+    matcher_main: &MainMatcher,
+) -> Result<Vec<KeyAccess>> {
+    let mut report: Vec<KeyAccess> = crypto::store::store_load_all_keys(store)
+        .map_err(Err::Load)?
+        .into_iter()
+        .map(|key| KeyAccess {
+            id: key.fingerprint(false),
+            key: Some(key),
+            recipient: true,
+            secrets: vec![],
+        })
+        .collect();
+
+    let mut secrets: Vec<Secret> = store.secret_iter().collect();
+    secrets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Cache report entry for each key ID, secrets share the same few key IDs
+    let mut entries: HashMap<String, usize> = HashMap::new();
+
+    // Progress bar
+    let pb = progress::progress_bar(secrets.len() as u64, matcher_main.quiet());
+
+    for secret in secrets {
+        pb.set_message_trunc(&secret.name);
+
+        let key_ids = context
+            .encrypted_key_ids_file(&secret.path)
+            .map_err(|err| Err::Read(secret.name.clone(), err))?;
+        for key_id in key_ids {
+            let index = match entries.get(&key_id) {
+                Some(index) => *index,
+                None => {
+                    let key = context.find_key_by_id(&key_id).map_err(Err::Load)?;
+                    let id = key
+                        .as_ref()
+                        .map(|key| key.fingerprint(false))
+                        .unwrap_or_else(|| key_id.clone());
+                    let index = match report.iter().position(|access| access.id == id) {
+                        Some(index) => index,
+                        None => {
+                            report.push(KeyAccess {
+                                id,
+                                key,
+                                recipient: false,
+                                secrets: vec![],
+                            });
+                            report.len() - 1
+                        }
+                    };
+                    entries.insert(key_id, index);
+                    index
+                }
+            };
+
+            let access = &mut report[index];
+            if access.secrets.last() != Some(&secret.name) {
+                access.secrets.push(secret.name.clone());
+            }
+        }
+
+        pb.inc(1);
+    }
+
+    pb.finish_and_clear();
+
+    Ok(report)
+}
+
+/// Select the report entry for the key matching the given query.
+///
+/// Keys that are not in the keychain are matched by key ID.
+fn select<'a>(report: &'a [KeyAccess], query: &str) -> Result<&'a KeyAccess> {
+    let unknown: Vec<&KeyAccess> = report
+        .iter()
+        .filter(|access| access.key.is_none())
+        .filter(|access| {
+            access
+                .id
+                .ends_with(&crypto::util::format_fingerprint(query))
+        })
+        .collect();
+    if let [access] = unknown.as_slice() {
+        return Ok(access);
+    }
+
+    let keys: Vec<Key> = report
+        .iter()
+        .filter_map(|access| access.key.clone())
+        .collect();
+    let key = super::select_key(&keys, Some(&query.into()), "Report on")?;
+    Ok(report
+        .iter()
+        .find(|access| access.key.as_ref() == Some(&key))
+        .unwrap())
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to load keys")]
+    Load(#[source] anyhow::Error),
+
+    #[error("failed to read recipients of secret: {0}")]
+    Read(String, #[source] anyhow::Error),
+}
@@ -1,6 +1,11 @@
+use std::time::SystemTime;
+
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{crypto::prelude::*, Recipients, Store};
+use prs_lib::{
+    crypto::{self, prelude::*},
+    Recipients, Store,
+};
 use thiserror::Error;
 
 use crate::cmd::matcher::{
@@ -48,28 +53,63 @@ impl<'a> Add<'a> {
             sync.prepare()?;
         }
 
+        // Parse expiry date, must not be in the past
+        let until = match matcher_add.until() {
+            Some(until) => {
+                let date = crypto::store::parse_expiry_date(until)?;
+                if crypto::store::expiry_time(date) <= SystemTime::now() {
+                    return Err(Err::ExpiryPast(until.into()).into());
+                }
+                Some(date)
+            }
+            None => None,
+        };
+
         let mut context = crate::crypto::context(&matcher_main)?;
-        let mut recipients = store.recipients().map_err(Err::Load)?;
-
-        // Find unused keys, select one and add to recipients
-        let mut tmp = Recipients::from(
-            if !matcher_add.secret() {
-                context.keys_public()
-            } else {
-                context.keys_private()
+        let dir = super::store_dir(&store, matcher_add.path())?;
+
+        let (label, added) = match matcher_add.group() {
+            // Add reference to recipient group
+            Some(name) => {
+                crypto::store::store_add_group_at(&store, &dir, name).map_err(Err::Group)?;
+                let group = crypto::groups::format_group_ref(name);
+                (group.clone(), group)
             }
-            .map_err(Err::Load)?,
-        );
-        tmp.remove_all(recipients.keys());
-        let key = select::select_key(tmp.keys(), None).ok_or(Err::NoneSelected)?;
-        recipients.add(key.clone());
-        recipients.save(&store)?;
+
+            // Find unused keys, select one and add to recipients
+            None => {
+                let mut recipients = store.recipients_for(&dir).map_err(Err::Load)?;
+                let mut tmp = Recipients::from(
+                    if !matcher_add.secret() {
+                        context.keys_public()
+                    } else {
+                        context.keys_private()
+                    }
+                    .map_err(Err::Load)?,
+                );
+                tmp.remove_all(recipients.keys());
+                let key = select::select_key(tmp.keys(), None).ok_or(Err::NoneSelected)?;
+                key.check_usable()
+                    .map_err(|err| Err::Unusable(key.to_string(), err))?;
+                recipients.add(key.clone());
+                recipients.save_at(&store, &dir)?;
+                if until.is_some() {
+                    crypto::store::store_set_expiry_at(
+                        &store,
+                        &dir,
+                        &key.fingerprint(false),
+                        until,
+                    )?;
+                }
+                (key.fingerprint(true), key.to_string())
+            }
+        };
 
         if prs_lib::store::can_decrypt(&store) {
             // Recrypt secrets
             // TODO: do not quit on error, finish sync, ask to revert instead?
             if !matcher_add.no_recrypt() {
-                crate::action::housekeeping::recrypt::recrypt_all(&store, &matcher_main)
+                crate::action::housekeeping::recrypt::recrypt_dir(&store, &dir, &matcher_main)
                     .map_err(Err::Recrypt)?;
             }
         } else if !matcher_main.quiet() {
@@ -77,14 +117,24 @@ impl<'a> Add<'a> {
         }
 
         // Finalize sync
-        sync.finalize(format!("Add recipient {}", key.fingerprint(true)))?;
+        sync.finalize(format!(
+            "Add recipient {}{}{}",
+            label,
+            super::store_dir_suffix(&store, &dir),
+            until
+                .map(|until| format!(" until {until}"))
+                .unwrap_or_default(),
+        ))?;
 
         // Finalize tomb
         #[cfg(all(feature = "tomb", target_os = "linux"))]
         tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
 
         if !matcher_main.quiet() {
-            eprintln!("Added recipient: {key}");
+            eprintln!("Added recipient: {added}");
+            if let Some(name) = matcher_add.group() {
+                super::group::print_compat_warning(name);
+            }
         }
 
         Ok(())
@@ -122,6 +172,15 @@ pub enum Err {
     #[error("no key selected")]
     NoneSelected,
 
+    #[error("recipient expiry date is in the past: {0}")]
+    ExpiryPast(String),
+
+    #[error("failed to add recipient group")]
+    Group(#[source] anyhow::Error),
+
+    #[error("cannot add unusable key as recipient: {0}")]
+    Unusable(String, #[source] anyhow::Error),
+
     #[error("failed to load usable keys from keychain")]
     Load(#[source] anyhow::Error),
 
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{crypto::prelude::*, Recipients, Store};
+use prs_lib::{
+    crypto::{prelude::*, Proto},
+    Recipients, Store,
+};
 use thiserror::Error;
 
 use crate::cmd::matcher::{
@@ -58,8 +61,10 @@ impl<'a> Generate<'a> {
             sync.prepare()?;
         }
 
-        // Generating recipient in no-interact mode is not supported
-        if matcher_main.no_interact() && !matcher_main.force() {
+        let proto = store.proto();
+
+        // Generating GPG recipient in no-interact mode is not supported
+        if proto == Proto::Gpg && matcher_main.no_interact() && !matcher_main.force() {
             error::quit_error_msg(
                 "generating recipient with --no-interact is not supported",
                 ErrorHintsBuilder::from_matcher(&matcher_main)
@@ -70,7 +75,7 @@ impl<'a> Generate<'a> {
         }
 
         // Show warning to user
-        if !matcher_main.force() {
+        if proto == Proto::Gpg && !matcher_main.force() {
             eprintln!("This will start a key pair generation wizard through 'gpg'");
             if !cli::prompt_yes("Continue?", Some(true), &matcher_main) {
                 if matcher_main.verbose() {
@@ -80,8 +85,12 @@ impl<'a> Generate<'a> {
             }
         }
 
-        // Generate new key through GPG
-        let new = gpg_generate(&matcher_main)?;
+        // Generate new key through GPG or age
+        let new = match proto {
+            #[cfg(feature = "backend-age")]
+            Proto::Age => age_generate(&matcher_main)?,
+            _ => gpg_generate(&matcher_main)?,
+        };
         let new_keys = new.keys();
 
         if !matcher_generate.no_add() {
@@ -109,8 +118,12 @@ impl<'a> Generate<'a> {
                 // Recrypt secrets
                 // TODO: do not quit on error, finish sync, ask to revert instead?
                 if !matcher_generate.no_recrypt() {
-                    crate::action::housekeeping::recrypt::recrypt_all(&store, &matcher_main)
-                        .map_err(Err::Recrypt)?;
+                    crate::action::housekeeping::recrypt::recrypt_dir(
+                        &store,
+                        &store.root,
+                        &matcher_main,
+                    )
+                    .map_err(Err::Recrypt)?;
                 };
             } else if !matcher_main.quiet() {
                 super::add::cannot_decrypt_show_recrypt_hints();
@@ -171,6 +184,21 @@ pub fn gpg_generate(matcher_main: &MainMatcher) -> Result<Recipients> {
     Ok(diff)
 }
 
+/// Generate new age identity.
+///
+/// Return new key as recipients.
+#[cfg(feature = "backend-age")]
+pub fn age_generate(matcher_main: &MainMatcher) -> Result<Recipients> {
+    let config = crate::crypto::config(matcher_main);
+    let key = prs_lib::crypto::backend::age::context::context(&config)?.generate()?;
+
+    if !matcher_main.quiet() {
+        eprintln!("Generated new age identity: {}", key.fingerprint(false));
+    }
+
+    Ok(Recipients::from(vec![key]))
+}
+
 #[derive(Debug, Error)]
 pub enum Err {
     #[error("failed to access password store")]
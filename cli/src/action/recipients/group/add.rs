@@ -0,0 +1,133 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, groups::Groups, prelude::*},
+    Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::group::{add::AddMatcher, GroupMatcher},
+    MainMatcher, Matcher,
+};
+use crate::util::sync;
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+
+/// A recipients group add action.
+pub struct Add<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Add<'a> {
+    /// Construct a new group add action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the group add action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_group = GroupMatcher::with(self.cmd_matches).unwrap();
+        let matcher_add = AddMatcher::with(self.cmd_matches).unwrap();
+
+        let jobs = matcher_add.jobs()?;
+        let name = matcher_add.group();
+        super::validate_name(name)?;
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_add.allow_dirty());
+        if !matcher_add.no_sync() {
+            sync.prepare()?;
+        }
+
+        let mut groups = Groups::load(&store).map_err(Err::Load)?;
+
+        // Select key that is not a member yet
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let mut keys = context.keys_public().map_err(Err::Load)?;
+        let members = groups.members(name).unwrap_or_default();
+        keys.retain(|key| {
+            !members
+                .iter()
+                .any(|fp| crypto::util::fingerprints_equal(fp, key.fingerprint(false)))
+        });
+        let key = crate::action::recipients::select_key(&keys, matcher_add.key(), "Add to group")?;
+        key.check_usable()
+            .map_err(|err| Err::Unusable(key.to_string(), err))?;
+
+        groups.add_member(name, &key.fingerprint(false));
+        crypto::store::store_save_groups(&store, &groups).map_err(Err::Save)?;
+
+        // Recrypt secrets in directories using the group
+        let dirs = crypto::store::store_group_dirs(&store, name).map_err(Err::Load)?;
+        if !dirs.is_empty() && !matcher_add.no_recrypt() {
+            if prs_lib::store::can_decrypt(&store) {
+                crate::action::housekeeping::recrypt::recrypt_dirs(
+                    &store,
+                    &dirs,
+                    jobs,
+                    &matcher_main,
+                )
+                .map_err(Err::Recrypt)?;
+            } else if !matcher_main.quiet() {
+                crate::action::recipients::add::cannot_decrypt_show_recrypt_hints();
+            }
+        }
+
+        // Finalize sync
+        if !matcher_add.no_sync() {
+            sync.finalize(format!(
+                "Add {} to recipient group {name}",
+                key.fingerprint(true),
+            ))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Added to group {name}: {key}");
+            super::print_compat_warning(name);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to load recipient groups")]
+    Load(#[source] anyhow::Error),
+
+    #[error("cannot add unusable key to group: {0}")]
+    Unusable(String, #[source] anyhow::Error),
+
+    #[error("failed to save recipient groups")]
+    Save(#[source] anyhow::Error),
+
+    #[error("failed to re-encrypt secrets in store")]
+    Recrypt(#[source] anyhow::Error),
+}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, groups::Groups, prelude::*},
+    Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::group::{list::ListMatcher, GroupMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+
+/// A recipients group list action.
+pub struct List<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> List<'a> {
+    /// Construct a new group list action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the group list action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_group = GroupMatcher::with(self.cmd_matches).unwrap();
+        let matcher_list = ListMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        let groups = Groups::load(&store).map_err(Err::Load)?;
+
+        match matcher_list.group() {
+            // List members of group
+            Some(name) => {
+                let members = groups
+                    .members(name)
+                    .ok_or_else(|| Err::UnknownGroup(name.into()))?;
+                let mut context = crate::crypto::context(&matcher_main)?;
+                for fp in members {
+                    let key = context.find_public_keys(&[fp]).map_err(Err::Load)?;
+                    match key.first() {
+                        Some(key) if !matcher_main.quiet() => println!("{key}"),
+                        None if !matcher_main.quiet() => println!("{fp} (not in keychain)"),
+                        _ => println!("{fp}"),
+                    }
+                }
+            }
+
+            // List groups, and the directories using them
+            None => {
+                for name in groups.names() {
+                    if matcher_main.quiet() {
+                        println!("{name}");
+                        continue;
+                    }
+
+                    let members = groups.members(name).unwrap_or_default().len();
+                    let dirs: Vec<String> = crypto::store::store_group_dirs(&store, name)
+                        .map_err(Err::Load)?
+                        .iter()
+                        .map(|dir| match dir.strip_prefix(&store.root) {
+                            Ok(rel) if !rel.as_os_str().is_empty() => rel.display().to_string(),
+                            _ => ".".into(),
+                        })
+                        .collect();
+                    if dirs.is_empty() {
+                        println!("{name} ({members} members, unused)");
+                    } else {
+                        println!("{name} ({members} members, used in: {})", dirs.join(", "));
+                    }
+                }
+            }
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to load recipient groups")]
+    Load(#[source] anyhow::Error),
+
+    #[error("unknown recipient group: {0}")]
+    UnknownGroup(String),
+}
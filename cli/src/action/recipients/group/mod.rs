@@ -0,0 +1,71 @@
+pub mod add;
+pub mod list;
+pub mod remove;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use thiserror::Error;
+
+use crate::cmd::matcher::{recipients::group::GroupMatcher, Matcher};
+use crate::util::error;
+
+/// A recipients group action.
+pub struct Group<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Group<'a> {
+    /// Construct a new group action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the group action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matcher
+        let matcher_group = GroupMatcher::with(self.cmd_matches).unwrap();
+
+        if matcher_group.cmd_add().is_some() {
+            return add::Add::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_group.cmd_list().is_some() {
+            return list::List::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_group.cmd_remove().is_some() {
+            return remove::Remove::new(self.cmd_matches).invoke();
+        }
+
+        // Unreachable, clap will print help for missing sub command instead
+        unreachable!()
+    }
+}
+
+/// Validate a group name.
+///
+/// Names may only contain alphanumeric characters, `-`, `_` and `.`.
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(Err::InvalidName(name.into()).into());
+    }
+    Ok(())
+}
+
+/// Warn that GPG IDs files referencing the given group are not compatible with pass and gopass.
+pub(crate) fn print_compat_warning(name: &str) {
+    error::print_warning(format!(
+        "'.gpg-id' files referencing '{}' are not compatible with pass and gopass",
+        prs_lib::crypto::groups::format_group_ref(name),
+    ));
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("invalid group name '{0}', use alphanumeric characters, '-', '_' and '.' only")]
+    InvalidName(String),
+}
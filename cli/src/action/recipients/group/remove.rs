@@ -0,0 +1,189 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, groups::Groups, prelude::*},
+    Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::group::{remove::RemoveMatcher, GroupMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{cli, error, sync};
+
+/// A recipients group remove action.
+pub struct Remove<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Remove<'a> {
+    /// Construct a new group remove action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the group remove action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_group = GroupMatcher::with(self.cmd_matches).unwrap();
+        let matcher_remove = RemoveMatcher::with(self.cmd_matches).unwrap();
+
+        let jobs = matcher_remove.jobs()?;
+        let name = matcher_remove.group();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_remove.allow_dirty());
+        if !matcher_remove.no_sync() {
+            sync.prepare()?;
+        }
+
+        let mut groups = Groups::load(&store).map_err(Err::Load)?;
+        let members = groups
+            .members(name)
+            .ok_or_else(|| Err::UnknownGroup(name.into()))?
+            .to_vec();
+        let dirs = crypto::store::store_group_dirs(&store, name).map_err(Err::Load)?;
+
+        // Remove the whole group if no key is given, only if it is unused
+        let query = match matcher_remove.key() {
+            Some(query) => query,
+            None => {
+                if !dirs.is_empty() {
+                    return Err(Err::GroupInUse(name.into(), dirs.len()).into());
+                }
+                confirm(&format!("Remove group '{name}'?"), &matcher_main);
+
+                groups.remove(name);
+                crypto::store::store_save_groups(&store, &groups).map_err(Err::Save)?;
+
+                // Finalize sync
+                if !matcher_remove.no_sync() {
+                    sync.finalize(format!("Remove recipient group {name}"))?;
+                }
+
+                // Finalize tomb
+                #[cfg(all(feature = "tomb", target_os = "linux"))]
+                tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+                if !matcher_main.quiet() {
+                    eprintln!("Removed group: {name}");
+                }
+                return Ok(());
+            }
+        };
+
+        // Select member to remove
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let members: Vec<&str> = members.iter().map(|fp| fp.as_str()).collect();
+        let keys = context.find_public_keys(&members).map_err(Err::Load)?;
+        let key = crate::action::recipients::select_key(&keys, Some(query), "Remove from group")?;
+
+        // Do not allow removing last member of a group in use unless forcing
+        if members.len() == 1 && !dirs.is_empty() && !matcher_main.force() {
+            error::print_error_msg(
+                "cannot remove last member of group that is in use, directories using it would have no recipients",
+            );
+            error::ErrorHintsBuilder::from_matcher(&matcher_main)
+                .force(true)
+                .verbose(false)
+                .build()
+                .unwrap()
+                .print(false);
+            error::quit();
+        }
+
+        eprintln!("{key}");
+        confirm(
+            &format!("Remove '{}' from group '{name}'?", key.fingerprint(true)),
+            &matcher_main,
+        );
+
+        groups.remove_member(name, &key.fingerprint(false));
+        crypto::store::store_save_groups(&store, &groups).map_err(Err::Save)?;
+
+        // Recrypt secrets in directories using the group
+        if !dirs.is_empty() && !matcher_remove.no_recrypt() {
+            if prs_lib::store::can_decrypt(&store) {
+                crate::action::housekeeping::recrypt::recrypt_dirs(
+                    &store,
+                    &dirs,
+                    jobs,
+                    &matcher_main,
+                )
+                .map_err(Err::Recrypt)?;
+            } else if !matcher_main.quiet() {
+                crate::action::recipients::add::cannot_decrypt_show_recrypt_hints();
+            }
+        }
+
+        // Finalize sync
+        if !matcher_remove.no_sync() {
+            sync.finalize(format!(
+                "Remove {} from recipient group {name}",
+                key.fingerprint(true),
+            ))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Removed from group {name}: {key}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Ask to confirm removal unless forcing, quit if not confirmed.
+fn confirm(msg: &str, matcher_main: &MainMatcher) {
+    if !matcher_main.force() && !cli::prompt_yes(msg, Some(true), matcher_main) {
+        if matcher_main.verbose() {
+            eprintln!("Removal cancelled");
+        }
+        error::quit();
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to load recipient groups")]
+    Load(#[source] anyhow::Error),
+
+    #[error("unknown recipient group: {0}")]
+    UnknownGroup(String),
+
+    #[error("cannot remove group '{0}', it is used in {1} store directories")]
+    GroupInUse(String, usize),
+
+    #[error("failed to save recipient groups")]
+    Save(#[source] anyhow::Error),
+
+    #[error("failed to re-encrypt secrets in store")]
+    Recrypt(#[source] anyhow::Error),
+}
@@ -0,0 +1,229 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, prelude::*, Context},
+    Key, Store,
+};
+use thiserror::Error;
+use walkdir::WalkDir;
+
+use crate::cmd::matcher::{
+    recipients::{import::ImportMatcher, RecipientsMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{cli, error, sync};
+
+/// A recipients import action.
+pub struct Import<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Import<'a> {
+    /// Construct a new import action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the import action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
+        let matcher_import = ImportMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        let source = matcher_import.source();
+        let files = key_files(&source)?;
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_import.allow_dirty());
+        if !matcher_import.no_sync() {
+            sync.prepare()?;
+        }
+
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let dir = super::store_dir(&store, matcher_import.path())?;
+        let mut recipients = store.recipients_for(&dir).map_err(Err::Load)?;
+
+        // Read keys from all files, let user confirm each new key
+        let mut added: Vec<Key> = vec![];
+        for file in &files {
+            let blocks = match read_key_blocks(&mut context, file) {
+                Ok(blocks) => blocks,
+                // Single file must contain keys, in directories skip unrelated files
+                Err(err) if !source.is_dir() => {
+                    return Err(Err::Read(file.clone(), err).into());
+                }
+                Err(_) => {
+                    if matcher_main.verbose() {
+                        eprintln!("Skipping file without public keys: {}", file.display());
+                    }
+                    continue;
+                }
+            };
+
+            for (key, data) in blocks {
+                let fingerprint = key.fingerprint(false);
+                if recipients.has_fingerprint(&fingerprint)
+                    || added.iter().any(|k| k.fingerprint(false) == fingerprint)
+                {
+                    if !matcher_main.quiet() {
+                        eprintln!("Already a recipient: {key}");
+                    }
+                    continue;
+                }
+                if let Err(err) = key.check_usable() {
+                    error::print_warning(format!("skipping unusable key {key}: {err}"));
+                    continue;
+                }
+
+                eprintln!("Fingerprint: {fingerprint}");
+                eprintln!("User IDs:    {}", key.display());
+                if !matcher_main.force()
+                    && !cli::prompt_yes("Add key as recipient?", Some(true), &matcher_main)
+                {
+                    continue;
+                }
+
+                // Import only this key into keychain, required to encrypt for it
+                context
+                    .import_key(&data)
+                    .map_err(|err| Err::Import(file.clone(), err))?;
+                added.push(key);
+            }
+        }
+
+        if added.is_empty() {
+            if !matcher_main.quiet() {
+                eprintln!("No recipients imported");
+            }
+            return Ok(());
+        }
+
+        // Add keys as loaded from keychain, to get their validity after import
+        for key in &added {
+            let key = context
+                .get_public_key(&key.fingerprint(false))
+                .map_err(Err::Load)?;
+            recipients.add(key);
+        }
+        recipients.save_at(&store, &dir)?;
+
+        if prs_lib::store::can_decrypt(&store) {
+            // Recrypt secrets
+            if !matcher_import.no_recrypt() {
+                crate::action::housekeeping::recrypt::recrypt_dir(&store, &dir, &matcher_main)
+                    .map_err(Err::Recrypt)?;
+            }
+        } else if !matcher_main.quiet() {
+            super::add::cannot_decrypt_show_recrypt_hints();
+        }
+
+        // Finalize sync
+        sync.finalize(format!(
+            "Import recipient{} {}{}",
+            if added.len() == 1 { "" } else { "s" },
+            added
+                .iter()
+                .map(|key| key.fingerprint(true))
+                .collect::<Vec<_>>()
+                .join(", "),
+            super::store_dir_suffix(&store, &dir),
+        ))?;
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            for key in &added {
+                eprintln!("Added recipient: {key}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the keys from the given key file, along with the key data to import each of them.
+///
+/// Key data holding more than one key is skipped, so declined keys are never imported.
+fn read_key_blocks(context: &mut Context, file: &Path) -> Result<Vec<(Key, Vec<u8>)>> {
+    let mut keys = vec![];
+    for data in crypto::read_key_file_split(file)? {
+        match context.read_keys(&data)?.as_slice() {
+            [key] => keys.push((key.clone(), data)),
+            [] => {}
+            many => error::print_warning(format!(
+                "skipping {} keys that can't be imported separately from {}",
+                many.len(),
+                file.display(),
+            )),
+        }
+    }
+    Ok(keys)
+}
+
+/// Collect key files to import from the given path.
+///
+/// A file is used as-is. A directory is searched recursively, including hidden directories to
+/// support a local `.well-known/openpgpkey/hu/` WKD layout.
+fn key_files(path: &Path) -> Result<Vec<PathBuf>, Err> {
+    if !path.is_dir() {
+        if !path.is_file() {
+            return Err(Err::NotFound(path.to_path_buf()));
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files: Vec<PathBuf> = WalkDir::new(path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("key file or directory does not exist: {}", .0.display())]
+    NotFound(PathBuf),
+
+    #[error("failed to read public keys from file: {}", .0.display())]
+    Read(PathBuf, #[source] anyhow::Error),
+
+    #[error("failed to import public keys from file: {}", .0.display())]
+    Import(PathBuf, #[source] anyhow::Error),
+
+    #[error("failed to load usable keys from keychain")]
+    Load(#[source] anyhow::Error),
+
+    #[error("failed to re-encrypt secrets in store")]
+    Recrypt(#[source] anyhow::Error),
+}
@@ -1,9 +1,18 @@
+use std::time::SystemTime;
+
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::Store;
+use prs_lib::{
+    crypto::{self, store::Expiry},
+    Key, Store,
+};
 use thiserror::Error;
 
-use crate::cmd::matcher::{recipients::RecipientsMatcher, MainMatcher, Matcher};
+use crate::cmd::matcher::{
+    recipients::{list::ListMatcher, RecipientsMatcher},
+    MainMatcher, Matcher,
+};
+use crate::util::time;
 #[cfg(all(feature = "tomb", target_os = "linux"))]
 use crate::util::tomb;
 
@@ -23,6 +32,7 @@ impl<'a> List<'a> {
         // Create the command matchers
         let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
         let _matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
+        let matcher_list = ListMatcher::with(self.cmd_matches).unwrap();
 
         let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
         #[cfg(all(feature = "tomb", target_os = "linux"))]
@@ -31,7 +41,9 @@ impl<'a> List<'a> {
             matcher_main.verbose(),
             matcher_main.force(),
         );
-        let recipients = store.recipients().map_err(Err::List)?;
+        let dir = super::store_dir(&store, matcher_list.path())?;
+        let recipients = store.recipients_for(&dir).map_err(Err::List)?;
+        let expiry = crypto::store::store_read_expiry_for(&store, &dir).map_err(Err::List)?;
 
         // Prepare tomb
         #[cfg(all(feature = "tomb", target_os = "linux"))]
@@ -42,7 +54,10 @@ impl<'a> List<'a> {
             .iter()
             .map(|key| {
                 if !matcher_main.quiet() {
-                    key.to_string()
+                    let expiry = expiry.iter().find(|e| {
+                        crypto::util::fingerprints_equal(&e.fingerprint, key.fingerprint(false))
+                    });
+                    display_key(key, expiry)
                 } else {
                     key.fingerprint(false)
                 }
@@ -57,6 +72,38 @@ impl<'a> List<'a> {
     }
 }
 
+/// Format key for display, along with its validity, capabilities and expiry if known.
+///
+/// Includes the remaining access time if the recipient has an expiry date in the store.
+fn display_key(key: &Key, expiry: Option<&Expiry>) -> String {
+    let mut status: Vec<String> = key.display_status().into_iter().collect();
+    if let Some(expires) = key.expiry_time().filter(|_| key.check_usable().is_ok()) {
+        if let Ok(remaining) = expires.duration_since(SystemTime::now()) {
+            status.push(format!(
+                "expires in {}",
+                time::format_duration(remaining.as_secs() as u32)
+            ));
+        }
+    }
+
+    if let Some(expiry) = expiry {
+        status.push(match expiry.remaining() {
+            Some(remaining) => format!(
+                "access until {}, {} left",
+                expiry.until,
+                time::format_duration(remaining.as_secs() as u32)
+            ),
+            None => format!("access expired on {}", expiry.until),
+        });
+    }
+
+    if status.is_empty() {
+        key.to_string()
+    } else {
+        format!("{key} ({})", status.join(", "))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Err {
     #[error("failed to access password store")]
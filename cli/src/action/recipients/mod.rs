@@ -1,13 +1,24 @@
+pub mod access;
 pub mod add;
 pub mod export;
 pub mod generate;
+pub mod group;
+pub mod import;
 pub mod list;
 pub mod remove;
+pub mod replace;
+pub mod request;
+pub mod requests;
+
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::Result;
 use clap::ArgMatches;
+use prs_lib::{crypto, Key, Store};
+use thiserror::Error;
 
 use crate::cmd::matcher::{Matcher, RecipientsMatcher};
+use crate::util::select;
 
 /// A file recipients action.
 pub struct Recipients<'a> {
@@ -25,6 +36,10 @@ impl<'a> Recipients<'a> {
         // Create the command matcher
         let matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
 
+        if matcher_recipients.cmd_access().is_some() {
+            return access::Access::new(self.cmd_matches).invoke();
+        }
+
         if matcher_recipients.cmd_add().is_some() {
             return add::Add::new(self.cmd_matches).invoke();
         }
@@ -37,6 +52,14 @@ impl<'a> Recipients<'a> {
             return generate::Generate::new(self.cmd_matches).invoke();
         }
 
+        if matcher_recipients.cmd_group().is_some() {
+            return group::Group::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_recipients.cmd_import().is_some() {
+            return import::Import::new(self.cmd_matches).invoke();
+        }
+
         if matcher_recipients.cmd_list().is_some() {
             return list::List::new(self.cmd_matches).invoke();
         }
@@ -45,7 +68,105 @@ impl<'a> Recipients<'a> {
             return remove::Remove::new(self.cmd_matches).invoke();
         }
 
+        if matcher_recipients.cmd_replace().is_some() {
+            return replace::Replace::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_recipients.cmd_request().is_some() {
+            return request::Request::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_recipients.cmd_requests().is_some() {
+            return requests::Requests::new(self.cmd_matches).invoke();
+        }
+
         // Unreachable, clap will print help for missing sub command instead
         unreachable!()
     }
 }
+
+/// Select the store directory to manage recipients for.
+///
+/// Returns the store root if no path is given. The path must be within the store.
+pub(crate) fn store_dir(store: &Store, path: Option<&String>) -> Result<PathBuf> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(store.root.clone()),
+    };
+
+    // Expand path, strip store prefix
+    let path = shellexpand::full(path).map_err(Err::ExpandPath)?;
+    let mut path = Path::new(path.as_ref());
+    if let Ok(stripped) = path.strip_prefix(&store.root) {
+        path = stripped;
+    }
+
+    // Path must be relative, and may not escape store
+    if !path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(Err::OutsideStore(path.into()).into());
+    }
+
+    Ok(store.root.join(path))
+}
+
+/// Get display name for store directory in sync commit messages.
+///
+/// Returns an empty string for the store root.
+pub(crate) fn store_dir_suffix(store: &Store, dir: &Path) -> String {
+    match dir.strip_prefix(&store.root) {
+        Ok(rel) if !rel.as_os_str().is_empty() => format!(" in {}", rel.display()),
+        _ => "".into(),
+    }
+}
+
+/// Select a key from the given list.
+///
+/// The query matches the end of the fingerprint, or any part of the user ID. Asks to select
+/// interactively if there's no query, or if the query matches multiple keys.
+pub(crate) fn select_key(keys: &[Key], query: Option<&String>, prompt: &str) -> Result<Key> {
+    let query = match query {
+        Some(query) => query,
+        None => {
+            return select::select_key(keys, Some(prompt))
+                .cloned()
+                .ok_or_else(|| Err::NoneSelected.into())
+        }
+    };
+
+    let fingerprint = crypto::util::format_fingerprint(query.trim_start_matches("0x"));
+    let needle = query.to_lowercase();
+    let matches: Vec<Key> = keys
+        .iter()
+        .filter(|key| {
+            key.fingerprint(false).ends_with(&fingerprint)
+                || key.to_string().to_lowercase().contains(&needle)
+        })
+        .cloned()
+        .collect();
+
+    match matches.len() {
+        0 => Err(Err::NoMatch(query.into()).into()),
+        1 => Ok(matches[0].clone()),
+        _ => select::select_key(&matches, Some(prompt))
+            .cloned()
+            .ok_or_else(|| Err::NoneSelected.into()),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to expand store directory path")]
+    ExpandPath(#[source] shellexpand::LookupError<std::env::VarError>),
+
+    #[error("store directory is not within password store: {}", _0.display())]
+    OutsideStore(PathBuf),
+
+    #[error("no key selected")]
+    NoneSelected,
+
+    #[error("no key found matching '{0}'")]
+    NoMatch(String),
+}
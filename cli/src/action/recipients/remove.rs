@@ -48,7 +48,8 @@ impl<'a> Remove<'a> {
             sync.prepare()?;
         }
 
-        let mut recipients = store.recipients().map_err(Err::Load)?;
+        let dir = super::store_dir(&store, matcher_remove.path())?;
+        let mut recipients = store.recipients_for(&dir).map_err(Err::Load)?;
 
         // Select key to remove
         let key = select::select_key(recipients.keys(), None)
@@ -85,17 +86,21 @@ impl<'a> Remove<'a> {
         }
 
         recipients.remove(&key);
-        recipients.save(&store)?;
+        recipients.save_at(&store, &dir)?;
 
         // Recrypt secrets
         if matcher_remove.recrypt() {
-            crate::action::housekeeping::recrypt::recrypt_all(&store, &matcher_main)
+            crate::action::housekeeping::recrypt::recrypt_dir(&store, &dir, &matcher_main)
                 .map_err(Err::Recrypt)?;
         }
 
         // Finalize sync
         if !matcher_remove.no_sync() {
-            sync.finalize(format!("Remove recipient {}", key.fingerprint(true)))?;
+            sync.finalize(format!(
+                "Remove recipient {}{}",
+                key.fingerprint(true),
+                super::store_dir_suffix(&store, &dir),
+            ))?;
         }
 
         // Finalize tomb
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, prelude::*, Context, Proto},
+    Key, Secret, Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::{replace::ReplaceMatcher, RecipientsMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{
+    cli, error,
+    progress::{self, ProgressBarExt},
+    sync,
+};
+
+/// A recipients replace action.
+pub struct Replace<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Replace<'a> {
+    /// Construct a new replace action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the replace action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
+        let matcher_replace = ReplaceMatcher::with(self.cmd_matches).unwrap();
+
+        let jobs = matcher_replace.jobs()?;
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_replace.allow_dirty());
+        if !matcher_replace.no_sync() {
+            sync.prepare()?;
+        }
+
+        // Select recipient to replace, and the key to replace it with
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let all_keys = crypto::store::store_load_all_keys(&store).map_err(Err::Load)?;
+        let old = super::select_key(&all_keys, matcher_replace.query_old(), "Replace")?;
+        let mut candidates = context.keys_public().map_err(Err::Load)?;
+        candidates.retain(|key| key != &old);
+        let new = super::select_key(&candidates, matcher_replace.query_new(), "Replace with")?;
+        new.check_usable()
+            .map_err(|err| Err::Unusable(new.to_string(), err))?;
+
+        // Find recipient files listing the old key, and secrets encrypted for it
+        let dirs = recipient_dirs(&store, &old)?;
+        let secrets = encrypted_for(&store, &mut context, &dirs, &old, &matcher_main)?;
+
+        // Confirm replacement
+        if !matcher_main.force() {
+            eprintln!("Old: {old}");
+            eprintln!("New: {new}");
+            eprintln!(
+                "Updates {} recipient file(s), re-encrypts {} secret(s)",
+                dirs.len(),
+                if matcher_replace.no_recrypt() {
+                    0
+                } else {
+                    secrets.len()
+                },
+            );
+            if !cli::prompt_yes(
+                &format!(
+                    "Replace '{}' with '{}'?",
+                    old.fingerprint(true),
+                    new.fingerprint(true),
+                ),
+                Some(true),
+                &matcher_main,
+            ) {
+                if matcher_main.verbose() {
+                    eprintln!("Replacement cancelled");
+                }
+                error::quit();
+            }
+        }
+
+        // Replace key in all recipient files, this also syncs public key files
+        for dir in &dirs {
+            let mut recipients = store.recipients_for(dir).map_err(Err::Load)?;
+            recipients.remove(&old);
+            if !recipients.has_fingerprint(&new.fingerprint(false)) {
+                recipients.add(new.clone());
+            }
+            recipients.save_at(&store, dir)?;
+        }
+
+        // Recrypt secrets that were encrypted for the old key
+        if !matcher_replace.no_recrypt() && !secrets.is_empty() {
+            if prs_lib::store::can_decrypt(&store) {
+                crate::action::housekeeping::recrypt::recrypt(
+                    &store,
+                    &secrets,
+                    jobs,
+                    false,
+                    &matcher_main,
+                )
+                .map_err(Err::Recrypt)?;
+            } else if !matcher_main.quiet() {
+                super::add::cannot_decrypt_show_recrypt_hints();
+            }
+        }
+
+        // Finalize sync
+        if !matcher_replace.no_sync() {
+            sync.finalize(format!(
+                "Replace recipient {} with {}",
+                old.fingerprint(true),
+                new.fingerprint(true),
+            ))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Replaced recipient: {old}");
+            eprintln!("With recipient: {new}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the directories of all recipient files that list the given key.
+fn recipient_dirs(store: &Store, key: &Key) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+    for file in crypto::store::store_recipients_files(store) {
+        let dir = match file.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => continue,
+        };
+        let recipients = store.recipients_for(&dir).map_err(Err::Load)?;
+        if recipients.has_fingerprint(&key.fingerprint(false)) {
+            dirs.push(dir);
+        }
+    }
+    Ok(dirs)
+}
+
+/// Find secrets encrypted for the given key.
+///
+/// For GPG this reads the key IDs from the ciphertext of all secrets, without decrypting them.
+/// Other protocols don't expose this, all secrets the given recipient directories apply to are
+/// returned instead.
+fn encrypted_for(
+    store: &Store,
+    context: &mut Context,
+    dirs: &[PathBuf],
+    key: &Key,
+    matcher_main: &MainMatcher,
+) -> Result<Vec<Secret>> {
+    let secrets: Vec<Secret> = store.secret_iter().collect();
+
+    if store.proto() != Proto::Gpg {
+        let files: Vec<PathBuf> = dirs
+            .iter()
+            .map(|dir| crypto::store::store_recipients_file_for(store, dir))
+            .collect();
+        return Ok(secrets
+            .into_iter()
+            .filter(|secret| {
+                files.contains(&crypto::store::store_recipients_file_for(
+                    store,
+                    &secret.path,
+                ))
+            })
+            .collect());
+    }
+
+    // Cache whether key IDs belong to the key, secrets share the same few key IDs
+    let mut is_key: HashMap<String, bool> = HashMap::new();
+    let mut found = vec![];
+
+    // Progress bar
+    let pb = progress::progress_bar(secrets.len() as u64, matcher_main.quiet());
+
+    for secret in secrets {
+        pb.set_message_trunc(&secret.name);
+
+        let key_ids = context
+            .encrypted_key_ids_file(&secret.path)
+            .map_err(|err| Err::Inspect(secret.name.clone(), err))?;
+        for key_id in key_ids {
+            let matches = match is_key.get(&key_id) {
+                Some(matches) => *matches,
+                None => {
+                    let matches = context.find_key_by_id(&key_id)?.as_ref() == Some(key);
+                    is_key.insert(key_id, matches);
+                    matches
+                }
+            };
+            if matches {
+                found.push(secret);
+                break;
+            }
+        }
+
+        pb.inc(1);
+    }
+
+    pb.finish_and_clear();
+
+    Ok(found)
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to load existing keys from store")]
+    Load(#[source] anyhow::Error),
+
+    #[error("cannot replace with unusable key: {0}")]
+    Unusable(String, #[source] anyhow::Error),
+
+    #[error("failed to read recipients of secret: {0}")]
+    Inspect(String, #[source] anyhow::Error),
+
+    #[error("failed to re-encrypt secrets in store")]
+    Recrypt(#[source] anyhow::Error),
+}
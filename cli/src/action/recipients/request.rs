@@ -0,0 +1,119 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, prelude::*},
+    Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::{request::RequestMatcher, RecipientsMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{self, style, sync};
+
+/// A recipients request action.
+pub struct Request<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Request<'a> {
+    /// Construct a new request action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the request action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
+        let matcher_request = RequestMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_request.allow_dirty());
+        if !matcher_request.no_sync() {
+            sync.prepare()?;
+        }
+
+        // Select own key to request with
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let keys = context.keys_private().map_err(Err::Load)?;
+        let key = super::select_key(&keys, matcher_request.query(), "Request with")?;
+        key.check_usable()
+            .map_err(|err| Err::Unusable(key.to_string(), err))?;
+
+        // Key must not be a recipient already
+        let recipients = crypto::store::store_load_all_keys(&store).map_err(Err::Load)?;
+        if crypto::util::keys_contain_fingerprint(&recipients, key.fingerprint(false)) {
+            return Err(Err::AlreadyRecipient(key.to_string()).into());
+        }
+
+        let request = crypto::store::store_write_request(&store, &key).map_err(Err::Request)?;
+
+        // Finalize sync
+        if !matcher_request.no_sync() {
+            sync.finalize(format!(
+                "Request to become recipient {}",
+                key.fingerprint(true),
+            ))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Requested to become recipient: {key}");
+            eprintln!();
+            eprintln!("Ask an existing store recipient to approve your request with:");
+            eprintln!(
+                "    {}",
+                style::highlight(format!(
+                    "{} recipients requests --approve {}",
+                    util::bin_name(),
+                    request.fingerprint,
+                ))
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to load keys")]
+    Load(#[source] anyhow::Error),
+
+    #[error("cannot request with unusable key: {0}")]
+    Unusable(String, #[source] anyhow::Error),
+
+    #[error("key is already a store recipient: {0}")]
+    AlreadyRecipient(String),
+
+    #[error("failed to add recipient request to store")]
+    Request(#[source] anyhow::Error),
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    crypto::{self, prelude::*, store::Request},
+    Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    recipients::{requests::RequestsMatcher, RecipientsMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{cli, error, sync};
+
+/// A recipients requests action.
+pub struct Requests<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Requests<'a> {
+    /// Construct a new requests action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the requests action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_recipients = RecipientsMatcher::with(self.cmd_matches).unwrap();
+        let matcher_requests = RequestsMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // List requests if not approving or denying
+        let approving = matcher_requests.approve().is_some();
+        let query = match matcher_requests.approve().or(matcher_requests.deny()) {
+            Some(query) => query,
+            None => {
+                list(&store, &matcher_main)?;
+
+                // Finalize tomb
+                #[cfg(all(feature = "tomb", target_os = "linux"))]
+                tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+                return Ok(());
+            }
+        };
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_requests.allow_dirty());
+        if !matcher_requests.no_sync() {
+            sync.prepare()?;
+        }
+
+        let request = find_request(&store, query)?;
+
+        if approving {
+            let dir = super::store_dir(&store, matcher_requests.path())?;
+            let mut recipients = store.recipients_for(&dir).map_err(Err::Load)?;
+
+            // Import requested key, confirm
+            let key = crypto::store::store_import_request(&store, &request).map_err(Err::Import)?;
+            key.check_usable()
+                .map_err(|err| Err::Unusable(key.to_string(), err))?;
+            if !matcher_main.force() {
+                eprintln!("{key}");
+                if !cli::prompt_yes(
+                    &format!("Approve '{}' as recipient?", key.fingerprint(true)),
+                    Some(true),
+                    &matcher_main,
+                ) {
+                    if matcher_main.verbose() {
+                        eprintln!("Approval cancelled");
+                    }
+                    error::quit();
+                }
+            }
+
+            // Add recipient, remove request
+            if !recipients.has_fingerprint(&key.fingerprint(false)) {
+                recipients.add(key.clone());
+                recipients.save_at(&store, &dir)?;
+            }
+            crypto::store::store_remove_request(&request).map_err(Err::Remove)?;
+
+            if prs_lib::store::can_decrypt(&store) {
+                // Recrypt secrets
+                if !matcher_requests.no_recrypt() {
+                    crate::action::housekeeping::recrypt::recrypt_dir(&store, &dir, &matcher_main)
+                        .map_err(Err::Recrypt)?;
+                }
+            } else if !matcher_main.quiet() {
+                super::add::cannot_decrypt_show_recrypt_hints();
+            }
+
+            // Finalize sync
+            if !matcher_requests.no_sync() {
+                sync.finalize(format!(
+                    "Approve recipient request {}{}",
+                    key.fingerprint(true),
+                    super::store_dir_suffix(&store, &dir),
+                ))?;
+            }
+
+            if !matcher_main.quiet() {
+                eprintln!("Approved recipient: {key}");
+            }
+        } else {
+            // Confirm denial
+            if !matcher_main.force()
+                && !cli::prompt_yes(
+                    &format!("Deny request from '{}'?", request.fingerprint),
+                    Some(true),
+                    &matcher_main,
+                )
+            {
+                if matcher_main.verbose() {
+                    eprintln!("Denial cancelled");
+                }
+                error::quit();
+            }
+
+            crypto::store::store_remove_request(&request).map_err(Err::Remove)?;
+
+            // Finalize sync
+            if !matcher_requests.no_sync() {
+                sync.finalize(format!("Deny recipient request {}", request.fingerprint))?;
+            }
+
+            if !matcher_main.quiet() {
+                eprintln!("Denied recipient request: {}", request.fingerprint);
+            }
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        Ok(())
+    }
+}
+
+/// List pending recipient requests.
+///
+/// Shows full key details for requests with a key that is already in the keychain.
+fn list(store: &Store, matcher_main: &MainMatcher) -> Result<()> {
+    let requests = crypto::store::store_read_requests(store).map_err(Err::Read)?;
+    if requests.is_empty() {
+        if !matcher_main.quiet() {
+            eprintln!("No pending recipient requests");
+        }
+        return Ok(());
+    }
+
+    let mut context = crate::crypto::context(matcher_main)?;
+    for request in requests {
+        if matcher_main.quiet() {
+            println!("{}", request.fingerprint);
+            continue;
+        }
+        match context
+            .find_public_keys(&[&request.fingerprint])
+            .map_err(Err::Load)?
+            .first()
+        {
+            Some(key) => println!("{key}"),
+            None => println!("{} (not in keychain)", request.fingerprint),
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the pending request matching the given fingerprint.
+///
+/// The query matches the end of the fingerprint, so a long or short key ID may be used.
+fn find_request(store: &Store, query: &str) -> Result<Request> {
+    let fingerprint = crypto::util::format_fingerprint(query.trim_start_matches("0x"));
+    let mut requests: Vec<Request> = crypto::store::store_read_requests(store)
+        .map_err(Err::Read)?
+        .into_iter()
+        .filter(|request| request.fingerprint.ends_with(&fingerprint))
+        .collect();
+
+    match requests.len() {
+        0 => Err(Err::NoRequest(query.into()).into()),
+        1 => Ok(requests.remove(0)),
+        _ => Err(Err::AmbiguousRequest(query.into()).into()),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to read recipient requests")]
+    Read(#[source] anyhow::Error),
+
+    #[error("no pending recipient request for '{0}'")]
+    NoRequest(String),
+
+    #[error("multiple pending recipient requests match '{0}', use full fingerprint")]
+    AmbiguousRequest(String),
+
+    #[error("failed to load existing keys from store")]
+    Load(#[source] anyhow::Error),
+
+    #[error("failed to import key from recipient request")]
+    Import(#[source] anyhow::Error),
+
+    #[error("cannot approve unusable key: {0}")]
+    Unusable(String, #[source] anyhow::Error),
+
+    #[error("failed to remove recipient request")]
+    Remove(#[source] anyhow::Error),
+
+    #[error("failed to re-encrypt secrets in store")]
+    Recrypt(#[source] anyhow::Error),
+}
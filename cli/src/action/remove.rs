@@ -71,14 +71,14 @@ impl<'a> Remove<'a> {
         tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
 
         if !matcher_main.quiet() {
-            eprintln!("Secret removed");
+            eprintln!("Secret moved to trash");
         }
 
         Ok(())
     }
 }
 
-/// Confirm to remove the given secret, then remove.
+/// Confirm to remove the given secret, then move it to the trash.
 ///
 /// This also asks to remove an alias target, and aliases targeting this secret, effectively asking
 /// to remove all linked aliases.
@@ -131,10 +131,12 @@ fn remove_confirm(
         }
     }
 
-    // Remove secret, remove directories that become empty
-    fs::remove_file(&secret.path)
-        .map(|_| ())
-        .map_err(Err::Remove)?;
+    // Move secret to trash, aliases are removed directly, remove directories that become empty
+    if is_alias {
+        fs::remove_file(&secret.path).map_err(Err::Remove)?;
+    } else {
+        store.trash().add(secret).map_err(Err::Trash)?;
+    }
     remove_empty_secret_dir(secret);
 
     Ok(true)
@@ -241,4 +243,7 @@ pub enum Err {
 
     #[error("failed to remove secret file")]
     Remove(#[source] std::io::Error),
+
+    #[error("failed to move secret to trash")]
+    Trash(#[source] anyhow::Error),
 }
@@ -0,0 +1,124 @@
+use std::fs;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{crypto::prelude::*, Secret, Store};
+use thiserror::Error;
+
+use crate::cmd::matcher::{restore::RestoreMatcher, MainMatcher, Matcher};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{secret, sync};
+
+/// Restore secret from history action.
+pub struct Restore<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Restore<'a> {
+    /// Construct a new restore action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the restore action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_restore = RestoreMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        if !sync.is_init() {
+            return Err(Err::NoSync.into());
+        }
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_restore.allow_dirty());
+        if !matcher_restore.no_sync() {
+            sync.prepare()?;
+        }
+
+        let name = secret::select_name_with_history(&store, matcher_restore.query())
+            .ok_or(Err::NoneSelected)?;
+        let rev = sync
+            .resolve_revision(matcher_restore.rev())
+            .map_err(Err::Read)?;
+        let (path, ciphertext) = sync.secret_at(&name, &rev).map_err(Err::Read)?;
+
+        // Restore over existing secret, or at its path in history if removed
+        let path = store
+            .find_at(&name)
+            .map(|secret| secret.path)
+            .unwrap_or(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Err::Write)?;
+        }
+
+        // Verify signature of revision, re-encrypt for current recipients
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let plaintext = crate::crypto::decrypt_secret_ciphertext(
+            &mut context,
+            &store,
+            &Secret::from(&store, path.clone()),
+            ciphertext,
+            &matcher_main,
+        )
+        .map_err(Err::Read)?;
+        let recipients = store.recipients_for(&path)?;
+        context
+            .encrypt_file(&recipients, plaintext, &path)
+            .map_err(Err::Encrypt)?;
+
+        // Finalize sync
+        if !matcher_restore.no_sync() {
+            sync.finalize(format!("Restore secret {name} to revision {}", &rev[..7]))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Secret restored");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("store does not keep history, sync is not initialized")]
+    NoSync,
+
+    #[error("no secret selected")]
+    NoneSelected,
+
+    #[error("failed to read secret from history")]
+    Read(#[source] anyhow::Error),
+
+    #[error("failed to write restored secret")]
+    Write(#[source] std::io::Error),
+
+    #[error("failed to encrypt restored secret")]
+    Encrypt(#[source] anyhow::Error),
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{crypto::prelude::*, Plaintext, SecretDocument, Store};
+use thiserror::Error;
+
+use crate::cmd::matcher::{set::SetMatcher, MainMatcher, Matcher};
+#[cfg(feature = "clipboard")]
+use crate::util::clipboard;
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{pass, secret, select, stdin, sync};
+
+/// Set secret field action.
+pub struct Set<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Set<'a> {
+    /// Construct a new set action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the set action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_set = SetMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_set.allow_dirty());
+        if !matcher_set.no_sync() {
+            sync.prepare()?;
+        }
+
+        let secret =
+            select::store_select_secret(&store, matcher_set.query()).ok_or(Err::NoneSelected)?;
+
+        secret::print_name(matcher_set.query(), &secret, &store, matcher_main.quiet());
+
+        // Get value to set
+        let field = matcher_set.field();
+        let value = if matcher_set.stdin() {
+            let value = stdin::read_plaintext(!matcher_main.quiet())?;
+            let value = value
+                .unsecure_to_str()
+                .map_err(|err| Err::Value(err.into()))?;
+            Plaintext::from(value.trim_end_matches(['\r', '\n']))
+        } else if matcher_set.generate() {
+            if matcher_set.passphrase() {
+                pass::generate_passphrase(matcher_set.length())
+            } else {
+                pass::generate_password(matcher_set.length())
+            }
+        } else {
+            matcher_set.value().unwrap().as_str().into()
+        };
+        let generated = if matcher_set.generate() {
+            Some(value.clone())
+        } else {
+            None
+        };
+
+        // Change field in secret
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let plaintext = crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+            .map_err(Err::Read)?;
+        let mut document = SecretDocument::parse(&plaintext).map_err(Err::Parse)?;
+        document.set(field, value).map_err(Err::Value)?;
+
+        // Encrypt and write changed plaintext
+        let recipients = store.recipients_for(&secret.path)?;
+        context
+            .encrypt_file(&recipients, document.to_plaintext(), &secret.path)
+            .map_err(Err::Write)?;
+
+        // Finalize sync
+        if !matcher_set.no_sync() {
+            sync.finalize(format!("Set field {} in secret {}", field, secret.name))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        // Copy or show generated value
+        if let Some(generated) = generated {
+            #[cfg(feature = "clipboard")]
+            if matcher_set.copy() {
+                clipboard::copy_plaintext(
+                    generated.clone(),
+                    false,
+                    !matcher_main.force(),
+                    matcher_main.quiet(),
+                    matcher_main.verbose(),
+                    matcher_set.timeout()?,
+                )?;
+            }
+
+            if matcher_set.show() {
+                secret::print(generated).map_err(Err::Print)?;
+            }
+        }
+
+        if !matcher_main.quiet() {
+            eprintln!("Field set");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("no secret selected")]
+    NoneSelected,
+
+    #[error("failed to read secret")]
+    Read(#[source] anyhow::Error),
+
+    #[error("failed to parse secret")]
+    Parse(#[source] anyhow::Error),
+
+    #[error("failed to set field value")]
+    Value(#[source] anyhow::Error),
+
+    #[error("failed to write changed secret")]
+    Write(#[source] anyhow::Error),
+
+    #[error("failed to print generated value to stdout")]
+    Print(#[source] std::io::Error),
+}
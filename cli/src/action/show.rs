@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use anyhow::Result;
 use clap::ArgMatches;
-use prs_lib::{crypto::prelude::*, Store};
+use prs_lib::{Secret, Store};
 use thiserror::Error;
 
 use crate::cmd::matcher::{show::ShowMatcher, MainMatcher, Matcher};
@@ -42,12 +42,33 @@ impl<'a> Show<'a> {
         #[cfg(all(feature = "tomb", target_os = "linux"))]
         tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
 
-        let secret =
-            select::store_select_secret(&store, matcher_show.query()).ok_or(Err::NoneSelected)?;
-
-        let mut plaintext = crate::crypto::context(&matcher_main)?
-            .decrypt_file(&secret.path)
-            .map_err(Err::Read)?;
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let (secret, mut plaintext) = match matcher_show.rev() {
+            // Decrypt secret at revision from store history
+            Some(rev) => {
+                let name = secret::select_name_with_history(&store, matcher_show.query())
+                    .ok_or(Err::NoneSelected)?;
+                let (path, ciphertext) = store.sync().secret_at(&name, rev).map_err(Err::Read)?;
+                let secret = Secret::from(&store, path);
+                let plaintext = crate::crypto::decrypt_secret_ciphertext(
+                    &mut context,
+                    &store,
+                    &secret,
+                    ciphertext,
+                    &matcher_main,
+                )
+                .map_err(Err::Read)?;
+                (secret, plaintext)
+            }
+            None => {
+                let secret = select::store_select_secret(&store, matcher_show.query())
+                    .ok_or(Err::NoneSelected)?;
+                let plaintext =
+                    crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+                        .map_err(Err::Read)?;
+                (secret, plaintext)
+            }
+        };
 
         // Trim plaintext to first line or property
         if matcher_show.first_line() {
@@ -2,9 +2,9 @@ use std::process::Command;
 
 use anyhow::{anyhow, Result};
 use clap::ArgMatches;
-#[cfg(unix)]
-use prs_lib::util::git;
 use prs_lib::Store;
+#[cfg(unix)]
+use prs_lib::{agent, util::git};
 use thiserror::Error;
 
 use crate::cmd::matcher::{slam::SlamMatcher, MainMatcher, Matcher};
@@ -40,6 +40,10 @@ impl<'a> Slam<'a> {
             }
         };
 
+        // Attempt to clear prs agent cache
+        #[cfg(unix)]
+        clear_prs_agent(&matcher_main);
+
         // Attempt to flush GPG agents
         flush_gpg_agents(&matcher_main);
 
@@ -77,6 +81,32 @@ impl<'a> Slam<'a> {
     }
 }
 
+/// Attempt to clear the prs agent cache, if the agent is running.
+#[cfg(unix)]
+fn clear_prs_agent(matcher_main: &MainMatcher) {
+    let mut client = match agent::Client::connect(&agent::socket_path()) {
+        Ok(client) => client,
+        Err(_) => return,
+    };
+
+    if !matcher_main.quiet() {
+        eprint!("Clear prs agent cache: ");
+    }
+    match client.clear() {
+        Err(err) => {
+            if !matcher_main.quiet() {
+                eprintln!("FAIL");
+            }
+            error::print_error(err.context("failed to clear prs agent cache"));
+        }
+        Ok(_) => {
+            if !matcher_main.quiet() {
+                eprintln!("ok");
+            }
+        }
+    }
+}
+
 /// Attempt to flush and clear all GPG agents that potentially unlock secrets.
 fn flush_gpg_agents(matcher_main: &MainMatcher) {
     let mut flushed = false;
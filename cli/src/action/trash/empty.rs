@@ -0,0 +1,108 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::Store;
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    trash::{empty::EmptyMatcher, TrashMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{cli, error, sync};
+
+/// A trash empty action.
+pub struct Empty<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Empty<'a> {
+    /// Construct a new empty action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the empty action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_trash = TrashMatcher::with(self.cmd_matches).unwrap();
+        let matcher_empty = EmptyMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+        let trash = store.trash();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        let secrets = super::list_valid(&trash).map_err(Err::List)?;
+        if secrets.is_empty() {
+            if !matcher_main.quiet() {
+                eprintln!("Trash is empty");
+            }
+            return Ok(());
+        }
+
+        // Confirm
+        let prompt = format!(
+            "Permanently delete {} removed secret{}?",
+            secrets.len(),
+            if secrets.len() == 1 { "" } else { "s" },
+        );
+        if !matcher_main.force() && !cli::prompt_yes(&prompt, Some(true), &matcher_main) {
+            if matcher_main.verbose() {
+                eprintln!("Emptying trash cancelled");
+            }
+            error::quit();
+        }
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_empty.allow_dirty());
+        if !matcher_empty.no_sync() {
+            sync.prepare()?;
+        }
+
+        for secret in &secrets {
+            trash.delete(secret).map_err(Err::Delete)?;
+        }
+
+        // Finalize sync
+        if !matcher_empty.no_sync() {
+            sync.finalize("Empty trash")?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Trash emptied");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to list trash")]
+    List(#[source] anyhow::Error),
+
+    #[error("failed to delete secret from trash")]
+    Delete(#[source] anyhow::Error),
+}
@@ -0,0 +1,85 @@
+use std::time::SystemTime;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::Store;
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    trash::{list::ListMatcher, TrashMatcher},
+    MainMatcher, Matcher,
+};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{style, time};
+
+/// A trash list action.
+pub struct List<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> List<'a> {
+    /// Construct a new list action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the list action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_trash = TrashMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_list = ListMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        let secrets = super::list_valid(&store.trash()).map_err(Err::List)?;
+        if secrets.is_empty() && !matcher_main.quiet() {
+            eprintln!("Trash is empty");
+        }
+
+        let now = SystemTime::now();
+        for secret in secrets {
+            if matcher_main.quiet() {
+                println!("{}", secret.name);
+                continue;
+            }
+
+            let age = now.duration_since(secret.removed).unwrap_or_default();
+            let removed = match age.as_secs() {
+                0 => "just now".into(),
+                secs => format!("{} ago", time::format_duration(secs as u32)),
+            };
+            println!("{}  removed {removed}", style::highlight(&secret.name));
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, false).map_err(Err::Tomb)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to list trash")]
+    List(#[source] anyhow::Error),
+}
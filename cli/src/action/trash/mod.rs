@@ -0,0 +1,63 @@
+pub mod empty;
+pub mod list;
+pub mod restore;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{
+    trash::{Trash as StoreTrash, TrashedSecret},
+    Secret, Store,
+};
+
+use crate::cmd::matcher::{trash::TrashMatcher, Matcher};
+use crate::util::error;
+
+/// Trash management action.
+pub struct Trash<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Trash<'a> {
+    /// Construct a new trash action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the trash action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_trash = TrashMatcher::with(self.cmd_matches).unwrap();
+
+        if matcher_trash.cmd_list().is_some() {
+            return list::List::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_trash.cmd_restore().is_some() {
+            return restore::Restore::new(self.cmd_matches).invoke();
+        }
+
+        if matcher_trash.cmd_empty().is_some() {
+            return empty::Empty::new(self.cmd_matches).invoke();
+        }
+
+        // Unreachable, clap will print help for missing sub command instead
+        unreachable!()
+    }
+}
+
+/// List secrets in the trash, warning about and skipping invalid entries.
+pub(crate) fn list_valid(trash: &StoreTrash) -> Result<Vec<TrashedSecret>> {
+    let (secrets, invalid) = trash.list_checked()?;
+    for (id, err) in invalid {
+        error::print_warning(format!("skipping invalid trash entry '{id}': {err}"));
+    }
+    Ok(secrets)
+}
+
+/// Get all valid secrets in the trash as store secrets, to inspect or re-encrypt them in place.
+pub(crate) fn secrets(store: &Store) -> Result<Vec<Secret>> {
+    Ok(list_valid(&store.trash())?
+        .iter()
+        .map(TrashedSecret::secret)
+        .collect())
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{trash::TrashedSecret, Store};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    trash::{restore::RestoreMatcher, TrashMatcher},
+    MainMatcher, Matcher,
+};
+use crate::util::sync;
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+
+/// A trash restore action.
+pub struct Restore<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Restore<'a> {
+    /// Construct a new restore action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the restore action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_trash = TrashMatcher::with(self.cmd_matches).unwrap();
+        let matcher_restore = RestoreMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+        let trash = store.trash();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_restore.allow_dirty());
+        if !matcher_restore.no_sync() {
+            sync.prepare()?;
+        }
+
+        let query = matcher_restore.query().unwrap_or_default();
+        let trashed = find_trashed(super::list_valid(&trash).map_err(Err::List)?, &query)?;
+        let secret = trash.restore(&trashed).map_err(Err::Restore)?;
+
+        // Finalize sync
+        if !matcher_restore.no_sync() {
+            sync.finalize(format!("Restore secret {} from trash", secret.name))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Secret restored: {}", secret.name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the trashed secret matching the given query.
+///
+/// An exact name match is preferred, picking the most recently removed one. Otherwise the query
+/// must match a single removed secret name.
+fn find_trashed(secrets: Vec<TrashedSecret>, query: &str) -> Result<TrashedSecret, Err> {
+    if let Some(secret) = secrets.iter().find(|secret| secret.name == query) {
+        return Ok(secret.clone());
+    }
+
+    let mut matches: Vec<TrashedSecret> = secrets
+        .into_iter()
+        .filter(|secret| secret.name.contains(query))
+        .collect();
+    match matches.len() {
+        0 => Err(Err::NotFound(query.into())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Err::Ambiguous(
+            matches
+                .iter()
+                .map(|secret| secret.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("failed to list trash")]
+    List(#[source] anyhow::Error),
+
+    #[error("no removed secret matches: {0}")]
+    NotFound(String),
+
+    #[error("multiple removed secrets match, be more specific: {0}")]
+    Ambiguous(String),
+
+    #[error("failed to restore secret from trash")]
+    Restore(#[source] anyhow::Error),
+}
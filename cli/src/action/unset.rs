@@ -0,0 +1,108 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use prs_lib::{crypto::prelude::*, SecretDocument, Store};
+use thiserror::Error;
+
+use crate::cmd::matcher::{unset::UnsetMatcher, MainMatcher, Matcher};
+#[cfg(all(feature = "tomb", target_os = "linux"))]
+use crate::util::tomb;
+use crate::util::{secret, select, sync};
+
+/// Unset secret field action.
+pub struct Unset<'a> {
+    cmd_matches: &'a ArgMatches,
+}
+
+impl<'a> Unset<'a> {
+    /// Construct a new unset action.
+    pub fn new(cmd_matches: &'a ArgMatches) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the unset action.
+    pub fn invoke(&self) -> Result<()> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_unset = UnsetMatcher::with(self.cmd_matches).unwrap();
+
+        let store = Store::open(matcher_main.store()).map_err(Err::Store)?;
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        let mut tomb = store.tomb(
+            !matcher_main.verbose(),
+            matcher_main.verbose(),
+            matcher_main.force(),
+        );
+        let sync = store.sync();
+
+        // Prepare tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::prepare_tomb(&mut tomb, &matcher_main).map_err(Err::Tomb)?;
+
+        // Prepare sync
+        sync::ensure_ready(&sync, matcher_unset.allow_dirty());
+        if !matcher_unset.no_sync() {
+            sync.prepare()?;
+        }
+
+        let secret =
+            select::store_select_secret(&store, matcher_unset.query()).ok_or(Err::NoneSelected)?;
+
+        secret::print_name(matcher_unset.query(), &secret, &store, matcher_main.quiet());
+
+        // Remove field from secret
+        let field = matcher_unset.field();
+        let mut context = crate::crypto::context(&matcher_main)?;
+        let plaintext = crate::crypto::decrypt_secret(&mut context, &store, &secret, &matcher_main)
+            .map_err(Err::Read)?;
+        let mut document = SecretDocument::parse(&plaintext).map_err(Err::Parse)?;
+        if !document.remove(field) {
+            return Err(Err::NoField(field.into()).into());
+        }
+
+        // Encrypt and write changed plaintext
+        let recipients = store.recipients_for(&secret.path)?;
+        context
+            .encrypt_file(&recipients, document.to_plaintext(), &secret.path)
+            .map_err(Err::Write)?;
+
+        // Finalize sync
+        if !matcher_unset.no_sync() {
+            sync.finalize(format!("Unset field {} in secret {}", field, secret.name))?;
+        }
+
+        // Finalize tomb
+        #[cfg(all(feature = "tomb", target_os = "linux"))]
+        tomb::finalize_tomb(&mut tomb, &matcher_main, true).map_err(Err::Tomb)?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Field removed");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to access password store")]
+    Store(#[source] anyhow::Error),
+
+    #[cfg(all(feature = "tomb", target_os = "linux"))]
+    #[error("failed to prepare password store tomb for usage")]
+    Tomb(#[source] anyhow::Error),
+
+    #[error("no secret selected")]
+    NoneSelected,
+
+    #[error("failed to read secret")]
+    Read(#[source] anyhow::Error),
+
+    #[error("failed to parse secret")]
+    Parse(#[source] anyhow::Error),
+
+    #[error("field '{}' does not exist in secret", _0)]
+    NoField(String),
+
+    #[error("failed to write changed secret")]
+    Write(#[source] anyhow::Error),
+}
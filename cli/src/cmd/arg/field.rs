@@ -0,0 +1,45 @@
+use clap::{Arg, ArgAction, ArgMatches};
+
+use super::{CmdArg, CmdArgOption};
+
+/// The template field argument.
+pub struct ArgField {}
+
+impl CmdArg for ArgField {
+    fn name() -> &'static str {
+        "field"
+    }
+
+    fn build() -> Arg {
+        Arg::new("field")
+            .long("field")
+            .short('F')
+            .value_name("KEY=VALUE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .value_parser(parse_field)
+            .requires("template")
+            .help("Template field value, may be given multiple times")
+    }
+}
+
+impl<'a> CmdArgOption<'a> for ArgField {
+    type Value = Vec<(String, String)>;
+
+    fn value(matches: &'a ArgMatches) -> Self::Value {
+        matches
+            .get_many::<(String, String)>(Self::name())
+            .map(|fields| fields.cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Parse a `KEY=VALUE` field argument.
+fn parse_field(field: &str) -> Result<(String, String), String> {
+    match field.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err("expected KEY=VALUE".into()),
+    }
+}
@@ -0,0 +1,62 @@
+use std::num::NonZeroUsize;
+use std::thread;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches};
+use thiserror::Error;
+
+use super::{CmdArg, CmdArgOption};
+
+/// Maximum number of jobs used by default.
+const MAX_DEFAULT_JOBS: usize = 8;
+
+/// The jobs argument.
+pub struct ArgJobs {}
+
+impl ArgJobs {
+    pub fn value_or_default(matches: &ArgMatches) -> Result<usize> {
+        Self::value(matches).unwrap_or_else(|| Ok(Self::default_jobs()))
+    }
+
+    /// Default number of jobs, based on available parallelism.
+    pub fn default_jobs() -> usize {
+        thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(MAX_DEFAULT_JOBS)
+    }
+}
+
+impl CmdArg for ArgJobs {
+    fn name() -> &'static str {
+        "jobs"
+    }
+
+    fn build() -> Arg {
+        Arg::new("jobs")
+            .long("jobs")
+            .short('j')
+            .alias("threads")
+            .value_name("N")
+            .num_args(1)
+            .help("Number of secrets to process in parallel")
+    }
+}
+
+impl<'a> CmdArgOption<'a> for ArgJobs {
+    type Value = Option<Result<usize>>;
+
+    fn value(matches: &'a ArgMatches) -> Self::Value {
+        Self::value_raw(matches).map(|jobs| {
+            jobs.parse::<NonZeroUsize>()
+                .map(NonZeroUsize::get)
+                .map_err(|err| Err::Parse(err).into())
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to parse number of jobs, must be a positive number")]
+    Parse(#[source] std::num::ParseIntError),
+}
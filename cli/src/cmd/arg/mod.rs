@@ -1,8 +1,11 @@
 pub mod allow_dirty;
+pub mod field;
+pub mod jobs;
 pub mod no_sync;
 pub mod property;
 pub mod query;
 pub mod store;
+pub mod template;
 pub mod timeout;
 pub mod viewer;
 
@@ -10,10 +13,13 @@ use clap::{parser::ValuesRef, Arg, ArgMatches};
 
 // Re-export to arg module
 pub use self::allow_dirty::ArgAllowDirty;
+pub use self::field::ArgField;
+pub use self::jobs::ArgJobs;
 pub use self::no_sync::ArgNoSync;
 pub use self::property::ArgProperty;
 pub use self::query::ArgQuery;
 pub use self::store::ArgStore;
+pub use self::template::ArgTemplate;
 pub use self::timeout::ArgTimeout;
 pub use self::viewer::ArgViewer;
 
@@ -0,0 +1,35 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgOption};
+
+/// The template argument.
+pub struct ArgTemplate {}
+
+impl CmdArg for ArgTemplate {
+    fn name() -> &'static str {
+        "template"
+    }
+
+    fn build() -> Arg {
+        Arg::new("template")
+            .long("template")
+            .short('T')
+            .alias("tmpl")
+            .value_name("TEMPLATE")
+            .num_args(1)
+            .help("Fill in a store template for the new secret")
+            .long_help(
+                "Fill in a store template for the new secret.\n\n\
+                 Templates are read from .prs/templates/<TEMPLATE>.tmpl in the store. Generated \
+                 passwords are filled in, fields are taken from --field or prompted for.",
+            )
+    }
+}
+
+impl<'a> CmdArgOption<'a> for ArgTemplate {
+    type Value = Option<&'a String>;
+
+    fn value(matches: &'a ArgMatches) -> Self::Value {
+        Self::value_raw(matches)
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
 use super::arg::{ArgStore, CmdArg};
@@ -81,7 +83,40 @@ impl<'a> Handler {
                     .global(true)
                     .help("Instruct GPG to ask passphrase in TTY rather than pinentry"),
             )
-            .subcommand(subcmd::CmdShow::build());
+            .arg(
+                Arg::new("passphrase-file")
+                    .long("passphrase-file")
+                    .value_name("FILE")
+                    .env("PRS_GPG_PASSPHRASE_FILE")
+                    .num_args(1)
+                    .global(true)
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("Read GPG passphrase from file rather than pinentry"),
+            )
+            .arg(
+                Arg::new("allow-passphrase-input")
+                    .long("allow-passphrase-input")
+                    .env("PRS_ALLOW_PASSPHRASE_INPUT")
+                    .num_args(0)
+                    .action(ArgAction::SetTrue)
+                    .value_parser(clap::builder::BoolishValueParser::new())
+                    .global(true)
+                    .help("Allow providing the GPG passphrase non-interactively"),
+            );
+
+        #[cfg(unix)]
+        let app = app.arg(
+            Arg::new("passphrase-fd")
+                .long("passphrase-fd")
+                .value_name("FD")
+                .num_args(1)
+                .global(true)
+                .conflicts_with("passphrase-file")
+                .value_parser(clap::value_parser!(i32))
+                .help("Read GPG passphrase from file descriptor rather than pinentry"),
+        );
+
+        let app = app.subcommand(subcmd::CmdShow::build());
 
         #[cfg(feature = "clipboard")]
         let app = app.subcommand(subcmd::CmdCopy::build());
@@ -90,6 +125,8 @@ impl<'a> Handler {
             .subcommand(subcmd::CmdGenerate::build())
             .subcommand(subcmd::CmdAdd::build())
             .subcommand(subcmd::CmdEdit::build())
+            .subcommand(subcmd::CmdSet::build())
+            .subcommand(subcmd::CmdUnset::build())
             .subcommand(subcmd::CmdDuplicate::build());
 
         #[cfg(feature = "alias")]
@@ -98,8 +135,12 @@ impl<'a> Handler {
         let app = app
             .subcommand(subcmd::CmdMove::build())
             .subcommand(subcmd::CmdRemove::build())
+            .subcommand(subcmd::CmdTrash::build())
             .subcommand(subcmd::CmdList::build())
             .subcommand(subcmd::CmdGrep::build())
+            .subcommand(subcmd::CmdHistory::build())
+            .subcommand(subcmd::CmdRestore::build())
+            .subcommand(subcmd::CmdInspect::build())
             .subcommand(subcmd::CmdInit::build())
             .subcommand(subcmd::CmdClone::build())
             .subcommand(subcmd::CmdSync::build())
@@ -115,6 +156,9 @@ impl<'a> Handler {
         #[cfg(all(feature = "tomb", target_os = "linux"))]
         let app = app.subcommand(subcmd::CmdTomb::build());
 
+        #[cfg(unix)]
+        let app = app.subcommand(subcmd::CmdAgent::build());
+
         #[allow(clippy::let_and_return)]
         let app = app
             .subcommand(subcmd::CmdHousekeeping::build())
@@ -145,6 +189,12 @@ impl<'a> Handler {
         matcher::AddMatcher::with(&self.matches)
     }
 
+    /// Get the agent sub command, if matched.
+    #[cfg(unix)]
+    pub fn agent(&'a self) -> Option<matcher::AgentMatcher> {
+        matcher::AgentMatcher::with(&self.matches)
+    }
+
     /// Get the alias sub command, if matched.
     #[cfg(feature = "alias")]
     pub fn alias(&'a self) -> Option<matcher::AliasMatcher> {
@@ -187,6 +237,11 @@ impl<'a> Handler {
         matcher::GrepMatcher::with(&self.matches)
     }
 
+    /// Get the history sub command, if matched.
+    pub fn history(&'a self) -> Option<matcher::HistoryMatcher> {
+        matcher::HistoryMatcher::with(&self.matches)
+    }
+
     /// Get the housekeeping sub command, if matched.
     pub fn housekeeping(&'a self) -> Option<matcher::HousekeepingMatcher> {
         matcher::HousekeepingMatcher::with(&self.matches)
@@ -197,6 +252,11 @@ impl<'a> Handler {
         matcher::InitMatcher::with(&self.matches)
     }
 
+    /// Get the inspect sub command, if matched.
+    pub fn inspect(&'a self) -> Option<matcher::InspectMatcher> {
+        matcher::InspectMatcher::with(&self.matches)
+    }
+
     /// Get the internal sub command, if matched.
     pub fn internal(&'a self) -> Option<matcher::InternalMatcher> {
         matcher::InternalMatcher::with(&self.matches)
@@ -227,6 +287,16 @@ impl<'a> Handler {
         matcher::RemoveMatcher::with(&self.matches)
     }
 
+    /// Get the restore sub command, if matched.
+    pub fn restore(&'a self) -> Option<matcher::RestoreMatcher> {
+        matcher::RestoreMatcher::with(&self.matches)
+    }
+
+    /// Get the set sub command, if matched.
+    pub fn set(&'a self) -> Option<matcher::SetMatcher> {
+        matcher::SetMatcher::with(&self.matches)
+    }
+
     /// Get the show sub command, if matched.
     pub fn show(&'a self) -> Option<matcher::ShowMatcher> {
         matcher::ShowMatcher::with(&self.matches)
@@ -248,4 +318,14 @@ impl<'a> Handler {
     pub fn totp(&'a self) -> Option<matcher::TotpMatcher> {
         matcher::TotpMatcher::with(&self.matches)
     }
+
+    /// Get the trash sub command, if matched.
+    pub fn trash(&'a self) -> Option<matcher::TrashMatcher> {
+        matcher::TrashMatcher::with(&self.matches)
+    }
+
+    /// Get the unset sub command, if matched.
+    pub fn unset(&'a self) -> Option<matcher::UnsetMatcher> {
+        matcher::UnsetMatcher::with(&self.matches)
+    }
 }
@@ -1,7 +1,7 @@
 use clap::ArgMatches;
 
 use super::Matcher;
-use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArgFlag};
+use crate::cmd::arg::{ArgAllowDirty, ArgField, ArgNoSync, ArgTemplate, CmdArgFlag, CmdArgOption};
 
 /// The add command matcher.
 pub struct AddMatcher<'a> {
@@ -24,6 +24,16 @@ impl<'a: 'b, 'b> AddMatcher<'a> {
         self.matches.get_flag("stdin")
     }
 
+    /// Secret template to fill in.
+    pub fn template(&self) -> Option<&String> {
+        ArgTemplate::value(self.matches)
+    }
+
+    /// Template field values.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        ArgField::value(self.matches)
+    }
+
     /// Whether to allow a dirty repository for syncing.
     pub fn allow_dirty(&self) -> bool {
         ArgAllowDirty::is_present(self.matches)
@@ -0,0 +1,17 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The agent clear command matcher.
+pub struct ClearMatcher<'a> {
+    _matches: &'a ArgMatches,
+}
+
+impl<'a> Matcher<'a> for ClearMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("agent")?
+            .subcommand_matches("clear")
+            .map(|matches| ClearMatcher { _matches: matches })
+    }
+}
@@ -0,0 +1,68 @@
+pub mod clear;
+pub mod run;
+#[cfg(target_os = "linux")]
+pub mod start;
+pub mod status;
+pub mod stop;
+
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::util::error::{quit_error, ErrorHints};
+
+/// The agent command matcher.
+pub struct AgentMatcher<'a> {
+    root: &'a ArgMatches,
+    _matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> AgentMatcher<'a> {
+    /// Get the agent run sub command, if matched.
+    pub fn cmd_run(&'a self) -> Option<run::RunMatcher> {
+        run::RunMatcher::with(self.root)
+    }
+
+    /// Get the agent start sub command, if matched.
+    #[cfg(target_os = "linux")]
+    pub fn cmd_start(&'a self) -> Option<start::StartMatcher> {
+        start::StartMatcher::with(self.root)
+    }
+
+    /// Get the agent stop sub command, if matched.
+    pub fn cmd_stop(&'a self) -> Option<stop::StopMatcher> {
+        stop::StopMatcher::with(self.root)
+    }
+
+    /// Get the agent status sub command, if matched.
+    pub fn cmd_status(&'a self) -> Option<status::StatusMatcher> {
+        status::StatusMatcher::with(self.root)
+    }
+
+    /// Get the agent clear sub command, if matched.
+    pub fn cmd_clear(&'a self) -> Option<clear::ClearMatcher> {
+        clear::ClearMatcher::with(self.root)
+    }
+}
+
+impl<'a> Matcher<'a> for AgentMatcher<'a> {
+    fn with(root: &'a ArgMatches) -> Option<Self> {
+        root.subcommand_matches("agent")
+            .map(|matches| AgentMatcher {
+                root,
+                _matches: matches,
+            })
+    }
+}
+
+/// Parse the time to live argument in seconds, quits on error.
+///
+/// Uses the default if not set.
+fn ttl(matches: &ArgMatches) -> u64 {
+    match matches.get_one::<String>("ttl") {
+        Some(time) => match crate::util::time::parse_duration(time) {
+            Ok(time) => time as u64,
+            Err(err) => quit_error(err.into(), ErrorHints::default()),
+        },
+        None => prs_lib::agent::AGENT_TTL_SEC,
+    }
+}
@@ -0,0 +1,24 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The agent run command matcher.
+pub struct RunMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> RunMatcher<'a> {
+    /// The time to cache decrypted secrets for, in seconds.
+    pub fn ttl(&self) -> u64 {
+        super::ttl(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for RunMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("agent")?
+            .subcommand_matches("run")
+            .map(|matches| RunMatcher { matches })
+    }
+}
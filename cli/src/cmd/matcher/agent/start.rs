@@ -0,0 +1,24 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The agent start command matcher.
+pub struct StartMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> StartMatcher<'a> {
+    /// The time to cache decrypted secrets for, in seconds.
+    pub fn ttl(&self) -> u64 {
+        super::ttl(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for StartMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("agent")?
+            .subcommand_matches("start")
+            .map(|matches| StartMatcher { matches })
+    }
+}
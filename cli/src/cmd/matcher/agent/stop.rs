@@ -0,0 +1,17 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The agent stop command matcher.
+pub struct StopMatcher<'a> {
+    _matches: &'a ArgMatches,
+}
+
+impl<'a> Matcher<'a> for StopMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("agent")?
+            .subcommand_matches("stop")
+            .map(|matches| StopMatcher { _matches: matches })
+    }
+}
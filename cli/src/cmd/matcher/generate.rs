@@ -5,13 +5,13 @@ use clap::ArgMatches;
 use super::Matcher;
 #[cfg(feature = "clipboard")]
 use crate::cmd::arg::ArgTimeout;
-use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArgFlag};
+use crate::cmd::arg::{ArgAllowDirty, ArgField, ArgNoSync, ArgTemplate, CmdArgFlag, CmdArgOption};
 
 /// Default password length in characters.
-const PASSWORD_LENGTH: u16 = 24;
+pub(crate) const PASSWORD_LENGTH: u16 = 24;
 
 /// Default passphrase length in words.
-const PASSPHRASE_LENGTH: u16 = 5;
+pub(crate) const PASSPHRASE_LENGTH: u16 = 5;
 
 /// The generate command matcher.
 pub struct GenerateMatcher<'a> {
@@ -75,6 +75,16 @@ impl<'a: 'b, 'b> GenerateMatcher<'a> {
         self.matches.get_flag("show")
     }
 
+    /// Secret template to fill in.
+    pub fn template(&self) -> Option<&String> {
+        ArgTemplate::value(self.matches)
+    }
+
+    /// Template field values.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        ArgField::value(self.matches)
+    }
+
     /// Whether to allow a dirty repository for syncing.
     pub fn allow_dirty(&self) -> bool {
         ArgAllowDirty::is_present(self.matches)
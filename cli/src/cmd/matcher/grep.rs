@@ -1,6 +1,8 @@
+use anyhow::Result;
 use clap::ArgMatches;
 
 use super::Matcher;
+use crate::cmd::arg::ArgJobs;
 
 /// The grep command matcher.
 pub struct GrepMatcher<'a> {
@@ -27,6 +29,11 @@ impl<'a: 'b, 'b> GrepMatcher<'a> {
     pub fn with_aliases(&self) -> bool {
         self.matches.get_flag("aliases")
     }
+
+    /// Number of secrets to grep in parallel.
+    pub fn jobs(&self) -> Result<usize> {
+        ArgJobs::value_or_default(self.matches)
+    }
 }
 
 impl<'a> Matcher<'a> for GrepMatcher<'a> {
@@ -0,0 +1,24 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgQuery, CmdArgOption};
+
+/// The history command matcher.
+pub struct HistoryMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> HistoryMatcher<'a> {
+    /// The secret query.
+    pub fn query(&self) -> Option<String> {
+        ArgQuery::value(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for HistoryMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("history")
+            .map(|matches| HistoryMatcher { matches })
+    }
+}
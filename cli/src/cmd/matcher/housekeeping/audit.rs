@@ -0,0 +1,25 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgQuery, CmdArgOption};
+
+/// The housekeeping audit command matcher.
+pub struct AuditMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> AuditMatcher<'a> {
+    /// The secret query.
+    pub fn query(&self) -> Option<String> {
+        ArgQuery::value(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for AuditMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("housekeeping")?
+            .subcommand_matches("audit")
+            .map(|matches| AuditMatcher { matches })
+    }
+}
@@ -0,0 +1,46 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArgFlag, CmdArgOption};
+
+/// The housekeeping convert command matcher.
+pub struct ConvertMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> ConvertMatcher<'a> {
+    /// The secret query.
+    pub fn query(&self) -> Option<String> {
+        ArgQuery::value(self.matches)
+    }
+
+    /// Whether to convert to ASCII armored format, `None` to use the store option.
+    pub fn armor(&self) -> Option<bool> {
+        if self.matches.get_flag("armor") {
+            Some(true)
+        } else if self.matches.get_flag("binary") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Whether to allow a dirty repository for syncing.
+    pub fn allow_dirty(&self) -> bool {
+        ArgAllowDirty::is_present(self.matches)
+    }
+
+    /// Whether to not sync.
+    pub fn no_sync(&self) -> bool {
+        ArgNoSync::is_present(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for ConvertMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("housekeeping")?
+            .subcommand_matches("convert")
+            .map(|matches| ConvertMatcher { matches })
+    }
+}
@@ -1,3 +1,5 @@
+pub mod audit;
+pub mod convert;
 pub mod recrypt;
 pub mod run;
 pub mod sync_keys;
@@ -13,6 +15,16 @@ pub struct HousekeepingMatcher<'a> {
 }
 
 impl<'a: 'b, 'b> HousekeepingMatcher<'a> {
+    /// Get the housekepeing audit sub command, if matched.
+    pub fn audit(&'a self) -> Option<audit::AuditMatcher> {
+        audit::AuditMatcher::with(self.root)
+    }
+
+    /// Get the housekepeing convert sub command, if matched.
+    pub fn convert(&'a self) -> Option<convert::ConvertMatcher> {
+        convert::ConvertMatcher::with(self.root)
+    }
+
     /// Get the housekepeing recrypt sub command, if matched.
     pub fn recrypt(&'a self) -> Option<recrypt::RecryptMatcher> {
         recrypt::RecryptMatcher::with(self.root)
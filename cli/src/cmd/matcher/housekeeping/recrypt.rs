@@ -1,7 +1,8 @@
+use anyhow::Result;
 use clap::ArgMatches;
 
 use super::Matcher;
-use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArgFlag, CmdArgOption};
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, ArgQuery, CmdArgFlag, CmdArgOption};
 
 /// The housekeeping recrypt command matcher.
 pub struct RecryptMatcher<'a> {
@@ -14,6 +15,21 @@ impl<'a: 'b, 'b> RecryptMatcher<'a> {
         ArgQuery::value(self.matches)
     }
 
+    /// Whether to only re-encrypt secrets with drifted recipients.
+    pub fn only_drifted(&self) -> bool {
+        self.matches.get_flag("only-drifted")
+    }
+
+    /// Whether to resume an interrupted re-encryption.
+    pub fn resume(&self) -> bool {
+        self.matches.get_flag("resume")
+    }
+
+    /// Number of secrets to re-encrypt in parallel.
+    pub fn jobs(&self) -> Result<usize> {
+        ArgJobs::value_or_default(self.matches)
+    }
+
     /// Whether to allow a dirty repository for syncing.
     pub fn allow_dirty(&self) -> bool {
         ArgAllowDirty::is_present(self.matches)
@@ -2,6 +2,7 @@ use clap::ArgMatches;
 
 use super::Matcher;
 use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArgFlag};
+use crate::util::error::{quit_error, ErrorHints};
 
 /// The housekeeping run command matcher.
 pub struct RunMatcher<'a> {
@@ -9,6 +10,15 @@ pub struct RunMatcher<'a> {
 }
 
 impl<'a: 'b, 'b> RunMatcher<'a> {
+    /// Window in seconds to warn about expiring recipient keys in.
+    pub fn expiry_window(&self) -> u64 {
+        let window: &String = self.matches.get_one("expiry-window").unwrap();
+        match crate::util::time::parse_duration(window) {
+            Ok(window) => window as u64,
+            Err(err) => quit_error(err.into(), ErrorHints::default()),
+        }
+    }
+
     /// Whether to allow a dirty repository for syncing.
     pub fn allow_dirty(&self) -> bool {
         ArgAllowDirty::is_present(self.matches)
@@ -1,18 +1,27 @@
 use clap::ArgMatches;
+use prs_lib::crypto::Proto;
 
 use super::Matcher;
 
 /// The init command matcher.
 pub struct InitMatcher<'a> {
-    _matches: &'a ArgMatches,
+    matches: &'a ArgMatches,
 }
 
-impl<'a: 'b, 'b> InitMatcher<'a> {}
+impl<'a: 'b, 'b> InitMatcher<'a> {
+    /// Cryptography protocol to use for the new store.
+    pub fn proto(&self) -> Proto {
+        match self.matches.get_one::<String>("proto").map(|p| p.as_str()) {
+            Some("age") => Proto::Age,
+            _ => Proto::Gpg,
+        }
+    }
+}
 
 impl<'a> Matcher<'a> for InitMatcher<'a> {
     fn with(matches: &'a ArgMatches) -> Option<Self> {
         matches
             .subcommand_matches("init")
-            .map(|matches| InitMatcher { _matches: matches })
+            .map(|matches| InitMatcher { matches })
     }
 }
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::ArgMatches;
 
 use super::Matcher;
@@ -43,6 +45,22 @@ impl<'a: 'b, 'b> MainMatcher<'a> {
     pub fn gpg_tty(&self) -> bool {
         self.matches.get_flag("gpg-tty")
     }
+
+    /// File to read the GPG passphrase from.
+    pub fn passphrase_file(&self) -> Option<&PathBuf> {
+        self.matches.get_one("passphrase-file")
+    }
+
+    /// File descriptor to read the GPG passphrase from.
+    #[cfg(unix)]
+    pub fn passphrase_fd(&self) -> Option<i32> {
+        self.matches.get_one("passphrase-fd").copied()
+    }
+
+    /// Check whether providing the GPG passphrase non-interactively is allowed.
+    pub fn allow_passphrase_input(&self) -> bool {
+        self.matches.get_flag("allow-passphrase-input")
+    }
 }
 
 impl<'a> Matcher<'a> for MainMatcher<'a> {
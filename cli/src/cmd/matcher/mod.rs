@@ -1,4 +1,6 @@
 pub mod add;
+#[cfg(unix)]
+pub mod agent;
 #[cfg(feature = "alias")]
 pub mod alias;
 pub mod clone;
@@ -9,14 +11,18 @@ pub mod edit;
 pub mod generate;
 pub mod git;
 pub mod grep;
+pub mod history;
 pub mod housekeeping;
 pub mod init;
+pub mod inspect;
 pub mod internal;
 pub mod list;
 pub mod main;
 pub mod r#move;
 pub mod recipients;
 pub mod remove;
+pub mod restore;
+pub mod set;
 pub mod show;
 pub mod slam;
 pub mod sync;
@@ -24,9 +30,13 @@ pub mod sync;
 pub mod tomb;
 #[cfg(feature = "totp")]
 pub mod totp;
+pub mod trash;
+pub mod unset;
 
 // Re-export to matcher module
 pub use self::add::AddMatcher;
+#[cfg(unix)]
+pub use self::agent::AgentMatcher;
 #[cfg(feature = "alias")]
 pub use self::alias::AliasMatcher;
 pub use self::clone::CloneMatcher;
@@ -37,14 +47,18 @@ pub use self::edit::EditMatcher;
 pub use self::generate::GenerateMatcher;
 pub use self::git::GitMatcher;
 pub use self::grep::GrepMatcher;
+pub use self::history::HistoryMatcher;
 pub use self::housekeeping::HousekeepingMatcher;
 pub use self::init::InitMatcher;
+pub use self::inspect::InspectMatcher;
 pub use self::internal::InternalMatcher;
 pub use self::list::ListMatcher;
 pub use self::main::MainMatcher;
 pub use self::r#move::MoveMatcher;
 pub use self::recipients::RecipientsMatcher;
 pub use self::remove::RemoveMatcher;
+pub use self::restore::RestoreMatcher;
+pub use self::set::SetMatcher;
 pub use self::show::ShowMatcher;
 pub use self::slam::SlamMatcher;
 pub use self::sync::SyncMatcher;
@@ -52,6 +66,8 @@ pub use self::sync::SyncMatcher;
 pub use self::tomb::TombMatcher;
 #[cfg(feature = "totp")]
 pub use self::totp::TotpMatcher;
+pub use self::trash::TrashMatcher;
+pub use self::unset::UnsetMatcher;
 
 use clap::ArgMatches;
 
@@ -0,0 +1,24 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The recipients access command matcher.
+pub struct AccessMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> AccessMatcher<'a> {
+    /// Query for the key to report on.
+    pub fn key(&self) -> Option<&String> {
+        self.matches.get_one("KEY")
+    }
+}
+
+impl<'a> Matcher<'a> for AccessMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("recipients")?
+            .subcommand_matches("access")
+            .map(|matches| AccessMatcher { matches })
+    }
+}
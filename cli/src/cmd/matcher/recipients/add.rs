@@ -19,6 +19,21 @@ impl<'a: 'b, 'b> AddMatcher<'a> {
         self.matches.get_flag("secret")
     }
 
+    /// Recipient group to add, instead of a key.
+    pub fn group(&self) -> Option<&String> {
+        self.matches.get_one("group")
+    }
+
+    /// Last day of access for the recipient, if set.
+    pub fn until(&self) -> Option<&String> {
+        self.matches.get_one("until")
+    }
+
+    /// Store sub directory to manage recipients for.
+    pub fn path(&self) -> Option<&String> {
+        self.matches.get_one("path")
+    }
+
     /// Whether to allow a dirty repository for syncing.
     pub fn allow_dirty(&self) -> bool {
         ArgAllowDirty::is_present(self.matches)
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, CmdArgFlag};
+
+/// The recipients group add command matcher.
+pub struct AddMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> AddMatcher<'a> {
+    /// Name of the group.
+    pub fn group(&self) -> &String {
+        self.matches.get_one("GROUP").unwrap()
+    }
+
+    /// Query for the key to add.
+    pub fn key(&self) -> Option<&String> {
+        self.matches.get_one("KEY")
+    }
+
+    /// Check whether to skip re-encrypting secrets.
+    pub fn no_recrypt(&self) -> bool {
+        self.matches.get_flag("no-recrypt")
+    }
+
+    /// Number of secrets to re-encrypt in parallel.
+    pub fn jobs(&self) -> Result<usize> {
+        ArgJobs::value_or_default(self.matches)
+    }
+
+    /// Whether to allow a dirty repository for syncing.
+    pub fn allow_dirty(&self) -> bool {
+        ArgAllowDirty::is_present(self.matches)
+    }
+
+    /// Whether to not sync.
+    pub fn no_sync(&self) -> bool {
+        ArgNoSync::is_present(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for AddMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("recipients")?
+            .subcommand_matches("group")?
+            .subcommand_matches("add")
+            .map(|matches| AddMatcher { matches })
+    }
+}
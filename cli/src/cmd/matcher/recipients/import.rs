@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArgFlag};
+
+/// The recipients import command matcher.
+pub struct ImportMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> ImportMatcher<'a> {
+    /// Key file or directory to import from.
+    pub fn source(&self) -> PathBuf {
+        PathBuf::from(self.matches.get_one::<String>("PATH").unwrap())
+    }
+
+    /// Check whether to skip re-encrypting secrets.
+    pub fn no_recrypt(&self) -> bool {
+        self.matches.get_flag("no-recrypt")
+    }
+
+    /// Store sub directory to manage recipients for.
+    pub fn path(&self) -> Option<&String> {
+        self.matches.get_one("path")
+    }
+
+    /// Whether to allow a dirty repository for syncing.
+    pub fn allow_dirty(&self) -> bool {
+        ArgAllowDirty::is_present(self.matches)
+    }
+
+    /// Whether to not sync.
+    pub fn no_sync(&self) -> bool {
+        ArgNoSync::is_present(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for ImportMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("recipients")?
+            .subcommand_matches("import")
+            .map(|matches| ImportMatcher { matches })
+    }
+}
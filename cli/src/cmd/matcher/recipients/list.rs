@@ -4,16 +4,21 @@ use super::Matcher;
 
 /// The recipients list command matcher.
 pub struct ListMatcher<'a> {
-    _matches: &'a ArgMatches,
+    matches: &'a ArgMatches,
 }
 
-impl<'a: 'b, 'b> ListMatcher<'a> {}
+impl<'a: 'b, 'b> ListMatcher<'a> {
+    /// Store sub directory to list recipients for.
+    pub fn path(&self) -> Option<&String> {
+        self.matches.get_one("path")
+    }
+}
 
 impl<'a> Matcher<'a> for ListMatcher<'a> {
     fn with(matches: &'a ArgMatches) -> Option<Self> {
         matches
             .subcommand_matches("recipients")?
             .subcommand_matches("list")
-            .map(|matches| ListMatcher { _matches: matches })
+            .map(|matches| ListMatcher { matches })
     }
 }
@@ -1,8 +1,14 @@
+pub mod access;
 pub mod add;
 pub mod export;
 pub mod generate;
+pub mod group;
+pub mod import;
 pub mod list;
 pub mod remove;
+pub mod replace;
+pub mod request;
+pub mod requests;
 
 use clap::ArgMatches;
 
@@ -15,6 +21,11 @@ pub struct RecipientsMatcher<'a> {
 }
 
 impl<'a: 'b, 'b> RecipientsMatcher<'a> {
+    /// Get the recipient access sub command, if matched.
+    pub fn cmd_access(&'a self) -> Option<access::AccessMatcher> {
+        access::AccessMatcher::with(self.root)
+    }
+
     /// Get the recipient add sub command, if matched.
     pub fn cmd_add(&'a self) -> Option<add::AddMatcher> {
         add::AddMatcher::with(self.root)
@@ -30,6 +41,16 @@ impl<'a: 'b, 'b> RecipientsMatcher<'a> {
         generate::GenerateMatcher::with(self.root)
     }
 
+    /// Get the recipient group sub command, if matched.
+    pub fn cmd_group(&'a self) -> Option<group::GroupMatcher> {
+        group::GroupMatcher::with(self.root)
+    }
+
+    /// Get the recipient import sub command, if matched.
+    pub fn cmd_import(&'a self) -> Option<import::ImportMatcher> {
+        import::ImportMatcher::with(self.root)
+    }
+
     /// Get the recipient list sub command, if matched.
     pub fn cmd_list(&'a self) -> Option<list::ListMatcher> {
         list::ListMatcher::with(self.root)
@@ -39,6 +60,21 @@ impl<'a: 'b, 'b> RecipientsMatcher<'a> {
     pub fn cmd_remove(&'a self) -> Option<remove::RemoveMatcher> {
         remove::RemoveMatcher::with(self.root)
     }
+
+    /// Get the recipient replace sub command, if matched.
+    pub fn cmd_replace(&'a self) -> Option<replace::ReplaceMatcher> {
+        replace::ReplaceMatcher::with(self.root)
+    }
+
+    /// Get the recipient request sub command, if matched.
+    pub fn cmd_request(&'a self) -> Option<request::RequestMatcher> {
+        request::RequestMatcher::with(self.root)
+    }
+
+    /// Get the recipient requests sub command, if matched.
+    pub fn cmd_requests(&'a self) -> Option<requests::RequestsMatcher> {
+        requests::RequestsMatcher::with(self.root)
+    }
 }
 
 impl<'a> Matcher<'a> for RecipientsMatcher<'a> {
@@ -14,6 +14,11 @@ impl<'a: 'b, 'b> RemoveMatcher<'a> {
         self.matches.get_flag("recrypt")
     }
 
+    /// Store sub directory to manage recipients for.
+    pub fn path(&self) -> Option<&String> {
+        self.matches.get_one("path")
+    }
+
     /// Whether to allow a dirty repository for syncing.
     pub fn allow_dirty(&self) -> bool {
         ArgAllowDirty::is_present(self.matches)
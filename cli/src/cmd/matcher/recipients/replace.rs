@@ -0,0 +1,51 @@
+use anyhow::Result;
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, CmdArgFlag};
+
+/// The recipients replace command matcher.
+pub struct ReplaceMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> ReplaceMatcher<'a> {
+    /// Query for the recipient key to replace.
+    pub fn query_old(&self) -> Option<&String> {
+        self.matches.get_one("OLD")
+    }
+
+    /// Query for the key to replace it with.
+    pub fn query_new(&self) -> Option<&String> {
+        self.matches.get_one("NEW")
+    }
+
+    /// Check whether to skip re-encrypting secrets.
+    pub fn no_recrypt(&self) -> bool {
+        self.matches.get_flag("no-recrypt")
+    }
+
+    /// Number of secrets to re-encrypt in parallel.
+    pub fn jobs(&self) -> Result<usize> {
+        ArgJobs::value_or_default(self.matches)
+    }
+
+    /// Whether to allow a dirty repository for syncing.
+    pub fn allow_dirty(&self) -> bool {
+        ArgAllowDirty::is_present(self.matches)
+    }
+
+    /// Whether to not sync.
+    pub fn no_sync(&self) -> bool {
+        ArgNoSync::is_present(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for ReplaceMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("recipients")?
+            .subcommand_matches("replace")
+            .map(|matches| ReplaceMatcher { matches })
+    }
+}
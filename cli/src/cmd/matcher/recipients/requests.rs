@@ -0,0 +1,50 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArgFlag};
+
+/// The recipients requests command matcher.
+pub struct RequestsMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> RequestsMatcher<'a> {
+    /// Fingerprint of request to approve.
+    pub fn approve(&self) -> Option<&String> {
+        self.matches.get_one("approve")
+    }
+
+    /// Fingerprint of request to deny.
+    pub fn deny(&self) -> Option<&String> {
+        self.matches.get_one("deny")
+    }
+
+    /// Check whether to skip re-encrypting secrets.
+    pub fn no_recrypt(&self) -> bool {
+        self.matches.get_flag("no-recrypt")
+    }
+
+    /// Store sub directory to add approved recipient to.
+    pub fn path(&self) -> Option<&String> {
+        self.matches.get_one("path")
+    }
+
+    /// Whether to allow a dirty repository for syncing.
+    pub fn allow_dirty(&self) -> bool {
+        ArgAllowDirty::is_present(self.matches)
+    }
+
+    /// Whether to not sync.
+    pub fn no_sync(&self) -> bool {
+        ArgNoSync::is_present(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for RequestsMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("recipients")?
+            .subcommand_matches("requests")
+            .map(|matches| RequestsMatcher { matches })
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(feature = "clipboard")]
+use anyhow::Result;
+use clap::ArgMatches;
+
+use super::{
+    generate::{PASSPHRASE_LENGTH, PASSWORD_LENGTH},
+    Matcher,
+};
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgTimeout;
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArgFlag, CmdArgOption};
+
+/// The set command matcher.
+pub struct SetMatcher<'a> {
+    matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> SetMatcher<'a> {
+    /// The secret query.
+    pub fn query(&self) -> Option<String> {
+        ArgQuery::value(self.matches)
+    }
+
+    /// Field to set.
+    pub fn field(&self) -> &String {
+        self.matches.get_one("FIELD").unwrap()
+    }
+
+    /// Value to set, if given.
+    pub fn value(&self) -> Option<&String> {
+        self.matches.get_one("VALUE")
+    }
+
+    /// Check whether to read from stdin.
+    pub fn stdin(&self) -> bool {
+        self.matches.get_flag("stdin")
+    }
+
+    /// Check whether to generate the value.
+    pub fn generate(&self) -> bool {
+        self.matches.get_flag("generate")
+    }
+
+    /// Check whether to generate a passphrase.
+    pub fn passphrase(&self) -> bool {
+        self.matches.get_flag("passphrase")
+    }
+
+    /// What length to use.
+    pub fn length(&self) -> u16 {
+        self.matches.get_one("length").copied().unwrap_or_else(|| {
+            if self.passphrase() {
+                PASSPHRASE_LENGTH
+            } else {
+                PASSWORD_LENGTH
+            }
+        })
+    }
+
+    /// Check whether to copy the generated value.
+    #[cfg(feature = "clipboard")]
+    pub fn copy(&self) -> bool {
+        self.matches.get_flag("copy")
+    }
+
+    /// Clipboard timeout in seconds.
+    #[cfg(feature = "clipboard")]
+    pub fn timeout(&self) -> Result<u64> {
+        ArgTimeout::value_or_default(self.matches)
+    }
+
+    /// Check whether to show the generated value.
+    pub fn show(&self) -> bool {
+        self.matches.get_flag("show")
+    }
+
+    /// Whether to allow a dirty repository for syncing.
+    pub fn allow_dirty(&self) -> bool {
+        ArgAllowDirty::is_present(self.matches)
+    }
+
+    /// Whether to not sync.
+    pub fn no_sync(&self) -> bool {
+        ArgNoSync::is_present(self.matches)
+    }
+}
+
+impl<'a> Matcher<'a> for SetMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("set")
+            .map(|matches| SetMatcher { matches })
+    }
+}
@@ -30,6 +30,11 @@ impl<'a: 'b, 'b> ShowMatcher<'a> {
         ArgProperty::value(self.matches)
     }
 
+    /// Revision to show the secret at, if set.
+    pub fn rev(&self) -> Option<&String> {
+        self.matches.get_one("rev")
+    }
+
     /// Check whether to read from copy.
     #[cfg(feature = "clipboard")]
     pub fn copy(&self) -> bool {
@@ -0,0 +1,17 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The trash list command matcher.
+pub struct ListMatcher<'a> {
+    _matches: &'a ArgMatches,
+}
+
+impl<'a> Matcher<'a> for ListMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("trash")?
+            .subcommand_matches("list")
+            .map(|matches| ListMatcher { _matches: matches })
+    }
+}
@@ -0,0 +1,40 @@
+pub mod empty;
+pub mod list;
+pub mod restore;
+
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The trash command matcher.
+pub struct TrashMatcher<'a> {
+    root: &'a ArgMatches,
+    _matches: &'a ArgMatches,
+}
+
+impl<'a: 'b, 'b> TrashMatcher<'a> {
+    /// Get the trash list sub command, if matched.
+    pub fn cmd_list(&'a self) -> Option<list::ListMatcher> {
+        list::ListMatcher::with(self.root)
+    }
+
+    /// Get the trash restore sub command, if matched.
+    pub fn cmd_restore(&'a self) -> Option<restore::RestoreMatcher> {
+        restore::RestoreMatcher::with(self.root)
+    }
+
+    /// Get the trash empty sub command, if matched.
+    pub fn cmd_empty(&'a self) -> Option<empty::EmptyMatcher> {
+        empty::EmptyMatcher::with(self.root)
+    }
+}
+
+impl<'a> Matcher<'a> for TrashMatcher<'a> {
+    fn with(root: &'a ArgMatches) -> Option<Self> {
+        root.subcommand_matches("trash")
+            .map(|matches| TrashMatcher {
+                root,
+                _matches: matches,
+            })
+    }
+}
@@ -1,6 +1,6 @@
 use clap::{Arg, Command};
 
-use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
+use crate::cmd::arg::{ArgAllowDirty, ArgField, ArgNoSync, ArgTemplate, CmdArg};
 
 /// The add command definition.
 pub struct CmdAdd;
@@ -32,6 +32,12 @@ impl CmdAdd {
                     .help("Read secret from stdin, do not open editor")
                     .conflicts_with("empty"),
             )
+            .arg(
+                ArgTemplate::build()
+                    .conflicts_with("empty")
+                    .conflicts_with("stdin"),
+            )
+            .arg(ArgField::build())
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build())
     }
@@ -0,0 +1,12 @@
+use clap::Command;
+
+/// The agent clear command definition.
+pub struct CmdClear;
+
+impl CmdClear {
+    pub fn build() -> Command {
+        Command::new("clear")
+            .alias("flush")
+            .about("Drop all cached secrets")
+    }
+}
@@ -0,0 +1,35 @@
+pub mod clear;
+pub mod run;
+#[cfg(target_os = "linux")]
+pub mod start;
+pub mod status;
+pub mod stop;
+
+use clap::Command;
+
+/// The agent command definition.
+pub struct CmdAgent;
+
+impl CmdAgent {
+    pub fn build() -> Command {
+        let cmd = Command::new("agent")
+            .about("Manage decryption cache agent")
+            .long_about(
+                "Manage decryption cache agent.\n\n\
+                The agent caches decrypted secrets in locked memory for a short time, so reading \
+                the same secrets again does not invoke GPG. It is used automatically while it is \
+                running. The cache is cleared on slam and when the screen is locked.",
+            )
+            .arg_required_else_help(true)
+            .subcommand_required(true)
+            .subcommand_value_name("CMD")
+            .subcommand(run::CmdRun::build());
+
+        #[cfg(target_os = "linux")]
+        let cmd = cmd.subcommand(start::CmdStart::build());
+
+        cmd.subcommand(stop::CmdStop::build())
+            .subcommand(status::CmdStatus::build())
+            .subcommand(clear::CmdClear::build())
+    }
+}
@@ -0,0 +1,22 @@
+use clap::{Arg, Command};
+
+/// The agent run command definition.
+pub struct CmdRun;
+
+impl CmdRun {
+    pub fn build() -> Command {
+        Command::new("run")
+            .about("Run agent in the foreground")
+            .arg(arg_ttl())
+    }
+}
+
+/// The time to live argument, shared with the start command.
+pub fn arg_ttl() -> Arg {
+    Arg::new("ttl")
+        .long("ttl")
+        .short('t')
+        .value_name("TIME")
+        .num_args(1)
+        .help("Time to cache decrypted secrets for [default: 2m]")
+}
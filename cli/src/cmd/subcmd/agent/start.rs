@@ -0,0 +1,12 @@
+use clap::Command;
+
+/// The agent start command definition.
+pub struct CmdStart;
+
+impl CmdStart {
+    pub fn build() -> Command {
+        Command::new("start")
+            .about("Start agent as systemd user service")
+            .arg(super::run::arg_ttl())
+    }
+}
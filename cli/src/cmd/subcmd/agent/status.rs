@@ -0,0 +1,10 @@
+use clap::Command;
+
+/// The agent status command definition.
+pub struct CmdStatus;
+
+impl CmdStatus {
+    pub fn build() -> Command {
+        Command::new("status").about("Show agent status")
+    }
+}
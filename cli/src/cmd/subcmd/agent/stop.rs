@@ -0,0 +1,10 @@
+use clap::Command;
+
+/// The agent stop command definition.
+pub struct CmdStop;
+
+impl CmdStop {
+    pub fn build() -> Command {
+        Command::new("stop").about("Stop agent, drop cached secrets")
+    }
+}
@@ -2,7 +2,7 @@ use clap::{Arg, Command};
 
 #[cfg(feature = "clipboard")]
 use crate::cmd::arg::ArgTimeout;
-use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
+use crate::cmd::arg::{ArgAllowDirty, ArgField, ArgNoSync, ArgTemplate, CmdArg};
 
 /// The generate command definition.
 pub struct CmdGenerate;
@@ -72,6 +72,8 @@ impl CmdGenerate {
                     .num_args(0)
                     .help("Display secret after generation"),
             )
+            .arg(ArgTemplate::build().conflicts_with("merge"))
+            .arg(ArgField::build())
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build());
 
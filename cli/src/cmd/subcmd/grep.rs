@@ -1,6 +1,6 @@
 use clap::{Arg, Command};
 
-use crate::cmd::arg::{ArgQuery, CmdArg};
+use crate::cmd::arg::{ArgJobs, ArgQuery, CmdArg};
 
 /// The grep command definition.
 pub struct CmdGrep;
@@ -36,5 +36,6 @@ impl CmdGrep {
                     .num_args(0)
                     .help("Include grepping aliases"),
             )
+            .arg(ArgJobs::build())
     }
 }
@@ -0,0 +1,21 @@
+use clap::Command;
+
+use crate::cmd::arg::{ArgQuery, CmdArg};
+
+/// The history command definition.
+pub struct CmdHistory;
+
+impl CmdHistory {
+    pub fn build() -> Command {
+        Command::new("history")
+            .alias("log")
+            .alias("hist")
+            .about("Show change history of a secret")
+            .long_about(
+                "Show change history of a secret.\n\n\
+                 Lists the commits that changed the secret, newest first. Removed secrets can be \
+                 queried by their full name.",
+            )
+            .arg(ArgQuery::build())
+    }
+}
@@ -0,0 +1,21 @@
+use clap::Command;
+
+use crate::cmd::arg::{ArgQuery, CmdArg};
+
+/// The housekeeping audit command definition.
+pub struct CmdAudit;
+
+impl CmdAudit {
+    pub fn build() -> Command {
+        Command::new("audit")
+            .about("Find secrets with recipients differing from store recipients")
+            .long_about(
+                "Find secrets with recipients differing from store recipients.\n\n\
+                Recipients are read from encrypted secrets without decrypting them. Each \
+                drifted recipient is printed on a line with the secret name, a + for missing \
+                or - for extra recipients, and the fingerprint or key ID, separated by tabs. \
+                Exits with a non-zero status if any secret has drifted.",
+            )
+            .arg(ArgQuery::build())
+    }
+}
@@ -0,0 +1,36 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArg};
+
+/// The housekeeping convert command definition.
+pub struct CmdConvert;
+
+impl CmdConvert {
+    pub fn build() -> Command {
+        Command::new("convert")
+            .about("Convert secrets between ASCII armored and binary format")
+            .long_about(
+                "Convert secrets between ASCII armored and binary format.\n\n\
+                Secrets are converted to the format set with the 'armor' store option, unless \
+                --armor or --binary is given. Armored secrets use the .asc suffix, binary \
+                secrets use .gpg. Secrets are not decrypted, and aliases are updated.",
+            )
+            .arg(
+                Arg::new("armor")
+                    .long("armor")
+                    .alias("ascii")
+                    .num_args(0)
+                    .help("Convert to ASCII armored format"),
+            )
+            .arg(
+                Arg::new("binary")
+                    .long("binary")
+                    .num_args(0)
+                    .help("Convert to binary format")
+                    .conflicts_with("armor"),
+            )
+            .arg(ArgQuery::build())
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -1,3 +1,5 @@
+pub mod audit;
+pub mod convert;
 pub mod recrypt;
 pub mod run;
 pub mod sync_keys;
@@ -19,5 +21,7 @@ impl CmdHousekeeping {
             .subcommand(run::CmdRun::build())
             .subcommand(recrypt::CmdRecrypt::build())
             .subcommand(sync_keys::CmdSyncKeys::build())
+            .subcommand(audit::CmdAudit::build())
+            .subcommand(convert::CmdConvert::build())
     }
 }
@@ -1,6 +1,6 @@
 use clap::{Arg, Command};
 
-use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArg};
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, ArgQuery, CmdArg};
 
 /// The housekeeping recrypt command definition.
 pub struct CmdRecrypt;
@@ -18,7 +18,25 @@ impl CmdRecrypt {
                     .help("Re-encrypt all secrets")
                     .conflicts_with("QUERY"),
             )
-            .arg(ArgQuery::build().required_unless_present("all"))
+            .arg(
+                Arg::new("only-drifted")
+                    .long("only-drifted")
+                    .alias("drifted")
+                    .num_args(0)
+                    .help(
+                        "Only re-encrypt secrets with drifted recipients, see 'housekeeping audit'",
+                    ),
+            )
+            .arg(
+                Arg::new("resume")
+                    .long("resume")
+                    .alias("continue")
+                    .num_args(0)
+                    .help("Resume an interrupted re-encryption")
+                    .conflicts_with_all(["all", "only-drifted", "QUERY"]),
+            )
+            .arg(ArgQuery::build().required_unless_present_any(["all", "only-drifted", "resume"]))
+            .arg(ArgJobs::build())
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build())
     }
@@ -1,7 +1,10 @@
-use clap::Command;
+use clap::{Arg, Command};
 
 use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
 
+/// Default window to warn about expiring recipient keys in.
+pub const EXPIRY_WINDOW_DEFAULT: &str = "4w";
+
 /// The housekeeping run command definition.
 pub struct CmdRun;
 
@@ -9,6 +12,15 @@ impl CmdRun {
     pub fn build() -> Command {
         Command::new("run")
             .about("Run housekeeping tasks")
+            .arg(
+                Arg::new("expiry-window")
+                    .long("expiry-window")
+                    .alias("expiry-warn")
+                    .value_name("DURATION")
+                    .num_args(1)
+                    .default_value(EXPIRY_WINDOW_DEFAULT)
+                    .help("Warn about recipient keys expiring within this time (e.g. 30d, 4w)"),
+            )
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build())
     }
@@ -1,4 +1,4 @@
-use clap::Command;
+use clap::{Arg, Command};
 
 use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
 
@@ -10,6 +10,16 @@ impl CmdInit {
         Command::new("init")
             .alias("initialize")
             .about("Initialize new password store")
+            .arg(
+                Arg::new("proto")
+                    .long("proto")
+                    .alias("protocol")
+                    .value_name("PROTO")
+                    .num_args(1)
+                    .value_parser(["gpg", "age"])
+                    .default_value("gpg")
+                    .help("Cryptography protocol to use for the store"),
+            )
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build())
     }
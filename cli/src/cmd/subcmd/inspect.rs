@@ -0,0 +1,20 @@
+use clap::Command;
+
+use crate::cmd::arg::{ArgQuery, CmdArg};
+
+/// The inspect command definition.
+pub struct CmdInspect;
+
+impl CmdInspect {
+    pub fn build() -> Command {
+        Command::new("inspect")
+            .about("Inspect recipients a secret is encrypted for")
+            .long_about(
+                "Inspect recipients a secret is encrypted for, without decrypting it.\n\n\
+                Compares the recipients in the secret with the current store recipients. \
+                Missing recipients are marked with '+', extra recipients that should no \
+                longer have access are marked with '-'.",
+            )
+            .arg(ArgQuery::build())
+    }
+}
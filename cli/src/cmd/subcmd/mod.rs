@@ -1,4 +1,6 @@
 pub mod add;
+#[cfg(unix)]
+pub mod agent;
 #[cfg(feature = "alias")]
 pub mod alias;
 pub mod clone;
@@ -9,13 +11,17 @@ pub mod edit;
 pub mod generate;
 pub mod git;
 pub mod grep;
+pub mod history;
 pub mod housekeeping;
 pub mod init;
+pub mod inspect;
 pub mod internal;
 pub mod list;
 pub mod r#move;
 pub mod recipients;
 pub mod remove;
+pub mod restore;
+pub mod set;
 pub mod show;
 pub mod slam;
 pub mod sync;
@@ -23,9 +29,13 @@ pub mod sync;
 pub mod tomb;
 #[cfg(feature = "totp")]
 pub mod totp;
+pub mod trash;
+pub mod unset;
 
 // Re-export to cmd module
 pub use self::add::CmdAdd;
+#[cfg(unix)]
+pub use self::agent::CmdAgent;
 #[cfg(feature = "alias")]
 pub use self::alias::CmdAlias;
 pub use self::clone::CmdClone;
@@ -36,13 +46,17 @@ pub use self::edit::CmdEdit;
 pub use self::generate::CmdGenerate;
 pub use self::git::CmdGit;
 pub use self::grep::CmdGrep;
+pub use self::history::CmdHistory;
 pub use self::housekeeping::CmdHousekeeping;
 pub use self::init::CmdInit;
+pub use self::inspect::CmdInspect;
 pub use self::internal::CmdInternal;
 pub use self::list::CmdList;
 pub use self::r#move::CmdMove;
 pub use self::recipients::CmdRecipients;
 pub use self::remove::CmdRemove;
+pub use self::restore::CmdRestore;
+pub use self::set::CmdSet;
 pub use self::show::CmdShow;
 pub use self::slam::CmdSlam;
 pub use self::sync::CmdSync;
@@ -50,3 +64,5 @@ pub use self::sync::CmdSync;
 pub use self::tomb::CmdTomb;
 #[cfg(feature = "totp")]
 pub use self::totp::CmdTotp;
+pub use self::trash::CmdTrash;
+pub use self::unset::CmdUnset;
@@ -0,0 +1,22 @@
+use clap::{Arg, Command};
+
+/// The recipient access command definition.
+pub struct CmdAccess;
+
+impl CmdAccess {
+    pub fn build() -> Command {
+        Command::new("access")
+            .alias("acl")
+            .about("Report which secrets each recipient can read")
+            .long_about(
+                "Report which secrets each recipient can read.\n\n\
+                 Reads the recipient key IDs from the ciphertext of each secret, secrets are not \
+                 decrypted. Lists the secrets of each recipient, or of a single key if given. \
+                 Keys that secrets are encrypted for but that are not in the keychain are listed \
+                 by key ID.\n\n\
+                 With --quiet, a line is printed for each recipient and secret as: \
+                 FINGERPRINT<TAB>SECRET",
+            )
+            .arg(Arg::new("KEY").help("Key to report on, fingerprint or user ID"))
+    }
+}
@@ -26,6 +26,34 @@ impl CmdAdd {
                     .num_args(0)
                     .help("Skip re-encrypting all secrets"),
             )
+            .arg(
+                Arg::new("group")
+                    .long("group")
+                    .short('g')
+                    .value_name("GROUP")
+                    .num_args(1)
+                    .conflicts_with("secret")
+                    .help("Add recipient group instead of a key"),
+            )
+            .arg(
+                Arg::new("until")
+                    .long("until")
+                    .alias("expire")
+                    .alias("expires")
+                    .value_name("DATE")
+                    .num_args(1)
+                    .conflicts_with("group")
+                    .help("Remove recipient after date (YYYY-MM-DD) in housekeeping run"),
+            )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .alias("dir")
+                    .value_name("PATH")
+                    .num_args(1)
+                    .help("Add recipient to store sub directory"),
+            )
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build())
     }
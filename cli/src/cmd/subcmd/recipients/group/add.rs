@@ -0,0 +1,32 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, CmdArg};
+
+/// The recipients group add command definition.
+pub struct CmdAdd;
+
+impl CmdAdd {
+    pub fn build() -> Command {
+        Command::new("add")
+            .alias("a")
+            .about("Add key to recipient group")
+            .arg(
+                Arg::new("GROUP")
+                    .required(true)
+                    .help("Group to add key to, created if it doesn't exist"),
+            )
+            .arg(Arg::new("KEY").help("Key to add, fingerprint or user ID"))
+            .arg(
+                Arg::new("no-recrypt")
+                    .long("no-recrypt")
+                    .alias("no-reencrypt")
+                    .alias("skip-recrypt")
+                    .alias("skip-reencrypt")
+                    .num_args(0)
+                    .help("Skip re-encrypting secrets"),
+            )
+            .arg(ArgJobs::build())
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,14 @@
+use clap::{Arg, Command};
+
+/// The recipients group list command definition.
+pub struct CmdList;
+
+impl CmdList {
+    pub fn build() -> Command {
+        Command::new("list")
+            .alias("ls")
+            .alias("l")
+            .about("List recipient groups")
+            .arg(Arg::new("GROUP").help("Group to list members of"))
+    }
+}
@@ -0,0 +1,29 @@
+pub mod add;
+pub mod list;
+pub mod remove;
+
+use clap::Command;
+
+/// The recipients group command definition.
+pub struct CmdGroup;
+
+impl CmdGroup {
+    pub fn build() -> Command {
+        Command::new("group")
+            .about("Manage recipient groups")
+            .long_about(
+                "Manage recipient groups.\n\n\
+                 Groups are defined in .prs/groups.toml of the store. Store directories use a \
+                 group by referencing it as @NAME in their .gpg-id file, see 'recipients add \
+                 --group'.",
+            )
+            .alias("groups")
+            .alias("grp")
+            .arg_required_else_help(true)
+            .subcommand_required(true)
+            .subcommand_value_name("CMD")
+            .subcommand(add::CmdAdd::build())
+            .subcommand(list::CmdList::build())
+            .subcommand(remove::CmdRemove::build())
+    }
+}
@@ -0,0 +1,37 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, CmdArg};
+
+/// The recipients group remove command definition.
+pub struct CmdRemove;
+
+impl CmdRemove {
+    pub fn build() -> Command {
+        Command::new("remove")
+            .alias("rm")
+            .alias("delete")
+            .alias("del")
+            .about("Remove key from recipient group, or remove group")
+            .arg(
+                Arg::new("GROUP")
+                    .required(true)
+                    .help("Group to remove from"),
+            )
+            .arg(
+                Arg::new("KEY")
+                    .help("Key to remove, fingerprint or user ID, removes unused group if not set"),
+            )
+            .arg(
+                Arg::new("no-recrypt")
+                    .long("no-recrypt")
+                    .alias("no-reencrypt")
+                    .alias("skip-recrypt")
+                    .alias("skip-reencrypt")
+                    .num_args(0)
+                    .help("Skip re-encrypting secrets"),
+            )
+            .arg(ArgJobs::build())
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,47 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
+
+/// The recipient import command definition.
+pub struct CmdImport;
+
+impl CmdImport {
+    pub fn build() -> Command {
+        Command::new("import")
+            .alias("imp")
+            .alias("i")
+            .about("Import store recipients from key files")
+            .long_about(
+                "Import store recipients from key files.\n\n\
+                 Accepts a file with armored or binary public keys, or a directory to search for \
+                 key files. A local web key directory (WKD) layout, such as \
+                 .well-known/openpgpkey/hu/, may be given as offline alternative for a WKD \
+                 lookup. Each key is shown for confirmation before it is added.",
+            )
+            .arg(
+                Arg::new("PATH")
+                    .help("Key file or directory to import from")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("no-recrypt")
+                    .long("no-recrypt")
+                    .alias("no-reencrypt")
+                    .alias("skip-recrypt")
+                    .alias("skip-reencrypt")
+                    .num_args(0)
+                    .help("Skip re-encrypting all secrets"),
+            )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .alias("dir")
+                    .value_name("PATH")
+                    .num_args(1)
+                    .help("Add recipients to store sub directory"),
+            )
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -1,4 +1,4 @@
-use clap::Command;
+use clap::{Arg, Command};
 
 /// The recipient list command definition.
 pub struct CmdList;
@@ -9,5 +9,14 @@ impl CmdList {
             .alias("ls")
             .alias("l")
             .about("List store recipients")
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .alias("dir")
+                    .value_name("PATH")
+                    .num_args(1)
+                    .help("List recipients for store sub directory"),
+            )
     }
 }
@@ -1,8 +1,14 @@
+pub mod access;
 pub mod add;
 pub mod export;
 pub mod generate;
+pub mod group;
+pub mod import;
 pub mod list;
 pub mod remove;
+pub mod replace;
+pub mod request;
+pub mod requests;
 
 use clap::Command;
 
@@ -24,7 +30,13 @@ impl CmdRecipients {
             .subcommand(add::CmdAdd::build())
             .subcommand(generate::CmdGenerate::build())
             .subcommand(list::CmdList::build())
+            .subcommand(access::CmdAccess::build())
             .subcommand(remove::CmdRemove::build())
+            .subcommand(replace::CmdReplace::build())
+            .subcommand(request::CmdRequest::build())
+            .subcommand(requests::CmdRequests::build())
+            .subcommand(import::CmdImport::build())
             .subcommand(export::CmdExport::build())
+            .subcommand(group::CmdGroup::build())
     }
 }
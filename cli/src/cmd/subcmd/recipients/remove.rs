@@ -19,6 +19,15 @@ impl CmdRemove {
                     .num_args(0)
                     .help("Re-encrypting all secrets"),
             )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .alias("dir")
+                    .value_name("PATH")
+                    .num_args(1)
+                    .help("Remove recipient from store sub directory"),
+            )
             .arg(ArgAllowDirty::build())
             .arg(ArgNoSync::build())
     }
@@ -0,0 +1,35 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgJobs, ArgNoSync, CmdArg};
+
+/// The recipient replace command definition.
+pub struct CmdReplace;
+
+impl CmdReplace {
+    pub fn build() -> Command {
+        Command::new("replace")
+            .alias("rotate")
+            .alias("swap")
+            .about("Replace store recipient with another key")
+            .long_about(
+                "Replace store recipient with another key.\n\n\
+                 The old key is replaced with the new key in all recipient files it is listed in, \
+                 and only secrets encrypted for the old key are re-encrypted. Keys may be \
+                 selected by fingerprint or user ID, or interactively if not given.",
+            )
+            .arg(Arg::new("OLD").help("Recipient key to replace, fingerprint or user ID"))
+            .arg(Arg::new("NEW").help("Key to replace it with, fingerprint or user ID"))
+            .arg(
+                Arg::new("no-recrypt")
+                    .long("no-recrypt")
+                    .alias("no-reencrypt")
+                    .alias("skip-recrypt")
+                    .alias("skip-reencrypt")
+                    .num_args(0)
+                    .help("Skip re-encrypting secrets"),
+            )
+            .arg(ArgJobs::build())
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
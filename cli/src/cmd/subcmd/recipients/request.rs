@@ -0,0 +1,22 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
+
+/// The recipient request command definition.
+pub struct CmdRequest;
+
+impl CmdRequest {
+    pub fn build() -> Command {
+        Command::new("request")
+            .alias("req")
+            .about("Request to become store recipient")
+            .long_about(
+                "Request to become store recipient.\n\n\
+                 Adds the public key of your own key to the store as pending request. An existing \
+                 recipient must approve it with the 'recipients requests' command.",
+            )
+            .arg(Arg::new("QUERY").help("Own key to request with, fingerprint or user ID"))
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,53 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
+
+/// The recipient requests command definition.
+pub struct CmdRequests;
+
+impl CmdRequests {
+    pub fn build() -> Command {
+        Command::new("requests")
+            .alias("reqs")
+            .about("List, approve or deny recipient requests")
+            .arg(
+                Arg::new("approve")
+                    .long("approve")
+                    .alias("accept")
+                    .value_name("FINGERPRINT")
+                    .num_args(1)
+                    .conflicts_with("deny")
+                    .help("Approve request, add key as recipient"),
+            )
+            .arg(
+                Arg::new("deny")
+                    .long("deny")
+                    .alias("reject")
+                    .value_name("FINGERPRINT")
+                    .num_args(1)
+                    .help("Deny request, remove it from store"),
+            )
+            .arg(
+                Arg::new("no-recrypt")
+                    .long("no-recrypt")
+                    .alias("no-reencrypt")
+                    .alias("skip-recrypt")
+                    .alias("skip-reencrypt")
+                    .num_args(0)
+                    .requires("approve")
+                    .help("Skip re-encrypting all secrets"),
+            )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .alias("dir")
+                    .value_name("PATH")
+                    .num_args(1)
+                    .requires("approve")
+                    .help("Add approved recipient to store sub directory"),
+            )
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,32 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArg};
+
+/// The restore command definition.
+pub struct CmdRestore;
+
+impl CmdRestore {
+    pub fn build() -> Command {
+        Command::new("restore")
+            .alias("revert")
+            .about("Restore a secret from history")
+            .long_about(
+                "Restore a secret from history.\n\n\
+                 Restores the secret as it was at the given revision, re-encrypted for the current \
+                 recipients. Removed secrets can be restored by their full name.",
+            )
+            .arg(ArgQuery::build().required(true))
+            .arg(
+                Arg::new("rev")
+                    .long("rev")
+                    .short('r')
+                    .alias("revision")
+                    .value_name("REV")
+                    .num_args(1)
+                    .required(true)
+                    .help("Revision to restore, see the history command"),
+            )
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,100 @@
+use clap::{Arg, Command};
+
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgTimeout;
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArg};
+
+/// The set command definition.
+pub struct CmdSet;
+
+impl CmdSet {
+    pub fn build() -> Command {
+        let cmd = Command::new("set")
+            .alias("set-field")
+            .about("Set a field in a secret")
+            .long_about(
+                "Set a field in a secret.\n\n\
+                 Changes only the given field, keeping the rest of the secret as-is. Fields are \
+                 pass-style 'key: value' properties, or YAML fields if the secret has a '---' \
+                 separator line.",
+            )
+            .arg(ArgQuery::build().required(true))
+            .arg(Arg::new("FIELD").help("Field to set").required(true))
+            .arg(
+                Arg::new("VALUE")
+                    .help("Value to set")
+                    .required_unless_present_any(["stdin", "generate"]),
+            )
+            .arg(
+                Arg::new("stdin")
+                    .long("stdin")
+                    .short('S')
+                    .alias("from-stdin")
+                    .num_args(0)
+                    .conflicts_with_all(["VALUE", "generate"])
+                    .help("Read value from stdin"),
+            )
+            .arg(
+                Arg::new("generate")
+                    .long("generate")
+                    .short('g')
+                    .alias("gen")
+                    .num_args(0)
+                    .conflicts_with("VALUE")
+                    .help("Set a generated password as value"),
+            )
+            .arg(
+                Arg::new("passphrase")
+                    .long("passphrase")
+                    .short('P')
+                    .num_args(0)
+                    .requires("generate")
+                    .conflicts_with_all(["VALUE", "stdin"])
+                    .help("Generate passphrase instead of random string"),
+            )
+            .arg(
+                Arg::new("length")
+                    .value_name("NUM")
+                    .long("length")
+                    .short('l')
+                    .alias("len")
+                    .num_args(1)
+                    .value_parser(clap::value_parser!(u16).range(1..))
+                    .requires("generate")
+                    .conflicts_with_all(["VALUE", "stdin"])
+                    .help("Generated password length in characters")
+                    .long_help(
+                        "Generated password length in characters. Passphrase length in words.",
+                    ),
+            )
+            .arg(
+                Arg::new("show")
+                    .long("show")
+                    .alias("cat")
+                    .alias("display")
+                    .alias("stdout")
+                    .num_args(0)
+                    .requires("generate")
+                    .conflicts_with_all(["VALUE", "stdin"])
+                    .help("Display generated value"),
+            )
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build());
+
+        #[cfg(feature = "clipboard")]
+        let cmd = cmd
+            .arg(
+                Arg::new("copy")
+                    .long("copy")
+                    .short('c')
+                    .alias("cp")
+                    .num_args(0)
+                    .requires("generate")
+                    .conflicts_with_all(["VALUE", "stdin"])
+                    .help("Copy generated value to clipboard"),
+            )
+            .arg(ArgTimeout::build().requires("copy"));
+
+        cmd
+    }
+}
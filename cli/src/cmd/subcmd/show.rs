@@ -29,7 +29,16 @@ impl CmdShow {
                     .help("Timeout after which to clear output, implies --viewer"),
             )
             .arg(ArgProperty::build().conflicts_with("first"))
-            .arg(ArgViewer::build());
+            .arg(ArgViewer::build())
+            .arg(
+                Arg::new("rev")
+                    .long("rev")
+                    .short('r')
+                    .alias("revision")
+                    .value_name("REV")
+                    .num_args(1)
+                    .help("Show secret as it was at revision, see the history command"),
+            );
 
         #[cfg(feature = "clipboard")]
         let cmd = cmd.arg(
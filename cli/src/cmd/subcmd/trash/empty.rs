@@ -0,0 +1,16 @@
+use clap::Command;
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, CmdArg};
+
+/// The trash empty command definition.
+pub struct CmdEmpty;
+
+impl CmdEmpty {
+    pub fn build() -> Command {
+        Command::new("empty")
+            .alias("purge")
+            .about("Permanently delete all removed secrets")
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,13 @@
+use clap::Command;
+
+/// The trash list command definition.
+pub struct CmdList;
+
+impl CmdList {
+    pub fn build() -> Command {
+        Command::new("list")
+            .alias("ls")
+            .alias("l")
+            .about("List removed secrets")
+    }
+}
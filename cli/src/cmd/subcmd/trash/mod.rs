@@ -0,0 +1,28 @@
+pub mod empty;
+pub mod list;
+pub mod restore;
+
+use clap::Command;
+
+/// The trash command definition.
+pub struct CmdTrash;
+
+impl CmdTrash {
+    pub fn build() -> Command {
+        Command::new("trash")
+            .alias("bin")
+            .about("Manage removed secrets")
+            .long_about(
+                "Manage removed secrets.\n\n\
+                 Removed secrets are moved to the trash, from where they can be restored. \
+                 Housekeeping permanently deletes secrets that have been in the trash for longer \
+                 than the trash-retention store option, 30 days by default.",
+            )
+            .arg_required_else_help(true)
+            .subcommand_required(true)
+            .subcommand_value_name("CMD")
+            .subcommand(list::CmdList::build())
+            .subcommand(restore::CmdRestore::build())
+            .subcommand(empty::CmdEmpty::build())
+    }
+}
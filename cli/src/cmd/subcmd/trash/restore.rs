@@ -0,0 +1,17 @@
+use clap::Command;
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArg};
+
+/// The trash restore command definition.
+pub struct CmdRestore;
+
+impl CmdRestore {
+    pub fn build() -> Command {
+        Command::new("restore")
+            .alias("undo")
+            .about("Restore a removed secret")
+            .arg(ArgQuery::build().required(true))
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -0,0 +1,18 @@
+use clap::{Arg, Command};
+
+use crate::cmd::arg::{ArgAllowDirty, ArgNoSync, ArgQuery, CmdArg};
+
+/// The unset command definition.
+pub struct CmdUnset;
+
+impl CmdUnset {
+    pub fn build() -> Command {
+        Command::new("unset")
+            .alias("unset-field")
+            .about("Remove a field from a secret")
+            .arg(ArgQuery::build().required(true))
+            .arg(Arg::new("FIELD").help("Field to remove").required(true))
+            .arg(ArgAllowDirty::build())
+            .arg(ArgNoSync::build())
+    }
+}
@@ -1,14 +1,65 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::sync::Mutex;
+
 use crate::cmd::matcher::MainMatcher;
-use prs_lib::crypto::{self, Config, Context, Proto};
+use crate::util::error::{print_warning, quit_error, quit_error_msg, ErrorHintsBuilder};
+use prs_lib::{
+    crypto::{self, prelude::*, Config, Context, Proto},
+    store::SignPolicy,
+    Ciphertext, Plaintext, Secret, Store,
+};
 
 /// Default cryptography protocol.
 const PROTO: Proto = Proto::Gpg;
 
+/// Maximum length in bytes of a passphrase read from a file or file descriptor.
+const PASSPHRASE_MAX_LEN: usize = 4096;
+
+lazy_static! {
+    /// Passphrase read from a file or file descriptor, read once for the whole process.
+    ///
+    /// Static data is never dropped, see `forget_passphrase` to zeroize it before exiting.
+    static ref PASSPHRASE: Mutex<Option<Option<Plaintext>>> = Mutex::new(None);
+}
+
+/// Forget the passphrase read from a file or file descriptor, zeroizing it.
+///
+/// Must be called before exiting the process, see `util::error::exit`. Does nothing if the
+/// passphrase is being read right now, reading it quits on failure.
+pub(crate) fn forget_passphrase() {
+    if let Ok(mut passphrase) = PASSPHRASE.try_lock() {
+        passphrase.take();
+    }
+}
+
 /// Construct crypto config, respect CLI arguments.
+///
+/// Uses the protocol and keyring of the selected store, or the default protocol if there is no
+/// store. Quits with an error if the store options cannot be loaded.
 pub(crate) fn config(matcher_main: &MainMatcher) -> Config {
-    let mut config = Config::from(PROTO);
+    let mut config = match Store::open(matcher_main.store()) {
+        Ok(store) => crypto::store::store_crypto_config(&store).unwrap_or_else(|err| {
+            quit_error(
+                err.context("failed to load store options"),
+                ErrorHintsBuilder::from_matcher(matcher_main)
+                    .build()
+                    .unwrap(),
+            )
+        }),
+        Err(_) => Config::from(PROTO),
+    };
     config.gpg_tty = matcher_main.gpg_tty();
+    config.gpg_passphrase = PASSPHRASE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| passphrase(matcher_main))
+        .clone();
     config.verbose = matcher_main.verbose();
+    #[cfg(unix)]
+    {
+        config.agent = Some(prs_lib::agent::socket_path());
+    }
     config
 }
 
@@ -17,3 +68,179 @@ pub(crate) fn context(matcher_main: &MainMatcher) -> Result<Context, crypto::Err
     let config = config(matcher_main);
     crypto::context(&config)
 }
+
+/// Decrypt a secret, and verify its signature according to the signing policy of the store.
+///
+/// Warns, or quits with an error, if the secret is unsigned or not signed by any of its
+/// recipients. Forcing turns the error into a warning. Signatures are not checked if the store
+/// doesn't sign secrets.
+///
+/// Use this for any secret that is shown, or written back after decrypting, so secrets forged by
+/// non-recipients are never signed again by the current user.
+pub(crate) fn decrypt_secret(
+    context: &mut Context,
+    store: &Store,
+    secret: &Secret,
+    matcher_main: &MainMatcher,
+) -> anyhow::Result<Plaintext> {
+    let ciphertext = crypto::read_ciphertext_file(&secret.path)?;
+    decrypt_secret_ciphertext(context, store, secret, ciphertext, matcher_main)
+}
+
+/// Decrypt the given ciphertext of a secret, and verify its signature according to the signing
+/// policy of the store.
+///
+/// Like `decrypt_secret`, but for ciphertext not read from the secret file, such as a revision
+/// from history. The signature is checked against the current recipients of the secret.
+pub(crate) fn decrypt_secret_ciphertext(
+    context: &mut Context,
+    store: &Store,
+    secret: &Secret,
+    ciphertext: Ciphertext,
+    matcher_main: &MainMatcher,
+) -> anyhow::Result<Plaintext> {
+    let policy = match store.options()?.sign_secrets {
+        Some(policy) => policy,
+        None => return context.decrypt(ciphertext),
+    };
+
+    let groups = crypto::groups::Groups::load(store)?;
+    let recipients = crypto::store::store_read_gpg_fingerprints_for(store, &groups, &secret.path)?;
+    let (plaintext, untrusted) =
+        decrypt_check_signers(context, ciphertext, &recipients, &secret.name)?;
+    if let Some(msg) = untrusted {
+        match policy {
+            SignPolicy::Require if !matcher_main.force() => quit_error_msg(
+                msg,
+                ErrorHintsBuilder::from_matcher(matcher_main)
+                    .force(true)
+                    .build()
+                    .unwrap(),
+            ),
+            _ => print_warning(msg),
+        }
+    }
+
+    Ok(plaintext)
+}
+
+/// Decrypt ciphertext of a secret, and check whether it is signed by any of the given recipient
+/// fingerprints.
+///
+/// Returns the plaintext, along with a message describing the problem if it is not signed by a
+/// recipient.
+pub(crate) fn decrypt_check_signers(
+    context: &mut Context,
+    ciphertext: Ciphertext,
+    recipients: &[String],
+    name: &str,
+) -> anyhow::Result<(Plaintext, Option<String>)> {
+    let verified = context.decrypt_verify(ciphertext)?;
+    let trusted = verified.signers.iter().any(|signer| {
+        recipients
+            .iter()
+            .any(|fp| crypto::util::fingerprints_equal(fp, signer))
+    });
+    if trusted {
+        return Ok((verified.plaintext, None));
+    }
+
+    let msg = if verified.signers.is_empty() {
+        format!("secret is not signed: {name}")
+    } else {
+        format!("secret is not signed by any of its recipients: {name}")
+    };
+    Ok((verified.plaintext, Some(msg)))
+}
+
+/// Read the GPG passphrase from the file or file descriptor given through CLI arguments.
+///
+/// Returns `None` if no passphrase is given, pinentry is used then. Quits with an error if
+/// passphrase input is not explicitly allowed, or if reading fails.
+fn passphrase(matcher_main: &MainMatcher) -> Option<Plaintext> {
+    let result = match matcher_main.passphrase_file() {
+        Some(path) => {
+            require_passphrase_input(matcher_main);
+            File::open(path).and_then(read_passphrase)
+        }
+        #[cfg(unix)]
+        None if matcher_main.passphrase_fd().is_some() => {
+            require_passphrase_input(matcher_main);
+            read_passphrase_fd(matcher_main.passphrase_fd().unwrap())
+        }
+        None => return None,
+    };
+
+    match result {
+        Ok(passphrase) => Some(passphrase),
+        Err(err) => quit_error(
+            anyhow::Error::from(err).context("failed to read GPG passphrase"),
+            ErrorHintsBuilder::from_matcher(matcher_main)
+                .build()
+                .unwrap(),
+        ),
+    }
+}
+
+/// Quit with an error if passphrase input is not explicitly allowed.
+fn require_passphrase_input(matcher_main: &MainMatcher) {
+    if !matcher_main.allow_passphrase_input() {
+        quit_error_msg(
+            "refusing to read GPG passphrase non-interactively, enable it with --allow-passphrase-input or PRS_ALLOW_PASSPHRASE_INPUT=1",
+            ErrorHintsBuilder::from_matcher(matcher_main)
+                .build()
+                .unwrap(),
+        );
+    }
+}
+
+/// Read a passphrase from the given file descriptor, see `read_passphrase`.
+///
+/// The descriptor is owned by the caller and is not closed. Errors if it is stdin, stdout or
+/// stderr, or if it isn't an open descriptor.
+#[cfg(unix)]
+fn read_passphrase_fd(fd: i32) -> io::Result<Plaintext> {
+    use std::mem::ManuallyDrop;
+    use std::os::unix::io::FromRawFd;
+
+    use nix::fcntl::{fcntl, FcntlArg};
+
+    if (0..=2).contains(&fd) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("passphrase file descriptor {fd} must not be stdin, stdout or stderr"),
+        ));
+    }
+    fcntl(fd, FcntlArg::F_GETFD).map_err(io::Error::from)?;
+
+    // The descriptor is owned by the caller, it must not be closed when dropped
+    let file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    read_passphrase(&*file)
+}
+
+/// Read a passphrase, a trailing newline is stripped.
+///
+/// Errors if the passphrase is longer than `PASSPHRASE_MAX_LEN` bytes. The buffer is allocated
+/// once, and is always zeroized after the passphrase is moved into secure storage, also when
+/// reading fails.
+fn read_passphrase(reader: impl Read) -> io::Result<Plaintext> {
+    // Read one byte more than allowed, to detect passphrases that are too long
+    let mut data = Vec::with_capacity(PASSPHRASE_MAX_LEN + 1);
+    let result = reader
+        .take(PASSPHRASE_MAX_LEN as u64 + 1)
+        .read_to_end(&mut data);
+    while matches!(data.last(), Some(b'\n') | Some(b'\r')) {
+        data.pop();
+    }
+    let too_long = data.len() > PASSPHRASE_MAX_LEN;
+    let passphrase = Plaintext::from(data);
+    result.and_then(|_| {
+        if too_long {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("passphrase is longer than {PASSPHRASE_MAX_LEN} bytes"),
+            ));
+        }
+        Ok(passphrase)
+    })
+}
@@ -44,6 +44,8 @@ fn main() {
         let matcher_main = MainMatcher::with(cmd_handler.matches()).unwrap();
         quit_error(err, ErrorHints::from_matcher(&matcher_main));
     };
+
+    crypto::forget_passphrase();
 }
 
 /// Invoke the proper action based on the CLI input.
@@ -60,6 +62,11 @@ fn invoke_action(handler: &Handler) -> Result<()> {
         return action::alias::Alias::new(handler.matches()).invoke();
     }
 
+    #[cfg(unix)]
+    if handler.agent().is_some() {
+        return action::agent::Agent::new(handler.matches()).invoke();
+    }
+
     if handler.clone().is_some() {
         return action::clone::Clone::new(handler.matches()).invoke();
     }
@@ -89,6 +96,10 @@ fn invoke_action(handler: &Handler) -> Result<()> {
         return action::grep::Grep::new(handler.matches()).invoke();
     }
 
+    if handler.history().is_some() {
+        return action::history::History::new(handler.matches()).invoke();
+    }
+
     if handler.housekeeping().is_some() {
         return action::housekeeping::Housekeeping::new(handler.matches()).invoke();
     }
@@ -101,6 +112,10 @@ fn invoke_action(handler: &Handler) -> Result<()> {
         return action::init::Init::new(handler.matches()).invoke();
     }
 
+    if handler.inspect().is_some() {
+        return action::inspect::Inspect::new(handler.matches()).invoke();
+    }
+
     if handler.internal().is_some() {
         return action::internal::Internal::new(handler.matches()).invoke();
     }
@@ -121,6 +136,14 @@ fn invoke_action(handler: &Handler) -> Result<()> {
         return action::remove::Remove::new(handler.matches()).invoke();
     }
 
+    if handler.restore().is_some() {
+        return action::restore::Restore::new(handler.matches()).invoke();
+    }
+
+    if handler.set().is_some() {
+        return action::set::Set::new(handler.matches()).invoke();
+    }
+
     if handler.show().is_some() {
         return action::show::Show::new(handler.matches()).invoke();
     }
@@ -139,6 +162,14 @@ fn invoke_action(handler: &Handler) -> Result<()> {
         return action::totp::Totp::new(handler.matches()).invoke();
     }
 
+    if handler.trash().is_some() {
+        return action::trash::Trash::new(handler.matches()).invoke();
+    }
+
+    if handler.unset().is_some() {
+        return action::unset::Unset::new(handler.matches()).invoke();
+    }
+
     // Get the main matcher
     let matcher_main = MainMatcher::with(handler.matches()).unwrap();
     if !matcher_main.quiet() {
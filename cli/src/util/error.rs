@@ -1,7 +1,6 @@
 use std::borrow::Borrow;
 use std::fmt::{Debug, Display};
 use std::io::{self, Write};
-pub use std::process::exit;
 
 use anyhow::anyhow;
 
@@ -54,6 +53,14 @@ where
     eprintln!("{} {}", highlight_warning("warning:"), err);
 }
 
+/// Exit the application with the given exit code.
+///
+/// Always use this to exit, it zeroizes secrets kept for the whole process first.
+pub fn exit(code: i32) -> ! {
+    crate::crypto::forget_passphrase();
+    std::process::exit(code);
+}
+
 /// Quit the application regularly.
 pub fn quit() -> ! {
     exit(0);
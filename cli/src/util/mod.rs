@@ -21,7 +21,7 @@ pub mod select_skim_bin;
 pub mod stdin;
 pub mod style;
 pub mod sync;
-#[cfg(all(feature = "tomb", target_os = "linux"))]
+pub mod template;
 pub mod time;
 #[cfg(all(feature = "tomb", target_os = "linux"))]
 pub mod tomb;
@@ -1,3 +1,4 @@
+use chbs::{config::BasicConfig, prelude::*};
 use rand::Rng;
 
 use prs_lib::Plaintext;
@@ -64,3 +65,16 @@ pub fn generate_password(len: u16) -> Plaintext {
         pass.truncate(0);
     }
 }
+
+/// Generate secure random passphrase.
+///
+/// This generates a cryptografically secure random passphrase of `words` words.
+///
+/// The returned passphrase is embedded in `Plaintext` for security reasons.
+pub fn generate_passphrase(words: u16) -> Plaintext {
+    let config = BasicConfig {
+        words: words as usize,
+        ..Default::default()
+    };
+    config.to_scheme().generate().into()
+}
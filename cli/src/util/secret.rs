@@ -2,6 +2,8 @@ use std::io::Write;
 
 use prs_lib::{Plaintext, Secret, Store};
 
+use crate::util::select;
+
 /// Secret alias recursion limit.
 const SECRET_ALIAS_DEPTH: u32 = 30;
 
@@ -55,6 +57,20 @@ pub fn print_name(query: Option<String>, secret: &Secret, store: &Store, quiet:
     }
 }
 
+/// Select a secret name, including secrets that only exist in the store history.
+///
+/// The query is used as name if the store history has a secret by that name, which may have been
+/// removed. Otherwise an existing secret is selected.
+pub fn select_name_with_history(store: &Store, query: Option<String>) -> Option<String> {
+    if let Some(query) = &query {
+        let history = store.sync().secret_history(query);
+        if history.map(|history| !history.is_empty()).unwrap_or(false) {
+            return Some(query.trim().into());
+        }
+    }
+    select::store_select_secret(store, query).map(|secret| secret.name)
+}
+
 /// Resolve secret that is aliased.
 ///
 /// This find the target alias if the given secret is an alias. This uses recursive searching.
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use prs_lib::{
+    template::{Placeholder, Template},
+    Plaintext, Store,
+};
+use thiserror::Error;
+
+use crate::cmd::matcher::{
+    generate::{PASSPHRASE_LENGTH, PASSWORD_LENGTH},
+    MainMatcher,
+};
+use crate::util::{cli, error, pass};
+
+/// Load and fill in the store template with the given name.
+///
+/// Field placeholders are filled in from `fields`, and prompted for if missing. Password and
+/// passphrase placeholders are generated. `generated` may be given to use an already generated
+/// secret for the listed placeholders instead.
+pub fn render(
+    store: &Store,
+    name: &str,
+    fields: &[(String, String)],
+    generated: Option<(&[Placeholder], &Plaintext)>,
+    matcher_main: &MainMatcher,
+) -> Result<Plaintext> {
+    let template = Template::load(store, name).map_err(|err| {
+        let available = Template::list(store);
+        if !available.is_empty() {
+            eprintln!("Available templates: {}", available.join(", "));
+        }
+        Err::Load(err)
+    })?;
+
+    // Warn about given fields the template doesn't use
+    let placeholders = template.placeholders();
+    for (key, _) in fields {
+        if !placeholders.contains(&&Placeholder::Field(key.clone())) {
+            error::print_warning(format!(
+                "template '{name}' does not have field '{key}', ignoring"
+            ));
+        }
+    }
+
+    template
+        .render(|placeholder| {
+            if let Some((kinds, plaintext)) = generated {
+                if kinds.contains(placeholder) {
+                    return Ok(plaintext.clone());
+                }
+            }
+
+            Ok(match placeholder {
+                Placeholder::Password(len) => {
+                    pass::generate_password(len.unwrap_or(PASSWORD_LENGTH))
+                }
+                Placeholder::Passphrase(words) => {
+                    pass::generate_passphrase(words.unwrap_or(PASSPHRASE_LENGTH))
+                }
+                Placeholder::Field(field) => match fields.iter().find(|(key, _)| key == field) {
+                    Some((_, value)) => value.as_str().into(),
+                    None => cli::prompt(field, matcher_main).into(),
+                },
+            })
+        })
+        .map_err(|err| Err::Render(err).into())
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to load secret template")]
+    Load(#[source] anyhow::Error),
+
+    #[error("failed to fill in secret template")]
+    Render(#[source] anyhow::Error),
+}
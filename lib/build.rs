@@ -1,11 +1,26 @@
 fn main() {
     // Crypto features warning
-    #[cfg(not(any(feature = "backend-gnupg-bin", feature = "backend-gpgme")))]
+    #[cfg(not(any(
+        feature = "backend-gnupg-bin",
+        feature = "backend-gpgme",
+        feature = "backend-age",
+        feature = "backend-rpgp",
+        feature = "backend-mock"
+    )))]
     {
-        compile_error!("no crypto backend selected, must set any of these features: backend-gnupg-bin, backend-gpgme");
+        compile_error!("no crypto backend selected, must set any of these features: backend-gnupg-bin, backend-gpgme, backend-age, backend-rpgp, backend-mock");
     }
 
     // GPG cryptography
-    #[cfg(any(feature = "backend-gpgme", feature = "backend-gnupg-bin"))]
+    #[cfg(any(
+        feature = "backend-gpgme",
+        feature = "backend-gnupg-bin",
+        feature = "backend-rpgp",
+        feature = "backend-mock"
+    ))]
     println!("cargo:rustc-cfg=feature=\"_crypto-gpg\"");
+
+    // age cryptography
+    #[cfg(feature = "backend-age")]
+    println!("cargo:rustc-cfg=feature=\"_crypto-age\"");
 }
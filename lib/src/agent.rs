@@ -0,0 +1,803 @@
+//! Decryption cache agent.
+//!
+//! The agent is a user daemon listening on a Unix socket. It caches decrypted plaintext in locked
+//! memory for a short time, so secrets that are read repeatedly don't invoke the crypto backend
+//! each time. Cached plaintext is zeroized when it expires, when the cache is cleared, and when
+//! the screen is locked.
+//!
+//! The agent never decrypts itself. Clients decrypt when the agent doesn't have the plaintext
+//! cached, and hand it to the agent afterwards. See `Context`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+use crate::crypto::{self, IsContext, Key, Proto, Verified};
+use crate::util;
+use crate::{Ciphertext, Plaintext, Recipients};
+
+/// Default time to keep decrypted plaintext cached for.
+pub const AGENT_TTL_SEC: u64 = 2 * 60;
+
+/// Agent socket file name, in the prs runtime directory.
+const SOCKET_FILE: &str = "agent.sock";
+
+/// Time clients wait for the agent to respond.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Time the agent waits for an idle client before disconnecting it.
+const SERVER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Interval to evict expired plaintext at.
+const EVICT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum length of a single message field, to limit memory usage.
+const FIELD_MAX_LEN: usize = 16 * 1024 * 1024;
+
+/// D-Bus monitor binary, used to watch for screen locks.
+const DBUS_MONITOR_BIN: &str = "dbus-monitor";
+
+/// D-Bus signals that indicate the screen is locked, along with the bus to watch.
+const DBUS_LOCK_SIGNALS: [(&str, &str); 3] = [
+    (
+        "--session",
+        "type='signal',interface='org.freedesktop.ScreenSaver',member='ActiveChanged'",
+    ),
+    (
+        "--session",
+        "type='signal',interface='org.gnome.ScreenSaver',member='ActiveChanged'",
+    ),
+    (
+        "--system",
+        "type='signal',interface='org.freedesktop.login1.Session',member='Lock'",
+    ),
+];
+
+/// Request operation: get cached plaintext for ciphertext.
+const OP_GET: u8 = 1;
+
+/// Request operation: cache plaintext for ciphertext.
+const OP_PUT: u8 = 2;
+
+/// Request operation: clear the cache.
+const OP_CLEAR: u8 = 3;
+
+/// Request operation: get agent status.
+const OP_STATUS: u8 = 4;
+
+/// Request operation: stop the agent.
+const OP_STOP: u8 = 5;
+
+/// Request operation: get cached plaintext and signers for ciphertext.
+const OP_GET_VERIFIED: u8 = 6;
+
+/// Request operation: cache plaintext and signers for ciphertext.
+const OP_PUT_VERIFIED: u8 = 7;
+
+/// Response status: request succeeded.
+const STATUS_OK: u8 = 0;
+
+/// Response status: no plaintext cached for ciphertext.
+const STATUS_MISS: u8 = 1;
+
+/// Response status: invalid request.
+const STATUS_ERR: u8 = 2;
+
+/// Get the socket path of the agent.
+pub fn socket_path() -> PathBuf {
+    util::env::runtime_dir().join(SOCKET_FILE)
+}
+
+/// Run the agent on the given socket, until it is stopped.
+///
+/// Errors if an agent is already running on the socket.
+pub fn serve(path: &Path, ttl: Duration) -> Result<()> {
+    if Client::connect(path).is_ok() {
+        return Err(Err::Running.into());
+    }
+
+    // Bind socket in private directory, remove stale socket from agent that didn't stop cleanly
+    if let Some(dir) = path.parent() {
+        util::fs::create_private_dir(dir)?;
+    }
+    let _ = fs::remove_file(path);
+    let listener = UnixListener::bind(path).map_err(Err::Bind)?;
+
+    // Plaintext must never end up on disk through core dumps
+    nix::sys::resource::setrlimit(nix::sys::resource::Resource::RLIMIT_CORE, 0, 0)
+        .map_err(Err::CoreDumps)?;
+
+    let cache = Arc::new(Mutex::new(Cache::new(ttl)));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Evict expired plaintext in the background
+    {
+        let cache = cache.clone();
+        thread::spawn(move || loop {
+            thread::sleep(EVICT_INTERVAL);
+            cache.lock().unwrap().evict();
+        });
+    }
+
+    watch_screen_lock(&cache);
+
+    for stream in listener.incoming() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // Only serve our own user, don't let idle clients keep a thread forever
+        let stream = match stream {
+            Ok(stream) if is_own_peer(&stream) => stream,
+            _ => continue,
+        };
+        if stream
+            .set_read_timeout(Some(SERVER_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(SERVER_TIMEOUT)))
+            .is_err()
+        {
+            continue;
+        }
+
+        let cache = cache.clone();
+        let stop = stop.clone();
+        let path = path.to_path_buf();
+        thread::spawn(move || {
+            if let Ok(true) = handle(stream, &cache) {
+                // Wake up listener to stop
+                stop.store(true, Ordering::Relaxed);
+                let _ = UnixStream::connect(path);
+            }
+        });
+    }
+
+    cache.lock().unwrap().clear();
+    let _ = fs::remove_file(path);
+    Ok(())
+}
+
+/// Handle requests from a client, until it disconnects.
+///
+/// Returns `true` if the agent should stop.
+fn handle(mut stream: UnixStream, cache: &Mutex<Cache>) -> io::Result<bool> {
+    loop {
+        let mut op = [0u8];
+        match stream.read_exact(&mut op) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(err) => return Err(err),
+        }
+
+        match op[0] {
+            OP_GET => {
+                let ciphertext = read_field(&mut stream)?;
+                let plaintext = cache.lock().unwrap().get(&ciphertext);
+                match plaintext {
+                    Some(plaintext) => {
+                        stream.write_all(&[STATUS_OK])?;
+                        write_field(&mut stream, plaintext.unsecure_ref())?;
+                    }
+                    None => stream.write_all(&[STATUS_MISS])?,
+                }
+            }
+            OP_PUT => {
+                let ciphertext = read_field(&mut stream)?;
+                let plaintext = Plaintext::from(read_field(&mut stream)?);
+                cache.lock().unwrap().put(ciphertext, plaintext, None);
+                stream.write_all(&[STATUS_OK])?;
+            }
+            OP_GET_VERIFIED => {
+                let ciphertext = read_field(&mut stream)?;
+                let verified = cache.lock().unwrap().get_verified(&ciphertext);
+                match verified {
+                    Some((plaintext, signers)) => {
+                        stream.write_all(&[STATUS_OK])?;
+                        write_field(&mut stream, plaintext.unsecure_ref())?;
+                        write_field(&mut stream, signers.join("\n").as_bytes())?;
+                    }
+                    None => stream.write_all(&[STATUS_MISS])?,
+                }
+            }
+            OP_PUT_VERIFIED => {
+                let ciphertext = read_field(&mut stream)?;
+                let plaintext = Plaintext::from(read_field(&mut stream)?);
+                let signers = parse_signers(&read_field(&mut stream)?);
+                cache
+                    .lock()
+                    .unwrap()
+                    .put(ciphertext, plaintext, Some(signers));
+                stream.write_all(&[STATUS_OK])?;
+            }
+            OP_CLEAR => {
+                cache.lock().unwrap().clear();
+                stream.write_all(&[STATUS_OK])?;
+            }
+            OP_STATUS => {
+                let status = cache.lock().unwrap().status();
+                stream.write_all(&[STATUS_OK])?;
+                write_field(
+                    &mut stream,
+                    format!("{} {}", status.entries, status.ttl.as_secs()).as_bytes(),
+                )?;
+            }
+            OP_STOP => {
+                stream.write_all(&[STATUS_OK])?;
+                return Ok(true);
+            }
+            _ => {
+                stream.write_all(&[STATUS_ERR])?;
+                return Ok(false);
+            }
+        }
+    }
+}
+
+/// Clear the cache whenever the screen is locked.
+///
+/// This watches for screen saver and session lock signals on D-Bus through `dbus-monitor`. Does
+/// nothing if `dbus-monitor` is not available.
+fn watch_screen_lock(cache: &Arc<Mutex<Cache>>) {
+    if which::which(DBUS_MONITOR_BIN).is_err() {
+        return;
+    }
+
+    for (bus, rule) in DBUS_LOCK_SIGNALS {
+        let cache = cache.clone();
+        thread::spawn(move || watch_dbus_signal(bus, rule, || cache.lock().unwrap().clear()));
+    }
+}
+
+/// Watch for a D-Bus signal matching the given rule, call `on_lock` when it signals a lock.
+///
+/// Screen saver signals have a boolean argument for whether it is activated, session lock signals
+/// have no arguments.
+fn watch_dbus_signal(bus: &str, rule: &str, on_lock: impl Fn()) -> io::Result<()> {
+    let mut child = Command::new(DBUS_MONITOR_BIN)
+        .args([bus, rule])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line == "boolean true"
+                || (line.starts_with("signal ") && line.contains("member=Lock"))
+            {
+                on_lock();
+            }
+        }
+    }
+
+    child.wait()?;
+    Ok(())
+}
+
+/// Agent status.
+pub struct Status {
+    /// Number of cached secrets.
+    pub entries: usize,
+
+    /// Time plaintext is cached for.
+    pub ttl: Duration,
+}
+
+/// Plaintext cache, by ciphertext.
+///
+/// Keyed by the full ciphertext, so a changed secret never gives stale plaintext.
+struct Cache {
+    /// Time to cache plaintext for.
+    ttl: Duration,
+
+    /// Cached plaintext along with its expiry time.
+    entries: HashMap<Vec<u8>, Entry>,
+}
+
+/// Cached plaintext.
+struct Entry {
+    /// Time the plaintext expires at.
+    expires: Instant,
+
+    /// The plaintext.
+    plaintext: Plaintext,
+
+    /// Fingerprints of valid signers, `None` if the signature wasn't verified.
+    signers: Option<Vec<String>>,
+}
+
+impl Cache {
+    /// Construct empty cache.
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Get cached plaintext for ciphertext, if not expired.
+    fn get(&mut self, ciphertext: &[u8]) -> Option<Plaintext> {
+        self.evict();
+        self.entries
+            .get(ciphertext)
+            .map(|entry| entry.plaintext.clone())
+    }
+
+    /// Get cached plaintext and signers for ciphertext, if not expired and verified.
+    fn get_verified(&mut self, ciphertext: &[u8]) -> Option<(Plaintext, Vec<String>)> {
+        self.evict();
+        self.entries.get(ciphertext).and_then(|entry| {
+            entry
+                .signers
+                .clone()
+                .map(|signers| (entry.plaintext.clone(), signers))
+        })
+    }
+
+    /// Cache plaintext for ciphertext, along with its signers if verified.
+    fn put(&mut self, ciphertext: Vec<u8>, plaintext: Plaintext, signers: Option<Vec<String>>) {
+        let entry = Entry {
+            expires: Instant::now() + self.ttl,
+            plaintext,
+            signers,
+        };
+        self.entries.insert(ciphertext, entry);
+    }
+
+    /// Drop expired plaintext.
+    fn evict(&mut self) {
+        let now = Instant::now();
+        self.entries.retain(|_, entry| entry.expires > now);
+    }
+
+    /// Drop all plaintext.
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Get cache status.
+    fn status(&mut self) -> Status {
+        self.evict();
+        Status {
+            entries: self.entries.len(),
+            ttl: self.ttl,
+        }
+    }
+}
+
+/// Agent client.
+pub struct Client {
+    /// Connection to the agent.
+    stream: UnixStream,
+}
+
+impl Client {
+    /// Connect to the agent on the given socket.
+    ///
+    /// Errors if no agent is running, or if it is not owned by the current user.
+    pub fn connect(path: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(path).map_err(Err::Connect)?;
+        if !is_own_peer(&stream) {
+            return Err(Err::NotOwned.into());
+        }
+        stream
+            .set_read_timeout(Some(CLIENT_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(CLIENT_TIMEOUT)))
+            .map_err(Err::Connect)?;
+        Ok(Self { stream })
+    }
+
+    /// Get cached plaintext for the given ciphertext.
+    ///
+    /// Returns `None` if not cached.
+    pub fn get(&mut self, ciphertext: &[u8]) -> Result<Option<Plaintext>> {
+        match self.request(OP_GET, &[ciphertext])? {
+            STATUS_OK => Ok(Some(Plaintext::from(
+                read_field(&mut self.stream).map_err(Err::Request)?,
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Cache plaintext for the given ciphertext.
+    pub fn put(&mut self, ciphertext: &[u8], plaintext: &Plaintext) -> Result<()> {
+        self.request(OP_PUT, &[ciphertext, plaintext.unsecure_ref()])?;
+        Ok(())
+    }
+
+    /// Get cached plaintext and valid signers for the given ciphertext.
+    ///
+    /// Returns `None` if not cached, or if it was cached without verifying its signature.
+    pub fn get_verified(&mut self, ciphertext: &[u8]) -> Result<Option<Verified>> {
+        match self.request(OP_GET_VERIFIED, &[ciphertext])? {
+            STATUS_OK => {
+                let plaintext =
+                    Plaintext::from(read_field(&mut self.stream).map_err(Err::Request)?);
+                let signers = read_field(&mut self.stream).map_err(Err::Request)?;
+                Ok(Some(Verified {
+                    plaintext,
+                    signers: parse_signers(&signers),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Cache plaintext and valid signers for the given ciphertext.
+    pub fn put_verified(&mut self, ciphertext: &[u8], verified: &Verified) -> Result<()> {
+        let signers = verified.signers.join("\n");
+        self.request(
+            OP_PUT_VERIFIED,
+            &[
+                ciphertext,
+                verified.plaintext.unsecure_ref(),
+                signers.as_bytes(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Clear all cached plaintext.
+    pub fn clear(&mut self) -> Result<()> {
+        self.request(OP_CLEAR, &[])?;
+        Ok(())
+    }
+
+    /// Get the agent status.
+    pub fn status(&mut self) -> Result<Status> {
+        self.request(OP_STATUS, &[])?;
+        let status = read_field(&mut self.stream).map_err(Err::Request)?;
+        let status = String::from_utf8_lossy(&status);
+        let mut parts = status.split(' ').map(|part| part.parse::<u64>());
+        match (parts.next(), parts.next()) {
+            (Some(Ok(entries)), Some(Ok(ttl))) => Ok(Status {
+                entries: entries as usize,
+                ttl: Duration::from_secs(ttl),
+            }),
+            _ => Err(Err::Response.into()),
+        }
+    }
+
+    /// Stop the agent, all cached plaintext is dropped.
+    pub fn stop(&mut self) -> Result<()> {
+        self.request(OP_STOP, &[])?;
+        Ok(())
+    }
+
+    /// Send a request with the given fields, return the response status.
+    ///
+    /// Errors if the agent reports an invalid request.
+    fn request(&mut self, op: u8, fields: &[&[u8]]) -> Result<u8> {
+        self.stream.write_all(&[op]).map_err(Err::Request)?;
+        for field in fields {
+            write_field(&mut self.stream, field).map_err(Err::Request)?;
+        }
+
+        let mut status = [0u8];
+        self.stream.read_exact(&mut status).map_err(Err::Request)?;
+        match status[0] {
+            STATUS_OK | STATUS_MISS => Ok(status[0]),
+            _ => Err(Err::Response.into()),
+        }
+    }
+}
+
+/// Crypto context using the agent to cache decrypted plaintext.
+///
+/// Wraps another context that is used for all operations. Decrypting takes plaintext from the
+/// agent if cached, and hands plaintext to the agent otherwise. Verified decryption caches the
+/// valid signers along with the plaintext, so signatures are not checked again until the cached
+/// plaintext expires. The agent is not used anymore once it fails.
+pub struct Context {
+    /// Inner context.
+    context: crypto::Context,
+
+    /// Agent client, `None` if the agent failed.
+    client: Option<Client>,
+}
+
+impl Context {
+    /// Construct context, wrapping the given context.
+    pub fn new(context: crypto::Context, client: Client) -> Self {
+        Self {
+            context,
+            client: Some(client),
+        }
+    }
+}
+
+impl IsContext for Context {
+    fn encrypt(&mut self, recipients: &Recipients, plaintext: Plaintext) -> Result<Ciphertext> {
+        self.context.encrypt(recipients, plaintext)
+    }
+
+    fn decrypt(&mut self, ciphertext: Ciphertext) -> Result<Plaintext> {
+        let client = match &mut self.client {
+            Some(client) => client,
+            None => return self.context.decrypt(ciphertext),
+        };
+
+        let data = ciphertext.unsecure_ref().to_vec();
+        match client.get(&data) {
+            Ok(Some(plaintext)) => return Ok(plaintext),
+            Ok(None) => {}
+            Err(_) => {
+                self.client.take();
+                return self.context.decrypt(ciphertext);
+            }
+        }
+
+        let plaintext = self.context.decrypt(ciphertext)?;
+        if client.put(&data, &plaintext).is_err() {
+            self.client.take();
+        }
+        Ok(plaintext)
+    }
+
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        let client = match &mut self.client {
+            Some(client) => client,
+            None => return self.context.decrypt_verify(ciphertext),
+        };
+
+        let data = ciphertext.unsecure_ref().to_vec();
+        match client.get_verified(&data) {
+            Ok(Some(verified)) => return Ok(verified),
+            Ok(None) => {}
+            Err(_) => {
+                self.client.take();
+                return self.context.decrypt_verify(ciphertext);
+            }
+        }
+
+        let verified = self.context.decrypt_verify(ciphertext)?;
+        if client.put_verified(&data, &verified).is_err() {
+            self.client.take();
+        }
+        Ok(verified)
+    }
+
+    fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
+        self.context.can_decrypt(ciphertext)
+    }
+
+    fn keys_public(&mut self) -> Result<Vec<Key>> {
+        self.context.keys_public()
+    }
+
+    fn keys_private(&mut self) -> Result<Vec<Key>> {
+        self.context.keys_private()
+    }
+
+    fn import_key(&mut self, key: &[u8]) -> Result<()> {
+        self.context.import_key(key)
+    }
+
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        self.context.read_keys(key)
+    }
+
+    fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
+        self.context.export_key(key)
+    }
+
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>> {
+        self.context.encrypted_key_ids(ciphertext)
+    }
+
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>> {
+        self.context.find_key_by_id(key_id)
+    }
+
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+        self.context.sign_detached(key, data)
+    }
+
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+        self.context.verify_detached(data, signature)
+    }
+
+    fn supports_proto(&self, proto: Proto) -> bool {
+        self.context.supports_proto(proto)
+    }
+}
+
+/// Read a length prefixed message field.
+///
+/// The field is zeroized if reading fails halfway.
+fn read_field(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > FIELD_MAX_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "agent message field too large",
+        ));
+    }
+
+    let mut data = vec![0; len];
+    if let Err(err) = reader.read_exact(&mut data) {
+        data.zeroize();
+        return Err(err);
+    }
+    Ok(data)
+}
+
+/// Parse newline separated signer fingerprints from a message field.
+fn parse_signers(data: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Write a length prefixed message field.
+fn write_field(writer: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    if data.len() > FIELD_MAX_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "agent message field too large",
+        ));
+    }
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(data)
+}
+
+/// Check whether the process on the other end of the socket is owned by the current user.
+fn is_own_peer(stream: &UnixStream) -> bool {
+    peer_uid(stream) == Some(nix::unistd::getuid().as_raw())
+}
+
+/// Get the user ID of the process on the other end of the socket.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_uid(stream: &UnixStream) -> Option<u32> {
+    use nix::sys::socket::{getsockopt, sockopt::PeerCredentials};
+
+    getsockopt(stream.as_raw_fd(), PeerCredentials)
+        .ok()
+        .map(|credentials| credentials.uid())
+}
+
+/// Get the user ID of the process on the other end of the socket.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+))]
+fn peer_uid(stream: &UnixStream) -> Option<u32> {
+    nix::unistd::getpeereid(stream.as_raw_fd())
+        .ok()
+        .map(|(uid, _)| uid.as_raw())
+}
+
+/// Get the user ID of the process on the other end of the socket.
+///
+/// Not supported on this platform, the agent is never trusted.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+)))]
+fn peer_uid(_stream: &UnixStream) -> Option<u32> {
+    None
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("agent is already running")]
+    Running,
+
+    #[error("failed to bind agent socket")]
+    Bind(#[source] io::Error),
+
+    #[error("failed to disable core dumps for agent")]
+    CoreDumps(#[source] nix::Error),
+
+    #[error("failed to connect to agent")]
+    Connect(#[source] io::Error),
+
+    #[error("agent socket is not owned by current user")]
+    NotOwned,
+
+    #[error("failed to communicate with agent")]
+    Request(#[source] io::Error),
+
+    #[error("agent sent unexpected response")]
+    Response,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run an agent with the given TTL in a temporary directory, return its socket path.
+    fn agent(ttl: Duration) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOCKET_FILE);
+        {
+            let path = path.clone();
+            thread::spawn(move || serve(&path, ttl).unwrap());
+        }
+        for _ in 0..100 {
+            if path.exists() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        (dir, path)
+    }
+
+    #[test]
+    fn protocol() {
+        let (_dir, path) = agent(Duration::from_secs(AGENT_TTL_SEC));
+        let mut client = Client::connect(&path).unwrap();
+
+        // Get misses until put
+        assert!(client.get(b"ciphertext").unwrap().is_none());
+        client
+            .put(b"ciphertext", &Plaintext::from("plaintext"))
+            .unwrap();
+        let plaintext = client.get(b"ciphertext").unwrap().unwrap();
+        assert_eq!(plaintext.unsecure_ref(), b"plaintext");
+        assert!(client.get(b"other").unwrap().is_none());
+
+        // Unverified plaintext is not used for verified decryption
+        assert!(client.get_verified(b"ciphertext").unwrap().is_none());
+        let verified = Verified {
+            plaintext: Plaintext::from("signed"),
+            signers: vec!["AAAA".into(), "BBBB".into()],
+        };
+        client.put_verified(b"signed", &verified).unwrap();
+        let cached = client.get_verified(b"signed").unwrap().unwrap();
+        assert_eq!(cached.plaintext.unsecure_ref(), b"signed");
+        assert_eq!(cached.signers, verified.signers);
+        assert_eq!(client.status().unwrap().entries, 2);
+
+        // Clear drops everything
+        client.clear().unwrap();
+        assert!(client.get(b"ciphertext").unwrap().is_none());
+        assert!(client.get_verified(b"signed").unwrap().is_none());
+        assert_eq!(client.status().unwrap().entries, 0);
+
+        client.stop().unwrap();
+    }
+
+    #[test]
+    fn ttl_expiry() {
+        let (_dir, path) = agent(Duration::from_millis(200));
+        let mut client = Client::connect(&path).unwrap();
+
+        client
+            .put(b"ciphertext", &Plaintext::from("plaintext"))
+            .unwrap();
+        assert!(client.get(b"ciphertext").unwrap().is_some());
+        thread::sleep(Duration::from_millis(300));
+        assert!(client.get(b"ciphertext").unwrap().is_none());
+        assert_eq!(client.status().unwrap().entries, 0);
+
+        client.stop().unwrap();
+    }
+}
@@ -0,0 +1,222 @@
+//! OpenPGP ASCII armor.
+//!
+//! Converts OpenPGP messages and keys between their binary and ASCII armored form as described in
+//! [RFC 4880, section 6](https://www.rfc-editor.org/rfc/rfc4880#section-6). This is a pure
+//! encoding, no decryption is involved.
+
+use thiserror::Error;
+
+/// Maximum length of an armored base64 line.
+const LINE_LEN: usize = 64;
+
+/// Base64 alphabet.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Type of armored OpenPGP data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    /// An OpenPGP message.
+    Message,
+
+    /// An OpenPGP public key.
+    PublicKey,
+}
+
+impl Block {
+    /// Header line of this armored block.
+    fn begin(&self) -> &'static str {
+        match self {
+            Self::Message => "-----BEGIN PGP MESSAGE-----",
+            Self::PublicKey => "-----BEGIN PGP PUBLIC KEY BLOCK-----",
+        }
+    }
+
+    /// Footer line of this armored block.
+    fn end(&self) -> &'static str {
+        match self {
+            Self::Message => "-----END PGP MESSAGE-----",
+            Self::PublicKey => "-----END PGP PUBLIC KEY BLOCK-----",
+        }
+    }
+}
+
+/// Check whether the given data is an ASCII armored OpenPGP block of the given type.
+pub fn is_armored(block: Block, data: &[u8]) -> bool {
+    let start = data
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(data.len());
+    data[start..].starts_with(block.begin().as_bytes())
+}
+
+/// ASCII armor binary OpenPGP data as the given block type.
+pub fn armor(block: Block, data: &[u8]) -> Vec<u8> {
+    let encoded = base64_encode(data);
+
+    let mut armored = format!("{}\n\n", block.begin());
+    for line in encoded.as_bytes().chunks(LINE_LEN) {
+        // Base64 output is always ASCII
+        armored.push_str(std::str::from_utf8(line).unwrap());
+        armored.push('\n');
+    }
+    armored.push('=');
+    armored.push_str(&base64_encode(&crc24(data).to_be_bytes()[1..]));
+    armored.push('\n');
+    armored.push_str(block.end());
+    armored.push('\n');
+    armored.into_bytes()
+}
+
+/// Remove the ASCII armor from an OpenPGP block of the given type, returning the binary data.
+///
+/// Only the first block is read. Armor headers are ignored. The checksum is verified if present.
+pub fn dearmor(block: Block, data: &[u8]) -> Result<Vec<u8>, Err> {
+    let data = std::str::from_utf8(data).map_err(|_| Err::Malformed)?;
+    let mut lines = data
+        .lines()
+        .map(|line| line.trim_end())
+        .skip_while(|line| line.is_empty());
+    if lines.next() != Some(block.begin()) {
+        return Err(Err::Malformed);
+    }
+
+    // Skip armor headers, base64 never contains a colon
+    let mut lines = lines
+        .skip_while(|line| line.contains(':'))
+        .skip_while(|line| line.is_empty());
+
+    let mut encoded = String::new();
+    let mut checksum = None;
+    loop {
+        match lines.next() {
+            None => return Err(Err::Malformed),
+            Some(line) if line == block.end() => break,
+            Some(line) if line.starts_with('=') => checksum = Some(&line[1..]),
+            Some(line) => encoded.push_str(line),
+        }
+    }
+
+    let message = base64_decode(&encoded)?;
+    if let Some(checksum) = checksum {
+        let checksum = base64_decode(checksum)?;
+        if checksum != crc24(&message).to_be_bytes()[1..] {
+            return Err(Err::Checksum);
+        }
+    }
+    Ok(message)
+}
+
+/// Encode data as padded base64.
+fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, b)| bits | (*b as u32) << (16 - i * 8));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(bits >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decode padded base64 data.
+fn base64_decode(encoded: &str) -> Result<Vec<u8>, Err> {
+    let encoded = encoded.trim_end_matches('=');
+    let mut data = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for c in encoded.bytes() {
+        let value = BASE64.iter().position(|b| *b == c).ok_or(Err::Malformed)?;
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            data.push((bits >> count) as u8);
+        }
+    }
+    Ok(data)
+}
+
+/// Compute the OpenPGP CRC-24 checksum over data.
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc = 0xb704ceu32;
+    for byte in data {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= 0x1864cfb;
+            }
+        }
+    }
+    crc & 0xffffff
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("malformed ASCII armored OpenPGP data")]
+    Malformed,
+
+    #[error("ASCII armored OpenPGP data has invalid checksum")]
+    Checksum,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64() {
+        for (data, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64_encode(data.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), data.as_bytes());
+        }
+    }
+
+    #[test]
+    fn dearmor_headers() {
+        let armored = "-----BEGIN PGP MESSAGE-----\r\n\
+            Comment: test\r\n\
+            \r\n\
+            Zm9v\r\n\
+            YmFy\r\n\
+            -----END PGP MESSAGE-----\r\n";
+        assert!(is_armored(Block::Message, armored.as_bytes()));
+        assert!(!is_armored(Block::PublicKey, armored.as_bytes()));
+        assert_eq!(
+            dearmor(Block::Message, armored.as_bytes()).unwrap(),
+            b"foobar"
+        );
+    }
+
+    #[quickcheck]
+    fn armor_roundtrip(data: Vec<u8>) -> bool {
+        [Block::Message, Block::PublicKey].iter().all(|block| {
+            let armored = armor(*block, &data);
+            is_armored(*block, &armored) && dearmor(*block, &armored).unwrap() == data
+        })
+    }
+
+    #[test]
+    fn dearmor_checksum() {
+        let mut armored = String::from_utf8(armor(Block::Message, b"foobar")).unwrap();
+        armored = armored.replace("Zm9vYmFy", "Zm9vYmFz");
+        assert!(matches!(
+            dearmor(Block::Message, armored.as_bytes()),
+            Err(Err::Checksum)
+        ));
+    }
+}
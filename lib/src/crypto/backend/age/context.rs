@@ -0,0 +1,126 @@
+//! Provides age context adapter.
+
+use anyhow::Result;
+
+use super::{raw, Config};
+use crate::crypto::{proto, Config as CryptoConfig, IsContext, Key, Proto, Verified};
+use crate::{Ciphertext, Plaintext, Recipients};
+
+/// Create age context.
+pub fn context(config: &CryptoConfig) -> Result<Context> {
+    let mut age_config = Config::from_env();
+    age_config.verbose = config.verbose;
+    Ok(Context::from(age_config))
+}
+
+/// age context.
+pub struct Context {
+    /// age config.
+    config: Config,
+}
+
+impl Context {
+    /// Construct context from age config.
+    fn from(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Generate a new identity, return its recipient key.
+    pub fn generate(&mut self) -> Result<Key> {
+        Ok(raw::generate_identity(&self.config)?.into_key())
+    }
+}
+
+impl IsContext for Context {
+    fn encrypt(&mut self, recipients: &Recipients, plaintext: Plaintext) -> Result<Ciphertext> {
+        let keys: Vec<proto::age::Key> = recipients
+            .keys()
+            .iter()
+            .filter_map(|key| match key {
+                Key::Age(key) => Some(key.clone()),
+                #[allow(unreachable_patterns)]
+                _ => None,
+            })
+            .collect();
+        raw::encrypt(&keys, plaintext)
+    }
+
+    fn decrypt(&mut self, ciphertext: Ciphertext) -> Result<Plaintext> {
+        raw::decrypt(&self.config, ciphertext)
+    }
+
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        // age does not support signatures
+        Ok(Verified {
+            plaintext: self.decrypt(ciphertext)?,
+            signers: vec![],
+        })
+    }
+
+    fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
+        raw::can_decrypt(&self.config, ciphertext)
+    }
+
+    fn keys_public(&mut self) -> Result<Vec<Key>> {
+        let mut keys: Vec<Key> = raw::read_keychain(&self.config)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect();
+        for key in self.keys_private()? {
+            if !keys
+                .iter()
+                .any(|k| k.fingerprint(false) == key.fingerprint(false))
+            {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    fn keys_private(&mut self) -> Result<Vec<Key>> {
+        Ok(raw::load_identities(&self.config)?
+            .into_iter()
+            .map(|identity| identity.key.into_key())
+            .collect())
+    }
+
+    fn import_key(&mut self, key: &[u8]) -> Result<()> {
+        let keys = proto::age::parse_recipients(std::str::from_utf8(key)?)?;
+        raw::write_keychain(&self.config, &keys)
+    }
+
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        Ok(proto::age::parse_recipients(std::str::from_utf8(key)?)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
+    fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
+        match key {
+            Key::Age(key) => Ok(proto::age::format_recipients(&[key]).into_bytes()),
+            #[allow(unreachable_patterns)]
+            _ => Err(crate::crypto::Err::Unsupported(key.proto()).into()),
+        }
+    }
+
+    fn encrypted_key_ids(&mut self, _ciphertext: Ciphertext) -> Result<Vec<String>> {
+        Err(crate::crypto::Err::Unsupported(Proto::Age).into())
+    }
+
+    fn find_key_by_id(&mut self, _key_id: &str) -> Result<Option<Key>> {
+        Err(crate::crypto::Err::Unsupported(Proto::Age).into())
+    }
+
+    fn sign_detached(&mut self, _key: &Key, _data: &[u8]) -> Result<Vec<u8>> {
+        Err(crate::crypto::Err::Unsupported(Proto::Age).into())
+    }
+
+    fn verify_detached(&mut self, _data: &[u8], _signature: &[u8]) -> Result<Vec<String>> {
+        Err(crate::crypto::Err::Unsupported(Proto::Age).into())
+    }
+
+    fn supports_proto(&self, proto: Proto) -> bool {
+        proto == Proto::Age
+    }
+}
@@ -0,0 +1,60 @@
+//! Crypto backend using age, implemented in pure Rust.
+
+pub mod context;
+pub mod raw;
+
+use std::env;
+use std::path::PathBuf;
+
+/// Environment variable to override the list of age identity files.
+pub const ENV_IDENTITIES: &str = "PRS_AGE_IDENTITIES";
+
+/// Environment variable to override the age recipients keychain file.
+pub const ENV_RECIPIENTS: &str = "PRS_AGE_RECIPIENTS";
+
+/// age config.
+pub struct Config {
+    /// Identity files used for decryption.
+    ///
+    /// These may contain native age identities or unencrypted SSH private keys.
+    pub identities: Vec<PathBuf>,
+
+    /// Keychain file listing known recipients.
+    pub recipients: PathBuf,
+
+    /// Whether to show verbose output.
+    pub verbose: bool,
+}
+
+impl Config {
+    /// Construct with default identity and recipient files.
+    ///
+    /// Respects the `PRS_AGE_IDENTITIES` and `PRS_AGE_RECIPIENTS` environment variables.
+    pub fn from_env() -> Self {
+        let dir = config_dir();
+        let identities = env::var_os(ENV_IDENTITIES)
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_else(|| vec![dir.join("identities")]);
+        let recipients = env::var_os(ENV_RECIPIENTS)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| dir.join("recipients"));
+
+        Self {
+            identities,
+            recipients,
+            verbose: false,
+        }
+    }
+}
+
+/// Get the age configuration directory.
+///
+/// Uses `$XDG_CONFIG_HOME/prs/age`, and falls back to `~/.config/prs/age`.
+fn config_dir() -> PathBuf {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(shellexpand::tilde("~/.config").as_ref()))
+        .join("prs")
+        .join("age")
+}
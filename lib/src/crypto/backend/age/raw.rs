@@ -0,0 +1,336 @@
+//! Raw interface to age.
+//!
+//! This provides the most basic functions to encrypt and decrypt using age, and to manage
+//! identity and recipient files.
+
+use std::convert::TryFrom;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+
+use age::secrecy::ExposeSecret;
+use anyhow::Result;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use super::Config;
+use crate::crypto::proto::age::{self as proto, Key};
+use crate::{Ciphertext, Plaintext};
+
+/// Encrypt plaintext for the given recipients.
+///
+/// - `recipients`: list of age recipients to encrypt for
+/// - `plaintext`: plaintext to encrypt
+///
+/// # Panics
+///
+/// Panics if list of recipients is empty.
+pub fn encrypt(recipients: &[Key], plaintext: Plaintext) -> Result<Ciphertext> {
+    assert!(
+        !recipients.is_empty(),
+        "attempting to encrypt secret for empty list of recipients"
+    );
+
+    let recipients = recipients
+        .iter()
+        .map(|key| into_recipient(key))
+        .collect::<Result<Vec<_>>>()?;
+    let encryptor = age::Encryptor::with_recipients(recipients).unwrap();
+
+    let mut ciphertext = vec![];
+    let mut writer = encryptor
+        .wrap_output(&mut ciphertext)
+        .map_err(Err::Encrypt)?;
+    writer
+        .write_all(plaintext.unsecure_ref())
+        .map_err(Err::Write)?;
+    writer.finish().map_err(Err::Write)?;
+
+    Ok(Ciphertext::from(ciphertext))
+}
+
+/// Decrypt ciphertext.
+///
+/// - `config`: age config
+/// - `ciphertext`: ciphertext to decrypt
+pub fn decrypt(config: &Config, ciphertext: Ciphertext) -> Result<Plaintext> {
+    let identities = load_identities(config)?;
+    if identities.is_empty() {
+        return Err(Err::NoIdentities.into());
+    }
+
+    let decryptor = match age::Decryptor::new(ciphertext.unsecure_ref()).map_err(Err::Decrypt)? {
+        age::Decryptor::Recipients(decryptor) => decryptor,
+        age::Decryptor::Passphrase(_) => return Err(Err::Passphrase.into()),
+    };
+    let mut reader = decryptor
+        .decrypt(identities.iter().map(|i| i.identity.as_ref()))
+        .map_err(Err::Decrypt)?;
+
+    let mut plaintext = vec![];
+    reader.read_to_end(&mut plaintext).map_err(Err::Read)?;
+    Ok(Plaintext::from(plaintext))
+}
+
+/// Check whether we can decrypt ciphertext.
+///
+/// This checks whether we own an identity to decrypt the given ciphertext with.
+///
+/// - `config`: age config
+/// - `ciphertext`: ciphertext to check
+pub fn can_decrypt(config: &Config, ciphertext: Ciphertext) -> Result<bool> {
+    match decrypt(config, ciphertext) {
+        Ok(_) => Ok(true),
+        Err(err) => match err.downcast_ref::<Err>() {
+            Some(Err::NoIdentities)
+            | Some(Err::Passphrase)
+            | Some(Err::Decrypt(age::DecryptError::NoMatchingKeys)) => Ok(false),
+            _ => Err(err),
+        },
+    }
+}
+
+/// An identity we can decrypt with, along with its recipient.
+pub struct Identity {
+    /// The identity.
+    identity: Box<dyn age::Identity>,
+
+    /// Recipient key for this identity.
+    pub key: Key,
+}
+
+/// Load all identities from the configured identity files.
+///
+/// Identity files that don't exist are skipped.
+pub fn load_identities(config: &Config) -> Result<Vec<Identity>> {
+    let mut identities = vec![];
+
+    for path in config.identities.iter().filter(|path| path.is_file()) {
+        let data = Zeroizing::new(fs::read(path).map_err(Err::Read)?);
+
+        // Try native age identities first, then an SSH private key
+        if let Ok(file) = age::IdentityFile::from_buffer(BufReader::new(data.as_slice())) {
+            for entry in file.into_identities() {
+                #[allow(irrefutable_let_patterns)]
+                if let age::IdentityFileEntry::Native(identity) = entry {
+                    identities.push(Identity {
+                        key: Key {
+                            recipient: identity.to_public().to_string(),
+                            comment: None,
+                        },
+                        identity: Box::new(identity),
+                    });
+                }
+            }
+            continue;
+        }
+
+        let identity = age::ssh::Identity::from_buffer(
+            BufReader::new(data.as_slice()),
+            Some(path.display().to_string()),
+        )
+        .map_err(|err| Err::Identity(path.display().to_string(), err))?;
+        let recipient = match &identity {
+            age::ssh::Identity::Unencrypted(_) => {
+                age::ssh::Recipient::try_from(identity.clone())
+                    .map_err(|_| Err::UnsupportedSsh(path.display().to_string()))?
+            }
+            _ => return Err(Err::UnsupportedSsh(path.display().to_string()).into()),
+        };
+        identities.push(Identity {
+            key: Key {
+                recipient: recipient.to_string(),
+                comment: None,
+            },
+            identity: Box::new(identity),
+        });
+    }
+
+    Ok(identities)
+}
+
+/// Read all recipients from the recipients keychain file.
+///
+/// Returns an empty list if the file doesn't exist.
+pub fn read_keychain(config: &Config) -> Result<Vec<Key>> {
+    if !config.recipients.is_file() {
+        return Ok(vec![]);
+    }
+    proto::parse_recipients(&fs::read_to_string(&config.recipients).map_err(Err::Read)?)
+}
+
+/// Add the given recipients to the recipients keychain file.
+///
+/// Recipients that are already in the keychain are skipped.
+pub fn write_keychain(config: &Config, keys: &[Key]) -> Result<()> {
+    let existing = read_keychain(config)?;
+    let new: Vec<Key> = keys
+        .iter()
+        .filter(|key| !existing.contains(key))
+        .cloned()
+        .collect();
+    if new.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(dir) = config.recipients.parent() {
+        fs::create_dir_all(dir).map_err(Err::Write)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config.recipients)
+        .and_then(|mut file| file.write_all(proto::format_recipients(&new).as_bytes()))
+        .map_err(|err| Err::Write(err).into())
+}
+
+/// Generate a new X25519 identity.
+///
+/// The identity is appended to the first configured identity file, which is created with
+/// restrictive permissions if it doesn't exist.
+pub fn generate_identity(config: &Config) -> Result<Key> {
+    let path = config.identities.first().ok_or(Err::NoIdentities)?;
+    let identity = age::x25519::Identity::generate();
+    let key = Key {
+        recipient: identity.to_public().to_string(),
+        comment: None,
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(Err::Write)?;
+    }
+    let mut file = open_private(path).map_err(Err::Write)?;
+    let data = Zeroizing::new(format!(
+        "# public key: {}\n{}\n",
+        key.recipient,
+        identity.to_string().expose_secret(),
+    ));
+    file.write_all(data.as_bytes()).map_err(Err::Write)?;
+
+    Ok(key)
+}
+
+/// Open file for appending, create it readable for the current user only.
+fn open_private(path: &Path) -> io::Result<fs::File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Transform key into age recipient.
+fn into_recipient(key: &Key) -> Result<Box<dyn age::Recipient + Send>> {
+    if let Ok(recipient) = key.recipient.parse::<age::x25519::Recipient>() {
+        return Ok(Box::new(recipient));
+    }
+    match key.recipient.parse::<age::ssh::Recipient>() {
+        Ok(recipient) => Ok(Box::new(recipient)),
+        Err(_) => Err(Err::Recipient(key.recipient.clone()).into()),
+    }
+}
+
+/// age backend error.
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to encrypt plaintext")]
+    Encrypt(#[source] age::EncryptError),
+
+    #[error("failed to decrypt ciphertext")]
+    Decrypt(#[source] age::DecryptError),
+
+    #[error("failed to decrypt ciphertext, no age identity configured")]
+    NoIdentities,
+
+    #[error("failed to decrypt ciphertext, passphrase encrypted secrets are not supported")]
+    Passphrase,
+
+    #[error("failed to load age identity from {0}")]
+    Identity(String, #[source] io::Error),
+
+    #[error("failed to load age identity from {0}, only unencrypted ed25519 and RSA SSH keys are supported")]
+    UnsupportedSsh(String),
+
+    #[error("invalid age recipient: {0}")]
+    Recipient(String),
+
+    #[error("failed to read data")]
+    Read(#[source] io::Error),
+
+    #[error("failed to write data")]
+    Write(#[source] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build config with identity and recipient files in the given directory.
+    fn age_config(dir: &Path) -> Config {
+        Config {
+            identities: vec![dir.join("identities")],
+            recipients: dir.join("recipients"),
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = age_config(dir.path());
+        let key = generate_identity(&config).unwrap();
+        assert!(load_identities(&config).unwrap()[0].key == key);
+
+        let ciphertext = encrypt(&[key], Plaintext::from("secret")).unwrap();
+        let copy = Ciphertext::from(ciphertext.unsecure_ref().to_vec());
+        assert!(can_decrypt(&config, copy).unwrap());
+        assert_eq!(
+            decrypt(&config, ciphertext).unwrap().unsecure_ref(),
+            b"secret",
+        );
+
+        // Identity of other recipient cannot decrypt
+        let other = tempfile::tempdir().unwrap();
+        let other = age_config(other.path());
+        let ciphertext = encrypt(
+            &[generate_identity(&other).unwrap()],
+            Plaintext::from("secret"),
+        )
+        .unwrap();
+        let copy = Ciphertext::from(ciphertext.unsecure_ref().to_vec());
+        assert!(!can_decrypt(&config, copy).unwrap());
+        assert!(decrypt(&config, ciphertext).is_err());
+    }
+
+    #[test]
+    fn decrypt_no_identities() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = age_config(dir.path());
+        let key = Key::parse(&age::x25519::Identity::generate().to_public().to_string()).unwrap();
+        let ciphertext = encrypt(&[key], Plaintext::from("secret")).unwrap();
+
+        let err = decrypt(&config, ciphertext).err().unwrap();
+        assert!(matches!(err.downcast_ref::<Err>(), Some(Err::NoIdentities)));
+    }
+
+    #[test]
+    fn keychain() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = age_config(dir.path());
+        assert!(read_keychain(&config).unwrap().is_empty());
+
+        let mut key = generate_identity(&config).unwrap();
+        key.comment = Some("Alice".into());
+        write_keychain(&config, &[key.clone()]).unwrap();
+        write_keychain(&config, &[key.clone()]).unwrap();
+
+        // Known recipients are not added twice
+        let keys = read_keychain(&config).unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0] == key);
+        assert_eq!(keys[0].comment.as_deref(), Some("Alice"));
+    }
+}
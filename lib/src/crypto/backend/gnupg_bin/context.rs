@@ -6,8 +6,8 @@ use version_compare::Version;
 
 use super::raw_cmd::gpg_stdout_ok;
 use super::{raw, Config};
-use crate::crypto::{proto, Config as CryptoConfig, IsContext, Key, Proto};
-use crate::{Ciphertext, Plaintext, Recipients};
+use crate::crypto::{self, proto, Config as CryptoConfig, IsContext, Key, Proto, Verified};
+use crate::{util, Ciphertext, Plaintext, Recipients};
 
 /// Binary name.
 #[cfg(not(windows))]
@@ -23,19 +23,41 @@ pub fn context(config: &CryptoConfig) -> Result<Context, Err> {
     let mut gpg_config = find_gpg_bin().map_err(Err::Context)?;
     gpg_config.gpg_tty = config.gpg_tty;
     gpg_config.verbose = config.verbose;
-    Ok(Context::from(gpg_config))
+
+    // Use global GnuPG home for secret keys, custom home for public keys if set
+    let mut secret_config = gpg_config.clone();
+    secret_config.passphrase = config.gpg_passphrase.clone();
+    if let Some(home) = &config.gpg_home {
+        util::fs::create_private_dir(home).map_err(Err::Context)?;
+        gpg_config.gpg_home = Some(home.clone());
+    }
+
+    Ok(Context::from(gpg_config, secret_config, config.sign))
 }
 
 /// GnuPG binary context.
 pub struct Context {
     /// GPG config.
     config: Config,
+
+    /// GPG config for operations using secret keys.
+    ///
+    /// This always uses the global GnuPG home, so secret keys are available through the users
+    /// agent when a custom GnuPG home is used for public keys.
+    secret_config: Config,
+
+    /// Whether to sign when encrypting.
+    sign: bool,
 }
 
 impl Context {
     /// Construct context from GPG config.
-    fn from(config: Config) -> Self {
-        Self { config }
+    fn from(config: Config, secret_config: Config, sign: bool) -> Self {
+        Self {
+            config,
+            secret_config,
+            sign,
+        }
     }
 }
 
@@ -47,38 +69,42 @@ impl IsContext for Context {
             .map(|key| key.fingerprint(false))
             .collect();
         let fingerprints: Vec<&str> = fingerprints.iter().map(|fp| fp.as_str()).collect();
-        raw::encrypt(&self.config, &fingerprints, plaintext)
+        if !self.sign {
+            return raw::encrypt(&self.config, &fingerprints, None, plaintext);
+        }
+
+        // Signing needs recipients and secret key in the same keyring
+        if self.config.gpg_home.is_some() {
+            return Err(Err::SignCustomHome.into());
+        }
+        let signer = crypto::find_signing_key(self, recipients)?.fingerprint(false);
+        raw::encrypt(&self.secret_config, &fingerprints, Some(&signer), plaintext)
     }
 
     fn decrypt(&mut self, ciphertext: Ciphertext) -> Result<Plaintext> {
-        raw::decrypt(&self.config, ciphertext)
+        raw::decrypt(&self.secret_config, ciphertext)
+    }
+
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        let (plaintext, signers) = raw::decrypt_verify(&self.secret_config, ciphertext)?;
+        Ok(Verified { plaintext, signers })
     }
 
     fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
-        raw::can_decrypt(&self.config, ciphertext)
+        raw::can_decrypt(&self.secret_config, ciphertext)
     }
 
     fn keys_public(&mut self) -> Result<Vec<Key>> {
         Ok(raw::public_keys(&self.config)?
             .into_iter()
-            .map(|key| {
-                Key::Gpg(proto::gpg::Key {
-                    fingerprint: key.0,
-                    user_ids: key.1,
-                })
-            })
+            .map(|key| key.into_key())
             .collect())
     }
 
     fn keys_private(&mut self) -> Result<Vec<Key>> {
-        Ok(raw::private_keys(&self.config)?
+        Ok(raw::private_keys(&self.secret_config)?
             .into_iter()
-            .map(|key| {
-                Key::Gpg(proto::gpg::Key {
-                    fingerprint: key.0,
-                    user_ids: key.1,
-                })
-            })
+            .map(|key| key.into_key())
             .collect())
     }
 
@@ -86,8 +112,41 @@ impl IsContext for Context {
         raw::import_key(&self.config, key)
     }
 
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        Ok(raw::read_keys(&self.config, key)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
     fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
-        raw::export_key(&self.config, &key.fingerprint(false))
+        // Own keys may only be in the global keyring when using a custom GnuPG home
+        let fingerprint = key.fingerprint(false);
+        if self.config.gpg_home.is_some()
+            && raw::primary_fingerprint(&self.config, &fingerprint)?.is_none()
+        {
+            return raw::export_key(&self.secret_config, &fingerprint);
+        }
+        raw::export_key(&self.config, &fingerprint)
+    }
+
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>> {
+        proto::gpg::pkesk_key_ids(ciphertext.unsecure_ref())
+    }
+
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>> {
+        match raw::primary_fingerprint(&self.config, key_id)? {
+            Some(fingerprint) => self.get_public_key(&fingerprint).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+        raw::sign_detached(&self.secret_config, &key.fingerprint(false), data)
+    }
+
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+        raw::verify_detached(&self.config, data, signature)
     }
 
     fn supports_proto(&self, proto: Proto) -> bool {
@@ -132,6 +191,9 @@ pub enum Err {
     #[error("failed to obtain GnuPG binary cryptography context")]
     Context(#[source] anyhow::Error),
 
+    #[error("signing secrets is not supported with a custom GnuPG home")]
+    SignCustomHome,
+
     #[error("failed to find GnuPG gpg binary")]
     Unavailable(#[source] which::Error),
 
@@ -6,7 +6,10 @@ mod raw_cmd;
 
 use std::path::PathBuf;
 
+use crate::Plaintext;
+
 /// GPG config.
+#[derive(Clone)]
 pub struct Config {
     /// GPG binary.
     bin: PathBuf,
@@ -14,6 +17,12 @@ pub struct Config {
     /// Use TTY for GPG password input, rather than GUI pinentry.
     pub gpg_tty: bool,
 
+    /// Custom GnuPG home directory, uses the default home if not set.
+    pub gpg_home: Option<PathBuf>,
+
+    /// Passphrase for secret keys, uses loopback pinentry mode if set.
+    pub passphrase: Option<Plaintext>,
+
     /// Whether to show verbose output.
     pub verbose: bool,
 }
@@ -26,6 +35,8 @@ impl Config {
         Self {
             bin,
             gpg_tty: false,
+            gpg_home: None,
+            passphrase: None,
             verbose: false,
         }
     }
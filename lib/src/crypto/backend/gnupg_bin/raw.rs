@@ -2,14 +2,19 @@
 //!
 //! This provides the most basic and bare functions to interface with a GnuPG backend binary.
 
-use std::collections::VecDeque;
+use std::io::Write;
 
 use anyhow::Result;
 use regex::Regex;
 use thiserror::Error;
 
-use super::raw_cmd::{gpg_stdin_output, gpg_stdin_stdout_ok_bin, gpg_stdout_ok, gpg_stdout_ok_bin};
+use super::raw_cmd::{
+    cmd_assert_status, gpg_input_output, gpg_input_output_status, gpg_input_stdout_ok_bin,
+    gpg_output, gpg_secret_input_stdout_ok_bin, gpg_stdin_output, gpg_stdin_stdout_ok_bin,
+    gpg_stdout_ok, gpg_stdout_ok_bin,
+};
 use super::Config;
+use crate::crypto::proto::gpg::{Capabilities, Key, Validity};
 use crate::crypto::util;
 use crate::{Ciphertext, Plaintext};
 
@@ -20,12 +25,18 @@ const GPG_OUTPUT_ERR_NO_SECKEY: &str = "decryption failed: No secret key";
 ///
 /// - `config`: GPG config
 /// - `recipients`: list of recipient fingerprints to encrypt for
+/// - `signer`: fingerprint of secret key to sign with, not signed if `None`
 /// - `plaintext`: plaintext to encrypt
 ///
 /// # Panics
 ///
 /// Panics if list of recipients is empty.
-pub fn encrypt(config: &Config, recipients: &[&str], plaintext: Plaintext) -> Result<Ciphertext> {
+pub fn encrypt(
+    config: &Config,
+    recipients: &[&str],
+    signer: Option<&str>,
+    plaintext: Plaintext,
+) -> Result<Ciphertext> {
     assert!(
         !recipients.is_empty(),
         "attempting to encrypt secret for empty list of recipients"
@@ -37,11 +48,16 @@ pub fn encrypt(config: &Config, recipients: &[&str], plaintext: Plaintext) -> Re
         args.push("--recipient");
         args.push(fp);
     }
+    if let Some(signer) = signer {
+        args.push("--local-user");
+        args.push(signer);
+        args.push("--sign");
+    }
     args.push("--encrypt");
 
     Ok(Ciphertext::from(
-        gpg_stdin_stdout_ok_bin(config, args.as_slice(), plaintext.unsecure_ref())
-            .map_err(Err::Decrypt)?,
+        gpg_secret_input_stdout_ok_bin(config, args.as_slice(), plaintext.unsecure_ref())
+            .map_err(Err::Encrypt)?,
     ))
 }
 
@@ -52,11 +68,35 @@ pub fn encrypt(config: &Config, recipients: &[&str], plaintext: Plaintext) -> Re
 pub fn decrypt(config: &Config, ciphertext: Ciphertext) -> Result<Plaintext> {
     // TODO: ensure ciphertext ends with PGP footer
     Ok(Plaintext::from(
-        gpg_stdin_stdout_ok_bin(config, ["--quiet", "--decrypt"], ciphertext.unsecure_ref())
+        gpg_input_stdout_ok_bin(config, &["--quiet", "--decrypt"], ciphertext.unsecure_ref())
             .map_err(Err::Decrypt)?,
     ))
 }
 
+/// Decrypt ciphertext, and verify its signatures.
+///
+/// Returns the plaintext, and the primary key fingerprints of all valid signatures.
+///
+/// - `config`: GPG config
+/// - `ciphertext`: ciphertext to decrypt
+pub fn decrypt_verify(config: &Config, ciphertext: Ciphertext) -> Result<(Plaintext, Vec<String>)> {
+    let (output, status) =
+        gpg_input_output_status(config, &["--quiet", "--decrypt"], ciphertext.unsecure_ref())
+            .map_err(Err::Decrypt)?;
+
+    // Exit code is non-zero on bad or unknown signatures, decryption itself may have succeeded
+    if !status
+        .lines()
+        .any(|line| line.starts_with("[GNUPG:] DECRYPTION_OKAY"))
+    {
+        cmd_assert_status(config, &output).map_err(Err::Decrypt)?;
+        return Err(Err::Decrypt(Err::UnexpectedOutput.into()).into());
+    }
+
+    let signers = parse_valid_signatures(&status);
+    Ok((Plaintext::from(output.stdout), signers))
+}
+
 /// Check whether we can decrypt ciphertext.
 ///
 /// This checks whether whether we own the secret key to decrypt the given ciphertext.
@@ -68,7 +108,7 @@ pub fn decrypt(config: &Config, ciphertext: Ciphertext) -> Result<Plaintext> {
 pub fn can_decrypt(config: &Config, ciphertext: Ciphertext) -> Result<bool> {
     // TODO: ensure ciphertext ends with PGP footer
 
-    let output = gpg_stdin_output(config, ["--quiet", "--decrypt"], ciphertext.unsecure_ref())
+    let output = gpg_input_output(config, &["--quiet", "--decrypt"], ciphertext.unsecure_ref())
         .map_err(Err::Decrypt)?;
 
     match output.status.code() {
@@ -81,18 +121,24 @@ pub fn can_decrypt(config: &Config, ciphertext: Ciphertext) -> Result<bool> {
 /// Get all public keys from keychain.
 ///
 /// - `config`: GPG config
-pub fn public_keys(config: &Config) -> Result<Vec<KeyId>> {
-    let list =
-        gpg_stdout_ok(config, ["--list-keys", "--keyid-format", "LONG"]).map_err(Err::Keys)?;
+pub fn public_keys(config: &Config) -> Result<Vec<Key>> {
+    let list = gpg_stdout_ok(
+        config,
+        ["--list-keys", "--with-colons", "--fixed-list-mode"],
+    )
+    .map_err(Err::Keys)?;
     parse_key_list(list).ok_or_else(|| Err::UnexpectedOutput.into())
 }
 
 /// Get all private/secret keys from keychain.
 ///
 /// - `config`: GPG config
-pub fn private_keys(config: &Config) -> Result<Vec<KeyId>> {
-    let list = gpg_stdout_ok(config, ["--list-secret-keys", "--keyid-format", "LONG"])
-        .map_err(Err::Keys)?;
+pub fn private_keys(config: &Config) -> Result<Vec<Key>> {
+    let list = gpg_stdout_ok(
+        config,
+        ["--list-secret-keys", "--with-colons", "--fixed-list-mode"],
+    )
+    .map_err(Err::Keys)?;
     parse_key_list(list).ok_or_else(|| Err::UnexpectedOutput.into())
 }
 
@@ -121,6 +167,28 @@ pub fn import_key(config: &Config, key: &[u8]) -> Result<()> {
         .map_err(|err| Err::Import(err).into())
 }
 
+/// Read public keys from bytes, without importing them into the keychain.
+///
+/// - `config`: GPG config
+/// - `key`: armored public keys
+pub fn read_keys(config: &Config, key: &[u8]) -> Result<Vec<Key>> {
+    let list = gpg_stdin_stdout_ok_bin(
+        config,
+        [
+            "--quiet",
+            "--with-colons",
+            "--fixed-list-mode",
+            "--import-options",
+            "show-only",
+            "--import",
+        ],
+        key,
+    )
+    .map_err(Err::Keys)?;
+    parse_key_list(String::from_utf8_lossy(&list).into())
+        .ok_or_else(|| Err::UnexpectedOutput.into())
+}
+
 /// Export the given key as bytes.
 ///
 /// # Panics
@@ -146,80 +214,228 @@ pub fn export_key(config: &Config, fingerprint: &str) -> Result<Vec<u8>> {
     Ok(data)
 }
 
-/// A key identifier with a fingerprint and user IDs.
-#[derive(Clone)]
-pub struct KeyId(pub String, pub Vec<String>);
-
-/// Parse key list output from gnupg.
-// TODO: throw proper errors on parse failure
-fn parse_key_list(list: String) -> Option<Vec<KeyId>> {
-    // Return empty list if there's no key loaded
-    if list.trim().is_empty() {
-        return Some(vec![]);
-    }
-
-    let mut lines: VecDeque<_> = list.lines().collect();
-
-    // Second line must be a line
-    lines.pop_front()?;
-    if lines
-        .pop_front()?
-        .bytes()
-        .filter(|&b| b != b'-')
-        .take(1)
-        .count()
-        > 0
-    {
-        return None;
+/// Get the primary key fingerprint for the (sub)key with the given key ID.
+///
+/// Returns `None` if no such key is in the keychain.
+///
+/// - `config`: GPG config
+/// - `key_id`: key ID of primary key or subkey
+pub fn primary_fingerprint(config: &Config, key_id: &str) -> Result<Option<String>> {
+    let output = gpg_output(
+        config,
+        ["--with-colons", "--list-keys", &format!("0x{key_id}")],
+    )
+    .map_err(Err::Keys)?;
+    if !output.status.success() {
+        return Ok(None);
     }
 
+    // First fingerprint record belongs to the primary key
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("fpr:"))
+        .and_then(|line| line.split(':').find(|field| !field.is_empty()))
+        .map(util::format_fingerprint))
+}
+
+/// Create a detached signature over data.
+///
+/// - `config`: GPG config
+/// - `fingerprint`: fingerprint of secret key to sign with
+/// - `data`: data to sign
+pub fn sign_detached(config: &Config, fingerprint: &str, data: &[u8]) -> Result<Vec<u8>> {
+    gpg_input_stdout_ok_bin(
+        config,
+        &["--quiet", "--local-user", fingerprint, "--detach-sign"],
+        data,
+    )
+    .map_err(|err| Err::Sign(err).into())
+}
+
+/// Verify a detached signature over data.
+///
+/// Returns the primary key fingerprints of all valid signatures.
+///
+/// - `config`: GPG config
+/// - `data`: signed data
+/// - `signature`: detached signature
+pub fn verify_detached(config: &Config, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+    // gpg reads the signature from a file, data is passed through stdin
+    let mut file = tempfile::NamedTempFile::new().map_err(|err| Err::Verify(err.into()))?;
+    file.write_all(signature)
+        .map_err(|err| Err::Verify(err.into()))?;
+    let path = file.path().to_string_lossy().to_string();
+
+    // Status output is checked, exit code is non-zero on invalid signatures
+    let output = gpg_stdin_output(
+        config,
+        ["--quiet", "--status-fd", "1", "--verify", &path, "-"],
+        data,
+    )
+    .map_err(Err::Verify)?;
+    Ok(parse_valid_signatures(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse colon formatted key list output from gnupg.
+///
+/// See `doc/DETAILS` in the GnuPG sources for a description of the format.
+// TODO: throw proper errors on parse failure
+fn parse_key_list(list: String) -> Option<Vec<Key>> {
     let re_fingerprint = Regex::new(r"^[0-9A-F]{16,}$").unwrap();
-    let re_user_id = Regex::new(r"^uid\s*\[[a-z ]+\]\s*(.*)$").unwrap();
 
-    // Walk through the list, collect list of keys
-    let mut keys = Vec::new();
-    while !lines.is_empty() {
-        match lines.pop_front()? {
+    // Walk through the records, collect list of keys
+    let mut keys: Vec<Key> = Vec::new();
+    let mut primary_fingerprint = false;
+    for line in list.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        match fields[0] {
             // Start reading a new key
-            l if l.starts_with("pub ") || l.starts_with("sec ") => {
-                // Get the fingerprint
-                let fingerprint = util::format_fingerprint(lines.pop_front()?.trim());
+            "pub" | "sec" => {
+                let capabilities = fields.get(11)?;
+                keys.push(Key {
+                    fingerprint: String::new(),
+                    user_ids: vec![],
+                    validity: parse_validity(fields.get(1)?, capabilities),
+                    expires: fields.get(6)?.parse().ok(),
+                    capabilities: Capabilities::from_letters(capabilities),
+                });
+                primary_fingerprint = true;
+            }
+
+            // Read fingerprint of primary key, ignore subkey fingerprints
+            "fpr" if primary_fingerprint => {
+                let fingerprint = util::format_fingerprint(fields.get(9)?);
                 if !re_fingerprint.is_match(&fingerprint) {
                     return None;
                 }
+                keys.last_mut()?.fingerprint = fingerprint;
+                primary_fingerprint = false;
+            }
 
-                // Find and parse user IDs
-                let mut user_ids = Vec::new();
-                while !lines.is_empty() {
-                    match lines.pop_front()? {
-                        // Read user ID
-                        l if l.starts_with("uid ") => {
-                            let captures = re_user_id.captures(l)?;
-                            user_ids.push(captures[1].to_string());
-                        }
-
-                        // Finalize on empty line
-                        l if l.trim().is_empty() => break,
-
-                        _ => {}
-                    }
+            // Read user ID, skip revoked and expired ones of valid keys like gnupg does
+            "uid" => {
+                let key = keys.last_mut()?;
+                if key.validity != Validity::Valid || !matches!(fields.get(1), Some(&"r" | &"e")) {
+                    key.user_ids.push(unescape_field(fields.get(9)?));
                 }
-
-                // Add read key to list
-                keys.push(KeyId(fingerprint, user_ids));
             }
 
-            // Ignore empty lines
-            l if l.trim().is_empty() => {}
-
-            // Got something unexpected
-            _ => return None,
+            // Subkeys and other records are ignored
+            _ => {}
         }
     }
 
+    // Every key must have a fingerprint
+    if keys.iter().any(|key| key.fingerprint.is_empty()) {
+        return None;
+    }
+
     Some(keys)
 }
 
+/// Parse key validity from colon formatted key list fields.
+///
+/// - `validity`: validity field
+/// - `capabilities`: key capabilities field, which marks disabled keys
+fn parse_validity(validity: &str, capabilities: &str) -> Validity {
+    match validity {
+        "e" => Validity::Expired,
+        "r" => Validity::Revoked,
+        "i" => Validity::Invalid,
+        "d" => Validity::Disabled,
+        _ if capabilities.contains('D') => Validity::Disabled,
+        _ => Validity::Valid,
+    }
+}
+
+/// Unescape a colon formatted key list field.
+///
+/// Special characters are escaped as `\xNN` in these fields.
+fn unescape_field(field: &str) -> String {
+    let mut bytes = Vec::with_capacity(field.len());
+    let mut rest = field.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'\\' && tail.len() >= 3 && tail[0] == b'x' {
+            let escaped = std::str::from_utf8(&tail[1..3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(escaped) = escaped {
+                bytes.push(escaped);
+                rest = &tail[3..];
+                continue;
+            }
+        }
+        bytes.push(byte);
+        rest = tail;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Parse primary key fingerprints of valid signatures from gnupg status output.
+///
+/// A signature is only valid if gnupg reports both `VALIDSIG` and `GOODSIG` for the same key.
+/// Signatures made by expired or revoked keys, bad signatures and expired signatures are
+/// rejected, even though gnupg reports `VALIDSIG` for some of these. Status lines are grouped per
+/// signature by `NEWSIG`.
+fn parse_valid_signatures(status: &str) -> Vec<String> {
+    let mut signatures: Vec<Vec<&str>> = vec![vec![]];
+    for line in status.lines() {
+        let line = match line.strip_prefix("[GNUPG:] ") {
+            Some(line) => line,
+            None => continue,
+        };
+        if line.starts_with("NEWSIG") {
+            signatures.push(vec![]);
+        }
+        signatures.last_mut().unwrap().push(line);
+    }
+
+    let mut fingerprints = vec![];
+    for lines in signatures {
+        // Key IDs per status keyword, a key ID may be a long key ID or a full fingerprint
+        let key_ids = |keyword: &str| -> Vec<String> {
+            lines
+                .iter()
+                .filter_map(|line| line.strip_prefix(keyword))
+                .filter_map(|line| line.strip_prefix(' '))
+                .filter_map(|line| line.split_whitespace().next())
+                .map(|id| id.to_uppercase())
+                .collect()
+        };
+        let good = key_ids("GOODSIG");
+        let bad: Vec<String> = ["BADSIG", "EXPSIG", "EXPKEYSIG", "REVKEYSIG", "ERRSIG"]
+            .iter()
+            .flat_map(|keyword| key_ids(keyword))
+            .collect();
+
+        for line in lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("VALIDSIG "))
+        {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let signing_key = match fields.first() {
+                Some(fingerprint) => fingerprint.to_uppercase(),
+                None => continue,
+            };
+            let matches = |id: &String| signing_key.ends_with(id.as_str());
+            if !good.iter().any(matches) || bad.iter().any(matches) {
+                continue;
+            }
+
+            // Primary key fingerprint is the last field, fall back to the signing key
+            let fingerprint = if fields.len() >= 10 {
+                fields[fields.len() - 1]
+            } else {
+                fields[0]
+            };
+            fingerprints.push(util::format_fingerprint(fingerprint));
+        }
+    }
+    fingerprints
+}
+
 /// GnuPG binary error.
 #[derive(Debug, Error)]
 pub enum Err {
@@ -240,4 +456,167 @@ pub enum Err {
 
     #[error("failed to export key from gpg keychain")]
     Export(#[source] anyhow::Error),
+
+    #[error("failed to sign data")]
+    Sign(#[source] anyhow::Error),
+
+    #[error("failed to verify signature")]
+    Verify(#[source] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Key list from `gpg --list-keys --with-colons --fixed-list-mode`, with a valid key, a
+    /// revoked key, an expired key and a disabled key.
+    const KEY_LIST: &str = r"tru:o:1:1792206624:1:3:1:5
+pub:u:255:22:795FD9B5C876523F:1792206623:::u:::scESC:::::ed25519:::0:
+fpr:::::::::68EA3744E3B9D85433B71104795FD9B5C876523F:
+uid:u::::1792206623::25CB42FE28B6C6E8EDFF0277B2917A7F3265BBDF::Alice\x3a Admin <alice@example.com>::::::::::0:
+uid:r::::::81F62CB3A9880DF29B4DBCD8FC9DF0F5811779C5::Alice Old <alice@old.example>::::::::::0:
+sub:u:255:18:A19B1549DEB1A779:1792206631::::::e:::::cv25519::
+fpr:::::::::AC8B990829E714C0790E261AA19B1549DEB1A779:
+pub:r:255:22:52E538F1F886AF90:1792206624:::-:::sc:::::ed25519:::0:
+fpr:::::::::830171328A8155DD8EB1353B52E538F1F886AF90:
+uid:r::::1792206624::53B786D9927B2601EBBF42093AAF7D24E6BB9B4E::Bob <bob@example.com>::::::::::0:
+pub:e:255:22:38D5FF528CDA35F8:1792206624:1792293027::u:::sc:::::ed25519:::0:
+fpr:::::::::4BD96B6683475F935743E3C738D5FF528CDA35F8:
+uid:e::::1792206627::9A8B27709304C03D51AB98068E24AC0CE75B212F::Carol <carol@example.com>::::::::::0:
+uid:r::::::EFB7BEB1E98661058AFEF356F6CFC91CAF8891CD::Carol Old <carol@old.example>::::::::::0:
+pub:u:255:22:CFF280C55286001B:1792206624:::u:::scSCD:::::ed25519:::0:
+fpr:::::::::FD63147C61C9436BB05E1D50CFF280C55286001B:
+uid:u::::1792206624::109AC36AEA60CD3A9401F42A78CD72AED9C67095::Dave <dave@example.com>::::::::::0:
+";
+
+    #[test]
+    fn key_list() {
+        let keys = parse_key_list(KEY_LIST.into()).unwrap();
+        assert_eq!(keys.len(), 4);
+
+        // Subkey fingerprint and revoked user ID of valid key are skipped
+        let alice = &keys[0];
+        assert_eq!(
+            alice.fingerprint,
+            "68EA3744E3B9D85433B71104795FD9B5C876523F"
+        );
+        assert_eq!(alice.user_ids, vec!["Alice: Admin <alice@example.com>"]);
+        assert_eq!(alice.validity, Validity::Valid);
+        assert_eq!(alice.expires, None);
+        assert_eq!(alice.capabilities.to_string(), "ESC");
+
+        let bob = &keys[1];
+        assert_eq!(bob.fingerprint, "830171328A8155DD8EB1353B52E538F1F886AF90");
+        assert_eq!(bob.user_ids, vec!["Bob <bob@example.com>"]);
+        assert_eq!(bob.validity, Validity::Revoked);
+        assert_eq!(bob.capabilities, Capabilities::default());
+
+        // User IDs of invalid keys are all kept
+        let carol = &keys[2];
+        assert_eq!(carol.validity, Validity::Expired);
+        assert_eq!(carol.expires, Some(1792293027));
+        assert_eq!(
+            carol.user_ids,
+            vec!["Carol <carol@example.com>", "Carol Old <carol@old.example>"],
+        );
+
+        let dave = &keys[3];
+        assert_eq!(dave.validity, Validity::Disabled);
+        assert_eq!(dave.capabilities.to_string(), "SC");
+    }
+
+    #[test]
+    fn key_list_invalid() {
+        assert!(parse_key_list(String::new()).unwrap().is_empty());
+
+        // Missing or malformed fingerprint
+        let list = "pub:u:255:22:795FD9B5C876523F:1792206623:::u:::scESC:::::ed25519:::0:\n";
+        assert!(parse_key_list(list.into()).is_none());
+        let list = format!("{list}fpr:::::::::not-a-fingerprint:\n");
+        assert!(parse_key_list(list).is_none());
+
+        // Truncated records
+        assert!(parse_key_list("pub:u:255".into()).is_none());
+        assert!(parse_key_list("uid:u::::1792206623".into()).is_none());
+    }
+
+    #[test]
+    fn validity() {
+        assert_eq!(parse_validity("u", "scESC"), Validity::Valid);
+        assert_eq!(parse_validity("-", "scESC"), Validity::Valid);
+        assert_eq!(parse_validity("e", "sc"), Validity::Expired);
+        assert_eq!(parse_validity("r", "sc"), Validity::Revoked);
+        assert_eq!(parse_validity("i", "sc"), Validity::Invalid);
+        assert_eq!(parse_validity("d", "sc"), Validity::Disabled);
+        assert_eq!(parse_validity("u", "scSCD"), Validity::Disabled);
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(
+            unescape_field(r"Alice\x3a Admin <alice@example.com>"),
+            "Alice: Admin <alice@example.com>",
+        );
+        assert_eq!(unescape_field(r"back\x5cslash"), r"back\slash");
+        assert_eq!(unescape_field(r"J\xc3\xa9r\xc3\xb4me"), "Jérôme");
+
+        // Invalid or truncated escapes are kept as-is
+        assert_eq!(unescape_field(r"a\xzz"), r"a\xzz");
+        assert_eq!(unescape_field(r"a\x3"), r"a\x3");
+        assert_eq!(unescape_field(r"a\"), r"a\");
+    }
+
+    /// Status output of `gpg --status-fd 1 --decrypt` for a good signature, an expired key
+    /// signature, a revoked key signature and a bad signature.
+    const STATUS_GOOD: &str = "[GNUPG:] NEWSIG eve@example.com
+[GNUPG:] KEY_CONSIDERED 0FFEA52F37AFCBF74425BBD873F25CFCD237E345 0
+[GNUPG:] SIG_ID Ue3Ax3L4Ym4QApK9Eor0yAmRJO4 2026-10-17 1792207519
+[GNUPG:] GOODSIG 73F25CFCD237E345 Eve <eve@example.com>
+[GNUPG:] VALIDSIG 0FFEA52F37AFCBF74425BBD873F25CFCD237E345 2026-10-17 1792207519 0 4 0 22 8 00 0FFEA52F37AFCBF74425BBD873F25CFCD237E345
+[GNUPG:] TRUST_ULTIMATE 0 pgp
+";
+    const STATUS_EXPIRED: &str = "[GNUPG:] NEWSIG exp@example.com
+[GNUPG:] KEY_CONSIDERED 7EF2617A6CB433EAC410D193F0555E5CB7AD0630 0
+[GNUPG:] KEYEXPIRED 1577923200
+[GNUPG:] SIG_ID lDk7Irc3Lg71N0V40yLqHQh5APo 2020-01-01 1577840400
+[GNUPG:] EXPKEYSIG F0555E5CB7AD0630 Exp <exp@example.com>
+[GNUPG:] VALIDSIG 7EF2617A6CB433EAC410D193F0555E5CB7AD0630 2020-01-01 1577840400 0 4 0 22 8 00 7EF2617A6CB433EAC410D193F0555E5CB7AD0630
+";
+    const STATUS_REVOKED: &str = "[GNUPG:] NEWSIG rev@example.com
+[GNUPG:] KEY_CONSIDERED 5845D423DB7FCCA342F93BAEB001CD7B6305DA65 0
+[GNUPG:] SIG_ID BW1LxfuOBEHaOYeNQ1Gq3amD23E 2026-10-17 1792207519
+[GNUPG:] REVKEYSIG B001CD7B6305DA65 Rev <rev@example.com>
+[GNUPG:] VALIDSIG 5845D423DB7FCCA342F93BAEB001CD7B6305DA65 2026-10-17 1792207519 0 4 0 22 8 00 5845D423DB7FCCA342F93BAEB001CD7B6305DA65
+[GNUPG:] KEYREVOKED
+[GNUPG:] TRUST_ULTIMATE 0 pgp
+";
+    const STATUS_BAD: &str = "[GNUPG:] NEWSIG eve@example.com
+[GNUPG:] KEY_CONSIDERED 0FFEA52F37AFCBF74425BBD873F25CFCD237E345 0
+[GNUPG:] BADSIG 73F25CFCD237E345 Eve <eve@example.com>
+[GNUPG:] FAILURE gpg-exit 33554433
+";
+
+    #[test]
+    fn valid_signatures() {
+        let eve = "0FFEA52F37AFCBF74425BBD873F25CFCD237E345";
+        assert_eq!(parse_valid_signatures(STATUS_GOOD), vec![eve]);
+        assert!(parse_valid_signatures(STATUS_EXPIRED).is_empty());
+        assert!(parse_valid_signatures(STATUS_REVOKED).is_empty());
+        assert!(parse_valid_signatures(STATUS_BAD).is_empty());
+
+        // Only the good signature is valid if there are many
+        let all = [STATUS_EXPIRED, STATUS_GOOD, STATUS_REVOKED, STATUS_BAD].concat();
+        assert_eq!(parse_valid_signatures(&all), vec![eve]);
+
+        // A valid signature must have a good signature for the same key
+        let no_good: String = STATUS_GOOD
+            .lines()
+            .filter(|line| !line.contains("GOODSIG"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert!(parse_valid_signatures(&no_good).is_empty());
+        let other_good =
+            STATUS_GOOD.replace("GOODSIG 73F25CFCD237E345", "GOODSIG F0555E5CB7AD0630");
+        assert!(parse_valid_signatures(&other_good).is_empty());
+    }
 }
@@ -9,7 +9,7 @@ use anyhow::Result;
 use thiserror::Error;
 
 use super::Config;
-use crate::util;
+use crate::{util, Plaintext};
 
 /// Invoke a gpg command, returns output.
 pub(super) fn gpg_output<I, S>(config: &Config, args: I) -> Result<Output>
@@ -41,6 +41,102 @@ where
         .map_err(|err| Err::System(err).into())
 }
 
+/// Invoke a gpg command with input data, returns output.
+///
+/// The input is passed through stdin. If a passphrase is configured, the passphrase is passed
+/// through stdin instead using loopback pinentry mode, and the input is passed through a
+/// temporary file that is appended to the arguments.
+pub(super) fn gpg_input_output(config: &Config, args: &[&str], input: &[u8]) -> Result<Output> {
+    let passphrase = match &config.passphrase {
+        Some(passphrase) => passphrase,
+        None => return gpg_stdin_output(config, args, input),
+    };
+
+    let mut file = tempfile::NamedTempFile::new().map_err(Err::System)?;
+    file.write_all(input).map_err(Err::System)?;
+    let path = file.path().to_string_lossy().to_string();
+
+    let mut passphrase_args = vec![
+        "--batch",
+        "--pinentry-mode",
+        "loopback",
+        "--passphrase-fd",
+        "0",
+    ];
+    passphrase_args.extend_from_slice(args);
+    passphrase_args.push(&path);
+    gpg_stdin_output(config, passphrase_args, passphrase.unsecure_ref())
+}
+
+/// Invoke a gpg command with input data, returns output and machine readable status.
+///
+/// The status is written to a dedicated temporary file, so it can't be mixed up with other
+/// output of gpg. See `gpg_input_output` for how input is passed.
+pub(super) fn gpg_input_output_status(
+    config: &Config,
+    args: &[&str],
+    input: &[u8],
+) -> Result<(Output, String)> {
+    let status_file = tempfile::NamedTempFile::new().map_err(Err::System)?;
+    let status_path = status_file.path().to_string_lossy().to_string();
+
+    let mut status_args = vec!["--status-file", &status_path];
+    status_args.extend_from_slice(args);
+    let output = gpg_input_output(config, &status_args, input)?;
+
+    let status = std::fs::read(status_file.path()).map_err(Err::System)?;
+    Ok((output, String::from_utf8_lossy(&status).into_owned()))
+}
+
+/// Invoke a gpg command with input data, return stdout on success.
+///
+/// See `gpg_input_output` for how input is passed.
+pub(super) fn gpg_input_stdout_ok_bin(
+    config: &Config,
+    args: &[&str],
+    input: &[u8],
+) -> Result<Vec<u8>> {
+    let output = gpg_input_output(config, args, input)?;
+    cmd_assert_status(config, &output)?;
+    Ok(output.stdout)
+}
+
+/// Invoke a gpg command with secret input data, return stdout on success.
+///
+/// The input is passed through stdin. If a passphrase is configured, it is passed through stdin
+/// as well, on a single line before the input, using loopback pinentry mode. Unlike
+/// `gpg_input_output`, the input is never written to a temporary file.
+pub(super) fn gpg_secret_input_stdout_ok_bin(
+    config: &Config,
+    args: &[&str],
+    input: &[u8],
+) -> Result<Vec<u8>> {
+    let passphrase = match &config.passphrase {
+        Some(passphrase) => passphrase,
+        None => return gpg_stdin_stdout_ok_bin(config, args, input),
+    };
+
+    // gpg reads a single line from the passphrase fd, the rest of stdin is input
+    if passphrase.unsecure_ref().contains(&b'\n') {
+        return Err(Err::PassphraseNewline.into());
+    }
+    let mut stdin = Vec::with_capacity(passphrase.unsecure_ref().len() + 1 + input.len());
+    stdin.extend_from_slice(passphrase.unsecure_ref());
+    stdin.push(b'\n');
+    stdin.extend_from_slice(input);
+    let stdin = Plaintext::from(stdin);
+
+    let mut passphrase_args = vec![
+        "--batch",
+        "--pinentry-mode",
+        "loopback",
+        "--passphrase-fd",
+        "0",
+    ];
+    passphrase_args.extend_from_slice(args);
+    gpg_stdin_stdout_ok_bin(config, passphrase_args, stdin.unsecure_ref())
+}
+
 /// Invoke a gpg command with the given arguments, return stdout on success.
 pub(super) fn gpg_stdout_ok_bin<I, S>(config: &Config, args: I) -> Result<Vec<u8>>
 where
@@ -94,6 +190,9 @@ where
         .stderr(Stdio::piped())
         .env("LANG", "en_US.UTF-8")
         .env("LANGUAGE", "en_US.UTF-8");
+    if let Some(home) = &config.gpg_home {
+        cmd.arg("--homedir").arg(home);
+    }
     if config.gpg_tty {
         cmd.arg("--pinentry-mode").arg("loopback");
         if !util::env::has_gpg_tty() {
@@ -116,7 +215,7 @@ where
 /// On error, this prints stdout/stderr output in verbose mode.
 ///
 /// Returns error is status is not succesful.
-fn cmd_assert_status(config: &Config, output: &Output) -> Result<()> {
+pub(super) fn cmd_assert_status(config: &Config, output: &Output) -> Result<()> {
     if !output.status.success() {
         // Output stdout/stderr in verbose mode
         if config.verbose {
@@ -228,6 +327,9 @@ pub enum Err {
     #[error("failed to invoke gpg command")]
     System(#[source] std::io::Error),
 
+    #[error("gpg passphrase must not contain a newline")]
+    PassphraseNewline,
+
     #[error("gpg command exited with non-zero status code: {0}")]
     Status(std::process::ExitStatus),
 }
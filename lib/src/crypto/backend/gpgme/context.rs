@@ -1,13 +1,14 @@
 //! Provides GPGME binary context adapter.
 
 use std::env;
+use std::io::Write;
 
 use anyhow::Result;
-use gpgme::{Context as GpgmeContext, PinentryMode, Protocol};
+use gpgme::{Context as GpgmeContext, PassphraseRequest, PinentryMode, Protocol};
 use thiserror::Error;
 
 use super::raw;
-use crate::crypto::{proto, Config, IsContext, Key, Proto};
+use crate::crypto::{self, proto, Config, IsContext, Key, Proto, Verified};
 use crate::{util, Ciphertext, Plaintext, Recipients};
 
 /// Protocol to use.
@@ -22,27 +23,100 @@ pub fn context(config: &Config) -> Result<Context, Err> {
         }
     }
 
+    let context = new_context(config)?;
+
+    // Use separate context with custom GnuPG home for public keys if set
+    match &config.gpg_home {
+        Some(home) => {
+            util::fs::create_private_dir(home).map_err(Err::Home)?;
+            let mut public_context = new_context(config)?;
+            public_context
+                .set_engine_home_dir(home.to_string_lossy().as_ref())
+                .map_err(Err::Context)?;
+            Ok(Context::from(
+                public_context,
+                Some(context),
+                config.gpg_passphrase.clone(),
+                config.sign,
+            ))
+        }
+        None => Ok(Context::from(
+            context,
+            None,
+            config.gpg_passphrase.clone(),
+            config.sign,
+        )),
+    }
+}
+
+/// Create a GPGME context using the global GnuPG home.
+fn new_context(config: &Config) -> Result<GpgmeContext, Err> {
     let mut context = gpgme::Context::from_protocol(PROTO).map_err(Err::Context)?;
 
-    // Set pinentry mode when using GPG TTY
-    if config.gpg_tty {
+    // Set pinentry mode when using GPG TTY or a given passphrase
+    if config.gpg_tty || config.gpg_passphrase.is_some() {
         context
             .set_pinentry_mode(PinentryMode::Loopback)
             .map_err(Err::Context)?;
     }
 
-    Ok(Context::from(context))
+    Ok(context)
 }
 
 /// GPGME crypto context.
 pub struct Context {
     /// GPGME crytp context.
     context: GpgmeContext,
+
+    /// GPGME context for operations using secret keys, if different from the main context.
+    ///
+    /// This always uses the global GnuPG home, so secret keys are available through the users
+    /// agent when a custom GnuPG home is used for public keys.
+    secret_context: Option<GpgmeContext>,
+
+    /// Passphrase for secret keys, provided through loopback pinentry mode if set.
+    passphrase: Option<Plaintext>,
+
+    /// Whether to sign secrets when encrypting.
+    sign: bool,
 }
 
 impl Context {
-    pub fn from(context: GpgmeContext) -> Self {
-        Self { context }
+    pub fn from(
+        context: GpgmeContext,
+        secret_context: Option<GpgmeContext>,
+        passphrase: Option<Plaintext>,
+        sign: bool,
+    ) -> Self {
+        Self {
+            context,
+            secret_context,
+            passphrase,
+            sign,
+        }
+    }
+
+    /// Get the context to use for operations using secret keys.
+    fn secret(&mut self) -> &mut GpgmeContext {
+        self.secret_context.as_mut().unwrap_or(&mut self.context)
+    }
+
+    /// Run an operation that may need to unlock secret keys.
+    ///
+    /// Provides the passphrase if set, pinentry is used otherwise.
+    fn with_secret<R>(&mut self, f: impl FnOnce(&mut GpgmeContext) -> R) -> R {
+        let context = self.secret_context.as_mut().unwrap_or(&mut self.context);
+        match &self.passphrase {
+            Some(passphrase) => context.with_passphrase_provider(
+                |_: PassphraseRequest<'_>, out: &mut dyn Write| {
+                    out.write_all(passphrase.unsecure_ref())?;
+                    out.write_all(b"\n")?;
+                    Ok(())
+                },
+                f,
+            ),
+            None => f(context),
+        }
     }
 }
 
@@ -54,38 +128,43 @@ impl IsContext for Context {
             .map(|key| key.fingerprint(false))
             .collect();
         let fingerprints: Vec<&str> = fingerprints.iter().map(|fp| fp.as_str()).collect();
-        raw::encrypt(&mut self.context, &fingerprints, plaintext)
+        if !self.sign {
+            return raw::encrypt(&mut self.context, &fingerprints, None, plaintext);
+        }
+
+        // Signing needs recipients and secret key in the same keyring
+        if self.secret_context.is_some() {
+            return Err(Err::SignCustomHome.into());
+        }
+        let signer = crypto::find_signing_key(self, recipients)?.fingerprint(false);
+        self.with_secret(|context| raw::encrypt(context, &fingerprints, Some(&signer), plaintext))
     }
 
     fn decrypt(&mut self, ciphertext: Ciphertext) -> Result<Plaintext> {
-        raw::decrypt(&mut self.context, ciphertext)
+        self.with_secret(|context| raw::decrypt(context, ciphertext))
+    }
+
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        let (plaintext, signers) =
+            self.with_secret(|context| raw::decrypt_verify(context, ciphertext))?;
+        Ok(Verified { plaintext, signers })
     }
 
     fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
-        raw::can_decrypt(&mut self.context, ciphertext)
+        self.with_secret(|context| raw::can_decrypt(context, ciphertext))
     }
 
     fn keys_public(&mut self) -> Result<Vec<Key>> {
         Ok(raw::public_keys(&mut self.context)?
             .into_iter()
-            .map(|key| {
-                Key::Gpg(proto::gpg::Key {
-                    fingerprint: key.0,
-                    user_ids: key.1,
-                })
-            })
+            .map(|key| key.into_key())
             .collect())
     }
 
     fn keys_private(&mut self) -> Result<Vec<Key>> {
-        Ok(raw::private_keys(&mut self.context)?
+        Ok(raw::private_keys(self.secret())?
             .into_iter()
-            .map(|key| {
-                Key::Gpg(proto::gpg::Key {
-                    fingerprint: key.0,
-                    user_ids: key.1,
-                })
-            })
+            .map(|key| key.into_key())
             .collect())
     }
 
@@ -93,8 +172,39 @@ impl IsContext for Context {
         raw::import_key(&mut self.context, key)
     }
 
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        Ok(raw::read_keys(&mut self.context, key)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
     fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
-        raw::export_key(&mut self.context, &key.fingerprint(false))
+        // Own keys may only be in the global keyring when using a custom GnuPG home
+        let fingerprint = key.fingerprint(false);
+        if self.secret_context.is_some()
+            && raw::find_key_by_id(&mut self.context, &fingerprint).is_none()
+        {
+            return raw::export_key(self.secret(), &fingerprint);
+        }
+        raw::export_key(&mut self.context, &fingerprint)
+    }
+
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>> {
+        proto::gpg::pkesk_key_ids(ciphertext.unsecure_ref())
+    }
+
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>> {
+        Ok(raw::find_key_by_id(&mut self.context, key_id).map(|key| key.into_key()))
+    }
+
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+        let fingerprint = key.fingerprint(false);
+        self.with_secret(|context| raw::sign_detached(context, &fingerprint, data))
+    }
+
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+        raw::verify_detached(&mut self.context, data, signature)
     }
 
     fn supports_proto(&self, proto: Proto) -> bool {
@@ -107,4 +217,10 @@ impl IsContext for Context {
 pub enum Err {
     #[error("failed to obtain GPGME cryptography context")]
     Context(#[source] gpgme::Error),
+
+    #[error("failed to create GnuPG home directory")]
+    Home(#[source] anyhow::Error),
+
+    #[error("signing secrets is not supported with a custom GnuPG home")]
+    SignCustomHome,
 }
@@ -2,11 +2,14 @@
 //!
 //! This provides the most basic and bare functions to interface with the GPGME backend.
 
+use std::time::UNIX_EPOCH;
+
 use anyhow::Result;
-use gpgme::{Context, EncryptFlags, Key};
+use gpgme::{Context, EncryptFlags, Key, VerificationResult};
 use thiserror::Error;
 use zeroize::Zeroize;
 
+use crate::crypto::proto::gpg::{self, Capabilities, Validity};
 use crate::{Ciphertext, Plaintext};
 
 /// GPGME encryption flags.
@@ -16,6 +19,7 @@ const ENCRYPT_FLAGS: EncryptFlags = EncryptFlags::ALWAYS_TRUST;
 ///
 /// - `context`: GPGME context
 /// - `recipients`: list of recipient fingerprints to encrypt for
+/// - `signer`: fingerprint of secret key to sign with, not signed if `None`
 /// - `plaintext`: plaintext to encrypt
 ///
 /// # Panics
@@ -24,6 +28,7 @@ const ENCRYPT_FLAGS: EncryptFlags = EncryptFlags::ALWAYS_TRUST;
 pub fn encrypt(
     context: &mut Context,
     recipients: &[&str],
+    signer: Option<&str>,
     plaintext: Plaintext,
 ) -> Result<Ciphertext> {
     assert!(
@@ -33,14 +38,35 @@ pub fn encrypt(
 
     let mut ciphertext = vec![];
     let keys = fingerprints_to_keys(context, recipients)?;
-    context
-        .encrypt_with_flags(
-            keys.iter(),
-            plaintext.unsecure_ref(),
-            &mut ciphertext,
-            ENCRYPT_FLAGS,
-        )
-        .map_err(Err::Encrypt)?;
+    match signer {
+        Some(signer) => {
+            let key = context
+                .get_secret_key(signer)
+                .map_err(Err::UnknownFingerprint)?;
+
+            // Sign with only the given key
+            context.clear_signers();
+            context.add_signer(&key).map_err(Err::Sign)?;
+            let result = context.sign_and_encrypt_with_flags(
+                keys.iter(),
+                plaintext.unsecure_ref(),
+                &mut ciphertext,
+                ENCRYPT_FLAGS,
+            );
+            context.clear_signers();
+            result.map_err(Err::Encrypt)?;
+        }
+        None => {
+            context
+                .encrypt_with_flags(
+                    keys.iter(),
+                    plaintext.unsecure_ref(),
+                    &mut ciphertext,
+                    ENCRYPT_FLAGS,
+                )
+                .map_err(Err::Encrypt)?;
+        }
+    }
     Ok(Ciphertext::from(ciphertext))
 }
 
@@ -56,6 +82,23 @@ pub fn decrypt(context: &mut Context, ciphertext: Ciphertext) -> Result<Plaintex
     Ok(Plaintext::from(plaintext))
 }
 
+/// Decrypt ciphertext, and verify its signatures.
+///
+/// Returns the plaintext, and the primary key fingerprints of all valid signatures.
+///
+/// - `context`: GPGME context
+/// - `ciphertext`: ciphertext to decrypt
+pub fn decrypt_verify(
+    context: &mut Context,
+    ciphertext: Ciphertext,
+) -> Result<(Plaintext, Vec<String>)> {
+    let mut plaintext = vec![];
+    let result = context.decrypt_and_verify(ciphertext.unsecure_ref(), &mut plaintext);
+    let plaintext = Plaintext::from(plaintext);
+    let (_, verification) = result.map_err(Err::Decrypt)?;
+    Ok((plaintext, valid_signers(context, &verification)))
+}
+
 /// Check whether we can decrypt ciphertext.
 ///
 /// This checks whether whether we own the secret key to decrypt the given ciphertext.
@@ -81,12 +124,11 @@ pub fn can_decrypt(context: &mut Context, ciphertext: Ciphertext) -> Result<bool
 /// Get all public keys from keychain.
 ///
 /// - `context`: GPGME context
-pub fn public_keys(context: &mut Context) -> Result<Vec<KeyId>> {
+pub fn public_keys(context: &mut Context) -> Result<Vec<gpg::Key>> {
     Ok(context
         .keys()?
         .into_iter()
         .filter_map(|k| k.ok())
-        .filter(|k| k.can_encrypt())
         .map(|k| k.into())
         .collect())
 }
@@ -94,12 +136,11 @@ pub fn public_keys(context: &mut Context) -> Result<Vec<KeyId>> {
 /// Get all private/secret keys from keychain.
 ///
 /// - `context`: GPGME context
-pub fn private_keys(context: &mut Context) -> Result<Vec<KeyId>> {
+pub fn private_keys(context: &mut Context) -> Result<Vec<gpg::Key>> {
     Ok(context
         .secret_keys()?
         .into_iter()
         .filter_map(|k| k.ok())
-        .filter(|k| k.can_encrypt())
         .map(|k| k.into())
         .collect())
 }
@@ -130,6 +171,19 @@ pub fn import_key(context: &mut Context, key: &[u8]) -> Result<()> {
         .map_err(|err| Err::Import(err.into()).into())
 }
 
+/// Read public keys from bytes, without importing them into the keychain.
+///
+/// - `context`: GPGME context
+/// - `key`: armored or binary public keys
+pub fn read_keys(context: &mut Context, key: &[u8]) -> Result<Vec<gpg::Key>> {
+    Ok(context
+        .read_keys(key)
+        .map_err(|err| Err::Import(err.into()))?
+        .filter_map(|k| k.ok())
+        .map(|k| k.into())
+        .collect())
+}
+
 /// Export the given key as bytes.
 ///
 /// # Panics
@@ -163,17 +217,101 @@ pub fn export_key(context: &mut Context, fingerprint: &str) -> Result<Vec<u8>> {
     Ok(data)
 }
 
-/// A key identifier with a fingerprint and user IDs.
-#[derive(Clone)]
-pub struct KeyId(pub String, pub Vec<String>);
+/// Find the public key having a (sub)key with the given key ID.
+///
+/// - `context`: GPGME context
+/// - `key_id`: key ID of primary key or subkey
+pub fn find_key_by_id(context: &mut Context, key_id: &str) -> Option<gpg::Key> {
+    context.get_key(key_id).ok().map(|key| key.into())
+}
+
+/// Create a detached signature over data.
+///
+/// - `context`: GPGME context
+/// - `fingerprint`: fingerprint of secret key to sign with
+/// - `data`: data to sign
+pub fn sign_detached(context: &mut Context, fingerprint: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let key = context
+        .get_secret_key(fingerprint)
+        .map_err(Err::UnknownFingerprint)?;
+
+    // Sign with only the given key
+    let mut signature = vec![];
+    context.clear_signers();
+    context.add_signer(&key).map_err(Err::Sign)?;
+    let result = context.sign_detached(data, &mut signature);
+    context.clear_signers();
+    result.map_err(Err::Sign)?;
+
+    Ok(signature)
+}
+
+/// Verify a detached signature over data.
+///
+/// Returns the primary key fingerprints of all valid signatures.
+///
+/// - `context`: GPGME context
+/// - `data`: signed data
+/// - `signature`: detached signature
+pub fn verify_detached(
+    context: &mut Context,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<Vec<String>> {
+    let result = context
+        .verify_detached(signature, data)
+        .map_err(Err::Verify)?;
+    Ok(valid_signers(context, &result))
+}
+
+/// Get the primary key fingerprints of all valid signatures in a verification result.
+fn valid_signers(context: &mut Context, result: &VerificationResult) -> Vec<String> {
+    let fingerprints: Vec<String> = result
+        .signatures()
+        .filter(|sig| sig.status().is_ok())
+        .filter_map(|sig| sig.fingerprint().ok().map(|fp| fp.to_string()))
+        .collect();
 
-impl From<Key> for KeyId {
+    // Signatures may be made by a subkey, resolve to the primary key fingerprint
+    fingerprints
+        .into_iter()
+        .filter_map(|fp| context.get_key(fp).ok())
+        .filter_map(|key| key.fingerprint().ok().map(|fp| fp.to_string()))
+        .collect()
+}
+
+impl From<Key> for gpg::Key {
     fn from(key: Key) -> Self {
-        Self(
-            key.fingerprint()
+        let validity = if key.is_revoked() {
+            Validity::Revoked
+        } else if key.is_expired() {
+            Validity::Expired
+        } else if key.is_disabled() {
+            Validity::Disabled
+        } else if key.is_invalid() {
+            Validity::Invalid
+        } else {
+            Validity::Valid
+        };
+        let expires = key
+            .primary_key()
+            .and_then(|primary| primary.expiration_time())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|time| time.as_secs());
+        let capabilities = Capabilities {
+            encrypt: key.can_encrypt(),
+            sign: key.can_sign(),
+            certify: key.can_certify(),
+            authenticate: key.can_authenticate(),
+        };
+
+        Self {
+            fingerprint: key
+                .fingerprint()
                 .expect("GPGME key does not have fingerprint")
                 .to_string(),
-            key.user_ids()
+            user_ids: key
+                .user_ids()
                 .map(|user| {
                     let mut parts = vec![];
                     if let Ok(name) = user.name() {
@@ -194,7 +332,10 @@ impl From<Key> for KeyId {
                     parts.join(" ")
                 })
                 .collect(),
-        )
+            validity,
+            expires,
+            capabilities,
+        }
     }
 }
 
@@ -231,6 +372,12 @@ pub enum Err {
     #[error("failed to export key")]
     Export(#[source] anyhow::Error),
 
+    #[error("failed to sign data")]
+    Sign(#[source] gpgme::Error),
+
+    #[error("failed to verify signature")]
+    Verify(#[source] gpgme::Error),
+
     #[error("fingerprint does not match public key in keychain")]
     UnknownFingerprint(#[source] gpgme::Error),
 }
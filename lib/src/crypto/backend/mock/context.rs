@@ -0,0 +1,103 @@
+//! Provides mock context adapter.
+
+use anyhow::Result;
+
+use super::raw;
+use crate::crypto::{self, Config as CryptoConfig, IsContext, Key, Proto, Verified};
+use crate::{Ciphertext, Plaintext, Recipients};
+
+/// Create mock context.
+pub fn context(config: &CryptoConfig) -> Result<Context> {
+    Ok(Context::from(config.sign))
+}
+
+/// Mock context.
+///
+/// All mock contexts share the same in-memory keychain.
+pub struct Context {
+    /// Whether to sign when encrypting.
+    sign: bool,
+}
+
+impl Context {
+    /// Construct mock context.
+    fn from(sign: bool) -> Self {
+        Self { sign }
+    }
+}
+
+impl IsContext for Context {
+    fn encrypt(&mut self, recipients: &Recipients, plaintext: Plaintext) -> Result<Ciphertext> {
+        let fingerprints: Vec<String> = recipients
+            .keys()
+            .iter()
+            .map(|key| key.fingerprint(false))
+            .collect();
+        let fingerprints: Vec<&str> = fingerprints.iter().map(|fp| fp.as_str()).collect();
+        let signer = if self.sign {
+            Some(crypto::find_signing_key(self, recipients)?.fingerprint(false))
+        } else {
+            None
+        };
+        raw::encrypt(&fingerprints, signer.as_deref(), plaintext)
+    }
+
+    fn decrypt(&mut self, ciphertext: Ciphertext) -> Result<Plaintext> {
+        raw::decrypt(ciphertext)
+    }
+
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        let (plaintext, signers) = raw::decrypt_verify(ciphertext)?;
+        Ok(Verified { plaintext, signers })
+    }
+
+    fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
+        raw::can_decrypt(ciphertext)
+    }
+
+    fn keys_public(&mut self) -> Result<Vec<Key>> {
+        Ok(super::keys(false)
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
+    fn keys_private(&mut self) -> Result<Vec<Key>> {
+        Ok(super::keys(true)
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
+    fn import_key(&mut self, key: &[u8]) -> Result<()> {
+        raw::import_key(key)
+    }
+
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        Ok(vec![raw::read_key(key)?.into_key()])
+    }
+
+    fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
+        raw::export_key(&key.fingerprint(false))
+    }
+
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>> {
+        raw::encrypted_key_ids(ciphertext)
+    }
+
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>> {
+        Ok(raw::find_key_by_id(key_id).map(|key| key.into_key()))
+    }
+
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+        raw::sign_detached(&key.fingerprint(false), data)
+    }
+
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+        raw::verify_detached(data, signature)
+    }
+
+    fn supports_proto(&self, proto: Proto) -> bool {
+        proto == Proto::Gpg
+    }
+}
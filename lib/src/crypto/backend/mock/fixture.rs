@@ -0,0 +1,73 @@
+//! Temporary password store fixture for tests.
+
+use std::fs;
+use std::ops::Deref;
+
+use anyhow::Result;
+use tempfile::TempDir;
+use thiserror::Error;
+
+use crate::crypto::{self, prelude::*};
+use crate::{Key, Plaintext, Secret, Store};
+
+/// A password store in a temporary directory.
+///
+/// The store directory is removed when this is dropped.
+pub struct TempStore {
+    /// The store.
+    store: Store,
+
+    /// Temporary directory holding the store, removed when dropped.
+    _dir: TempDir,
+}
+
+impl TempStore {
+    /// Create a new store in a temporary directory, for the given recipients.
+    ///
+    /// The recipient keys must be in the keychain, see `add_secret_key` and `add_public_key`.
+    /// Sync is not initialized.
+    ///
+    /// This enables the mock backend for the rest of the process, see `super::enable`.
+    pub fn new(recipients: &[Key]) -> Result<Self> {
+        super::enable();
+        let dir = tempfile::tempdir().map_err(Err::Create)?;
+        let store = Store::open(dir.path().to_string_lossy())?;
+        if !recipients.is_empty() {
+            crypto::store::store_save_keys(&store, recipients)?;
+        }
+        Ok(Self { store, _dir: dir })
+    }
+
+    /// Add a secret with the given name and plaintext, encrypted for its recipients.
+    ///
+    /// Overwrites an existing secret with the same name.
+    pub fn add_secret(&self, name: &str, plaintext: Plaintext) -> Result<Secret> {
+        let path = self
+            .store
+            .root
+            .join(format!("{name}{}", self.secret_suffix()));
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(Err::Create)?;
+        }
+
+        let recipients = self.store.recipients_for(&path)?;
+        let config = crypto::store::store_crypto_config(&self.store)?;
+        crypto::context(&config)?.encrypt_file(&recipients, plaintext, &path)?;
+
+        Ok(Secret::from(&self.store, path))
+    }
+}
+
+impl Deref for TempStore {
+    type Target = Store;
+
+    fn deref(&self) -> &Store {
+        &self.store
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to create temporary password store")]
+    Create(#[source] std::io::Error),
+}
@@ -0,0 +1,322 @@
+//! In-memory mock crypto backend for GPG, for testing purposes.
+//!
+//! Keys are kept in a keychain in memory, shared by all contexts in the current process.
+//! Ciphertext is a readable text format holding the plaintext as-is, encryption and signatures are
+//! deterministic.
+//!
+//! This is NOT secure, never use this backend to store real secrets.
+//!
+//! Compiling with the `backend-mock` feature alone does not use this backend. It must explicitly
+//! be enabled at runtime with `enable`, so a feature enabled elsewhere in a build never silently
+//! replaces real cryptography.
+
+pub mod context;
+pub mod fixture;
+pub mod raw;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::crypto::proto::gpg::{Capabilities, Key, Validity};
+
+lazy_static! {
+    /// Keychain shared by all mock contexts.
+    static ref KEYCHAIN: Mutex<Keychain> = Mutex::new(Keychain::default());
+}
+
+/// Whether the mock backend is enabled for this process, see `enable`.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enable the mock backend for GPG for the rest of this process.
+///
+/// This makes all new GPG contexts use the mock backend instead of any other GPG backend. The
+/// store fixture enables it, see `fixture::TempStore`.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Check whether the mock backend is enabled for this process.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// In-memory keychain.
+#[derive(Default)]
+struct Keychain {
+    /// Public keys, along with whether we own the secret key.
+    keys: Vec<(Key, bool)>,
+}
+
+/// Create a mock key for the given user ID.
+///
+/// The fingerprint is derived from the user ID, the same user ID always gives the same key. This
+/// does not add the key to the keychain.
+pub fn key(user_id: &str) -> Key {
+    let fingerprint: String = (0..3u8)
+        .map(|seed| format!("{:016X}", raw::hash(&[&[seed], user_id.as_bytes()])))
+        .collect();
+    Key {
+        fingerprint: fingerprint[..40].into(),
+        user_ids: vec![user_id.into()],
+        validity: Validity::Valid,
+        expires: None,
+        capabilities: Capabilities {
+            encrypt: true,
+            sign: true,
+            certify: true,
+            authenticate: false,
+        },
+    }
+}
+
+/// Add a mock key for the given user ID to the keychain, of which we own the secret key.
+pub fn add_secret_key(user_id: &str) -> crate::crypto::Key {
+    let key = key(user_id);
+    insert_key(key.clone(), true);
+    key.into_key()
+}
+
+/// Add a mock key for the given user ID to the keychain, of which we only have the public key.
+///
+/// This does not forget the secret key if it was added before.
+pub fn add_public_key(user_id: &str) -> crate::crypto::Key {
+    let key = key(user_id);
+    insert_key(key.clone(), false);
+    key.into_key()
+}
+
+/// Insert a key into the keychain.
+///
+/// Keys that are already in the keychain are updated, owning the secret key is never forgotten.
+fn insert_key(key: Key, secret: bool) {
+    let mut keychain = KEYCHAIN.lock().unwrap();
+    match keychain
+        .keys
+        .iter_mut()
+        .find(|(other, _)| other.fingerprint == key.fingerprint)
+    {
+        Some((other, other_secret)) => {
+            *other = key;
+            *other_secret |= secret;
+        }
+        None => keychain.keys.push((key, secret)),
+    }
+}
+
+/// Get all keys from the keychain, only the keys we own the secret key for if `secret` is set.
+fn keys(secret: bool) -> Vec<Key> {
+    KEYCHAIN
+        .lock()
+        .unwrap()
+        .keys
+        .iter()
+        .filter(|(_, is_secret)| !secret || *is_secret)
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::TempStore;
+    use super::*;
+    use crate::crypto::{self, prelude::*, Config, Proto};
+    use crate::{Plaintext, Recipients};
+
+    fn context() -> crypto::Context {
+        enable();
+        crypto::context(&Config::from(Proto::Gpg)).unwrap()
+    }
+
+    #[test]
+    fn key_deterministic() {
+        assert_eq!(key("alice").fingerprint, key("alice").fingerprint);
+        assert_ne!(key("alice").fingerprint, key("bob").fingerprint);
+        assert_eq!(key("alice").fingerprint.len(), 40);
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let alice = add_secret_key("encrypt-alice");
+        let bob = add_public_key("encrypt-bob");
+        let mut context = context();
+
+        // Decryptable if we own the secret key of any recipient
+        let recipients = Recipients::from(vec![alice.clone(), bob.clone()]);
+        let ciphertext = context
+            .encrypt(&recipients, Plaintext::from("secret"))
+            .unwrap();
+        assert_eq!(
+            context
+                .decrypt(ciphertext)
+                .unwrap()
+                .unsecure_to_str()
+                .unwrap(),
+            "secret",
+        );
+
+        // Not decryptable without secret key
+        let recipients = Recipients::from(vec![bob.clone()]);
+        let ciphertext = context
+            .encrypt(&recipients, Plaintext::from("secret"))
+            .unwrap();
+        assert!(!context.can_decrypt(ciphertext).unwrap());
+    }
+
+    #[test]
+    fn sign_verify() {
+        let alice = add_secret_key("sign-alice");
+        let mut context = context();
+
+        let signature = context.sign_detached(&alice, b"data").unwrap();
+        assert_eq!(
+            context.verify_detached(b"data", &signature).unwrap(),
+            vec![alice.fingerprint(false)],
+        );
+        assert!(context
+            .verify_detached(b"other", &signature)
+            .unwrap()
+            .is_empty());
+
+        // Sign when encrypting
+        let mut config = Config::from(Proto::Gpg);
+        config.sign = true;
+        let mut context = crypto::context(&config).unwrap();
+        let recipients = Recipients::from(vec![alice.clone()]);
+        let ciphertext = context
+            .encrypt(&recipients, Plaintext::from("secret"))
+            .unwrap();
+        let verified = context.decrypt_verify(ciphertext).unwrap();
+        assert_eq!(verified.signers, vec![alice.fingerprint(false)]);
+    }
+
+    #[test]
+    fn context_pool_parallel() {
+        enable();
+        let config = Config::from(Proto::Gpg);
+        let items: Vec<u64> = (0..32).collect();
+
+        // Jobs finish out of order, results are handled in order
+        let job = |_: &mut crypto::Context, item: &u64| {
+            std::thread::sleep(std::time::Duration::from_millis((32 - item) % 4));
+            item * 2
+        };
+        let mut results = vec![];
+        crypto::ContextPool::parallel(&config, 4, &items, job, |item, result| {
+            results.push((*item, result));
+            true
+        })
+        .unwrap();
+        let expected: Vec<(u64, u64)> = items.iter().map(|item| (*item, item * 2)).collect();
+        assert_eq!(results, expected);
+
+        // Stops handling results early
+        let mut handled = vec![];
+        crypto::ContextPool::parallel(&config, 4, &items, job, |item, _| {
+            handled.push(*item);
+            *item < 10
+        })
+        .unwrap();
+        assert_eq!(handled, (0..=10).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn export_import() {
+        let alice = add_public_key("export-alice");
+        let mut context = context();
+
+        let data = context.export_key(alice.clone()).unwrap();
+        context.import_key(&data).unwrap();
+        assert_eq!(
+            context
+                .get_public_key(&alice.fingerprint(false))
+                .unwrap()
+                .display(),
+            "export-alice",
+        );
+    }
+
+    #[test]
+    fn store_fixture() {
+        let alice = add_secret_key("store-alice");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+
+        // Recipients and public key files are saved
+        assert!(store.recipients().unwrap().keys() == [alice.clone()]);
+        assert!(crypto::store::store_public_keys_dir(&store)
+            .join(alice.fingerprint(false))
+            .is_file());
+
+        // Secrets are found and decryptable
+        let secret = store
+            .add_secret("dir/secret", Plaintext::from("password"))
+            .unwrap();
+        assert_eq!(secret.name, "dir/secret");
+        assert_eq!(store.secrets(None).len(), 1);
+        assert!(crate::store::can_decrypt(&store));
+        assert_eq!(
+            context()
+                .decrypt_file(&secret.path)
+                .unwrap()
+                .unsecure_to_str()
+                .unwrap(),
+            "password",
+        );
+    }
+
+    #[test]
+    fn store_request() {
+        let alice = add_secret_key("request-alice");
+        let bob = add_public_key("request-bob");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+        let request = crypto::store::store_write_request(&store, &bob).unwrap();
+
+        // Files not named after a full fingerprint are ignored
+        let short_id = &bob.fingerprint(false)[24..];
+        std::fs::copy(
+            &request.path,
+            crypto::store::store_requests_dir(&store).join(short_id),
+        )
+        .unwrap();
+        let requests = crypto::store::store_read_requests(&store).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            crypto::store::store_import_request(&store, &requests[0])
+                .unwrap()
+                .fingerprint(false),
+            bob.fingerprint(false),
+        );
+
+        // Requests holding a different key are rejected
+        let carol = add_public_key("request-carol");
+        let data = context().export_key(carol).unwrap();
+        std::fs::write(&request.path, data).unwrap();
+        assert!(crypto::store::store_import_request(&store, &request).is_err());
+    }
+
+    #[test]
+    fn store_armor() {
+        let alice = add_secret_key("armor-alice");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+        std::fs::create_dir_all(store.root.join(".prs")).unwrap();
+        std::fs::write(store.root.join(".prs/config.toml"), "armor = true").unwrap();
+
+        // New secrets are armored, and transparently decrypted
+        let secret = store
+            .add_secret("secret", Plaintext::from("password"))
+            .unwrap();
+        assert!(secret.path.to_string_lossy().ends_with(".asc"));
+        assert!(crypto::armor::is_armored(
+            crypto::armor::Block::Message,
+            &std::fs::read(&secret.path).unwrap()
+        ));
+        assert_eq!(store.find_at("secret").unwrap().name, "secret");
+        assert_eq!(
+            context()
+                .decrypt_file(&secret.path)
+                .unwrap()
+                .unsecure_to_str()
+                .unwrap(),
+            "password",
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Raw mock interface.
+//!
+//! All messages, signatures and exported keys use a simple text format. It starts with a header
+//! line, followed by `name value` field lines and an empty line. Messages have their plaintext
+//! appended after that.
+
+use anyhow::Result;
+use thiserror::Error;
+
+use crate::crypto::{proto::gpg::Key, util};
+use crate::{Ciphertext, Plaintext};
+
+/// Header of mock messages.
+const MESSAGE_HEADER: &str = "-----BEGIN PRS MOCK MESSAGE-----";
+
+/// Header of mock detached signatures.
+const SIGNATURE_HEADER: &str = "-----BEGIN PRS MOCK SIGNATURE-----";
+
+/// Header of mock exported keys.
+const KEY_HEADER: &str = "-----BEGIN PRS MOCK KEY-----";
+
+/// Decoded `name value` fields.
+type Fields = Vec<(String, String)>;
+
+/// Encrypt plaintext for the given recipients.
+///
+/// - `recipients`: list of recipient fingerprints to encrypt for, must be in the keychain
+/// - `signer`: fingerprint of secret key to sign with, not signed if `None`
+/// - `plaintext`: plaintext to encrypt
+///
+/// # Panics
+///
+/// Panics if list of recipients is empty.
+pub fn encrypt(
+    recipients: &[&str],
+    signer: Option<&str>,
+    plaintext: Plaintext,
+) -> Result<Ciphertext> {
+    assert!(
+        !recipients.is_empty(),
+        "attempting to encrypt secret for empty list of recipients"
+    );
+
+    let mut fields = vec![];
+    for fp in recipients {
+        let key = find_key(fp, false).ok_or_else(|| Err::UnknownFingerprint(fp.to_string()))?;
+        fields.push(("recipient", key.fingerprint(false)));
+    }
+    if let Some(signer) = signer {
+        let key = find_key(signer, true).ok_or(Err::NoSecretKey)?;
+        fields.push(("signer", key.fingerprint(false)));
+    }
+
+    Ok(Ciphertext::from(encode(
+        MESSAGE_HEADER,
+        &fields,
+        plaintext.unsecure_ref(),
+    )))
+}
+
+/// Decrypt ciphertext.
+///
+/// Errors if we don't own the secret key of any recipient.
+pub fn decrypt(ciphertext: Ciphertext) -> Result<Plaintext> {
+    decrypt_verify(ciphertext).map(|(plaintext, _)| plaintext)
+}
+
+/// Decrypt ciphertext, and verify its signature.
+///
+/// Returns the plaintext, and the signer fingerprint if the signing key is in the keychain.
+pub fn decrypt_verify(ciphertext: Ciphertext) -> Result<(Plaintext, Vec<String>)> {
+    let (fields, body) = decode(MESSAGE_HEADER, ciphertext.unsecure_ref())?;
+    if !fields_get(&fields, "recipient").any(|fp| find_key(fp, true).is_some()) {
+        return Err(Err::NoSecretKey.into());
+    }
+
+    let signers = fields_get(&fields, "signer")
+        .filter_map(|fp| find_key(fp, false))
+        .map(|key| key.fingerprint(false))
+        .collect();
+    Ok((Plaintext::from(body.to_vec()), signers))
+}
+
+/// Check whether we can decrypt ciphertext.
+///
+/// This checks whether we own the secret key of any recipient.
+pub fn can_decrypt(ciphertext: Ciphertext) -> Result<bool> {
+    let (fields, _) = decode(MESSAGE_HEADER, ciphertext.unsecure_ref())?;
+    let owned = fields_get(&fields, "recipient").any(|fp| find_key(fp, true).is_some());
+    Ok(owned)
+}
+
+/// Get the key IDs of the recipients of a ciphertext.
+///
+/// These are the last 16 characters of the recipient fingerprints.
+pub fn encrypted_key_ids(ciphertext: Ciphertext) -> Result<Vec<String>> {
+    let (fields, _) = decode(MESSAGE_HEADER, ciphertext.unsecure_ref())?;
+    Ok(fields_get(&fields, "recipient")
+        .map(|fp| fp[fp.len().saturating_sub(16)..].to_string())
+        .collect())
+}
+
+/// Find the key in the keychain having the given key ID.
+pub fn find_key_by_id(key_id: &str) -> Option<Key> {
+    let key_id = util::format_fingerprint(key_id);
+    super::keys(false)
+        .into_iter()
+        .find(|key| key.fingerprint(false).ends_with(&key_id))
+}
+
+/// Export the key with the given fingerprint from the keychain.
+pub fn export_key(fingerprint: &str) -> Result<Vec<u8>> {
+    let key =
+        find_key(fingerprint, false).ok_or_else(|| Err::UnknownFingerprint(fingerprint.into()))?;
+    let mut fields = vec![("fingerprint", key.fingerprint(false))];
+    fields.extend(key.user_ids.iter().map(|uid| ("uid", uid.clone())));
+    Ok(encode(KEY_HEADER, &fields, &[]))
+}
+
+/// Import a key exported with `export_key` into the keychain.
+pub fn import_key(data: &[u8]) -> Result<()> {
+    super::insert_key(read_key(data)?, false);
+    Ok(())
+}
+
+/// Read a key exported with `export_key`, without importing it.
+pub fn read_key(data: &[u8]) -> Result<Key> {
+    let (fields, _) = decode(KEY_HEADER, data)?;
+    let fingerprint = fields_get(&fields, "fingerprint")
+        .next()
+        .ok_or(Err::Format)?;
+    let mut key = super::key("");
+    key.fingerprint = util::format_fingerprint(fingerprint);
+    key.user_ids = fields_get(&fields, "uid")
+        .map(|uid| uid.to_string())
+        .collect();
+    Ok(key)
+}
+
+/// Create a detached signature over data with the given secret key.
+pub fn sign_detached(fingerprint: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let key = find_key(fingerprint, true).ok_or(Err::NoSecretKey)?;
+    Ok(encode(
+        SIGNATURE_HEADER,
+        &[
+            ("signer", key.fingerprint(false)),
+            ("digest", format!("{:016x}", hash(&[data]))),
+        ],
+        &[],
+    ))
+}
+
+/// Verify a detached signature over data.
+///
+/// Returns the signer fingerprint if the signature is valid, and the signing key is in the
+/// keychain.
+pub fn verify_detached(data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+    let (fields, _) = decode(SIGNATURE_HEADER, signature)?;
+    let digest = format!("{:016x}", hash(&[data]));
+    if !fields_get(&fields, "digest").any(|other| other == digest) {
+        return Ok(vec![]);
+    }
+    Ok(fields_get(&fields, "signer")
+        .filter_map(|fp| find_key(fp, false))
+        .map(|key| key.fingerprint(false))
+        .collect())
+}
+
+/// Find a key in the keychain by fingerprint, only keys we own the secret key for if `secret`.
+fn find_key(fingerprint: &str, secret: bool) -> Option<Key> {
+    super::keys(secret)
+        .into_iter()
+        .find(|key| util::fingerprints_equal(&key.fingerprint, fingerprint))
+}
+
+/// FNV-1a hash over the given parts, stable across platforms and Rust versions.
+pub(super) fn hash(parts: &[&[u8]]) -> u64 {
+    parts
+        .iter()
+        .flat_map(|part| part.iter())
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Encode data in the mock text format.
+fn encode(header: &str, fields: &[(&str, String)], body: &[u8]) -> Vec<u8> {
+    let mut data = format!("{header}\n");
+    for (name, value) in fields {
+        data.push_str(&format!("{name} {value}\n"));
+    }
+    data.push('\n');
+
+    let mut data = data.into_bytes();
+    data.extend_from_slice(body);
+    data
+}
+
+/// Decode data in the mock text format, returns the fields and body.
+///
+/// Errors if the data does not start with the given header.
+fn decode<'a>(header: &str, data: &'a [u8]) -> Result<(Fields, &'a [u8]), Err> {
+    let mut rest = data
+        .strip_prefix(header.as_bytes())
+        .and_then(|rest| rest.strip_prefix(b"\n"))
+        .ok_or(Err::Format)?;
+
+    let mut fields = vec![];
+    loop {
+        let end = rest.iter().position(|b| *b == b'\n').ok_or(Err::Format)?;
+        let line = std::str::from_utf8(&rest[..end]).map_err(|_| Err::Format)?;
+        rest = &rest[end + 1..];
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(' ').ok_or(Err::Format)?;
+        fields.push((name.into(), value.into()));
+    }
+
+    Ok((fields, rest))
+}
+
+/// Get all values of the field with the given name.
+fn fields_get<'a>(
+    fields: &'a [(String, String)],
+    name: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+    fields
+        .iter()
+        .filter(move |(other, _)| other == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("malformed mock message, signature or key")]
+    Format,
+
+    #[error("fingerprint does not match public key in keychain: {0}")]
+    UnknownFingerprint(String),
+
+    #[error("no secret key available in keychain")]
+    NoSecretKey,
+}
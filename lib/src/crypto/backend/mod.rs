@@ -2,7 +2,13 @@
 //!
 //! This module groups all crytpo backend implementations.
 
+#[cfg(feature = "backend-age")]
+pub mod age;
 #[cfg(feature = "backend-gnupg-bin")]
 pub mod gnupg_bin;
 #[cfg(feature = "backend-gpgme")]
 pub mod gpgme;
+#[cfg(feature = "backend-mock")]
+pub mod mock;
+#[cfg(feature = "backend-rpgp")]
+pub mod rpgp;
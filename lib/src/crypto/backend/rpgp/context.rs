@@ -0,0 +1,102 @@
+//! Provides rpgp context adapter.
+
+use anyhow::Result;
+
+use super::{raw, Config};
+use crate::crypto::{Config as CryptoConfig, IsContext, Key, Proto, Verified};
+use crate::{Ciphertext, Plaintext, Recipients};
+
+/// Create rpgp context.
+pub fn context(config: &CryptoConfig) -> Result<Context> {
+    let mut rpgp_config = Config::from_env();
+    rpgp_config.passphrase = config.gpg_passphrase.clone();
+    rpgp_config.verbose = config.verbose;
+    Ok(Context::from(rpgp_config))
+}
+
+/// rpgp context.
+pub struct Context {
+    /// rpgp config.
+    config: Config,
+}
+
+impl Context {
+    /// Construct context from rpgp config.
+    fn from(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+impl IsContext for Context {
+    fn encrypt(&mut self, recipients: &Recipients, plaintext: Plaintext) -> Result<Ciphertext> {
+        let fingerprints: Vec<String> = recipients
+            .keys()
+            .iter()
+            .map(|key| key.fingerprint(false))
+            .collect();
+        let fingerprints: Vec<&str> = fingerprints.iter().map(|fp| fp.as_str()).collect();
+        raw::encrypt(&self.config, &fingerprints, plaintext)
+    }
+
+    fn decrypt(&mut self, ciphertext: Ciphertext) -> Result<Plaintext> {
+        raw::decrypt(&self.config, ciphertext)
+    }
+
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        let (plaintext, signers) = raw::decrypt_verify(&self.config, ciphertext)?;
+        Ok(Verified { plaintext, signers })
+    }
+
+    fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
+        raw::can_decrypt(&self.config, ciphertext)
+    }
+
+    fn keys_public(&mut self) -> Result<Vec<Key>> {
+        Ok(raw::public_keys(&self.config)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
+    fn keys_private(&mut self) -> Result<Vec<Key>> {
+        Ok(raw::private_keys(&self.config)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
+    fn import_key(&mut self, key: &[u8]) -> Result<()> {
+        raw::import_key(&self.config, key)
+    }
+
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        Ok(raw::read_keys(key)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect())
+    }
+
+    fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
+        raw::export_key(&self.config, &key.fingerprint(false))
+    }
+
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>> {
+        raw::encrypted_key_ids(ciphertext)
+    }
+
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>> {
+        Ok(raw::find_key_by_id(&self.config, key_id)?.map(|key| key.into_key()))
+    }
+
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+        raw::sign_detached(&self.config, &key.fingerprint(false), data)
+    }
+
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+        raw::verify_detached(&self.config, data, signature)
+    }
+
+    fn supports_proto(&self, proto: Proto) -> bool {
+        proto == Proto::Gpg
+    }
+}
@@ -0,0 +1,55 @@
+//! Crypto backend using rpgp for GPG, implemented in pure Rust.
+//!
+//! This does not require the `gpg` binary or GPGME library, and reads keys from a keyring file or
+//! directory instead of a GnuPG keychain.
+
+pub mod context;
+pub mod raw;
+
+use std::env;
+use std::path::PathBuf;
+
+use crate::Plaintext;
+
+/// Environment variable to override the keyring path.
+pub const ENV_KEYRING: &str = "PRS_RPGP_KEYRING";
+
+/// rpgp config.
+pub struct Config {
+    /// Keyring path.
+    ///
+    /// This may be a single file holding any number of keys, or a directory of key files. Keys may
+    /// be ASCII armored or binary. Imported keys can only be written to a directory.
+    pub keyring: PathBuf,
+
+    /// Passphrase to unlock protected secret keys, protected keys can't be used if not set.
+    pub passphrase: Option<Plaintext>,
+
+    /// Whether to show verbose output.
+    pub verbose: bool,
+}
+
+impl Config {
+    /// Construct with default keyring path.
+    ///
+    /// Respects the `PRS_RPGP_KEYRING` environment variable.
+    pub fn from_env() -> Self {
+        let keyring = env::var_os(ENV_KEYRING)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(default_keyring);
+
+        Self {
+            keyring,
+            passphrase: None,
+            verbose: false,
+        }
+    }
+}
+
+/// Get the default keyring directory.
+///
+/// Uses `$XDG_DATA_HOME/prs/keyring`, and falls back to `~/.local/share/prs/keyring`.
+fn default_keyring() -> PathBuf {
+    crate::util::env::data_dir().join("keyring")
+}
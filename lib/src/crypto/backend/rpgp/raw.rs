@@ -0,0 +1,943 @@
+//! Raw interface to rpgp.
+//!
+//! This provides the most basic functions to encrypt and decrypt using OpenPGP, and to manage a
+//! simple keyring of key files.
+
+use std::convert::TryFrom;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, SubsecRound, Utc};
+use pgp::composed::message::Esk;
+use pgp::composed::signed_key::{from_reader_many, PublicOrSecret, SignedKeyDetails};
+use pgp::crypto::hash::HashAlgorithm;
+use pgp::crypto::public_key::PublicKeyAlgorithm;
+use pgp::crypto::sym::SymmetricKeyAlgorithm;
+use pgp::packet::{KeyFlags, Signature, SignatureConfig, SignatureType, Subpacket, SubpacketData};
+use pgp::ser::Serialize;
+use pgp::types::{
+    EskType, Fingerprint, KeyId, KeyVersion, PkeskBytes, PublicKeyTrait, PublicParams,
+    SecretKeyTrait, SignatureBytes,
+};
+use pgp::{
+    ArmorOptions, Deserializable, Message, SignedPublicKey, SignedPublicSubKey, SignedSecretKey,
+    StandaloneSignature,
+};
+use rand::{CryptoRng, Rng};
+use thiserror::Error;
+
+use super::Config;
+use crate::crypto::proto::gpg::{self, Capabilities, Validity};
+use crate::{Ciphertext, Plaintext};
+
+/// Encrypt plaintext for the given recipients.
+///
+/// - `config`: rpgp config
+/// - `recipients`: list of recipient fingerprints to encrypt for
+/// - `plaintext`: plaintext to encrypt
+///
+/// # Panics
+///
+/// Panics if list of recipients is empty.
+pub fn encrypt(config: &Config, recipients: &[&str], plaintext: Plaintext) -> Result<Ciphertext> {
+    assert!(
+        !recipients.is_empty(),
+        "attempting to encrypt secret for empty list of recipients"
+    );
+
+    let keyring = read_keyring(config)?;
+    let keys = recipients
+        .iter()
+        .map(|fingerprint| {
+            let key = keyring
+                .public
+                .iter()
+                .find(|key| fingerprint_hex(&key.fingerprint()) == fingerprint.to_uppercase())
+                .ok_or_else(|| Err::UnknownKey(fingerprint.to_string()))?;
+            encryption_keys(key)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let keys: Vec<&UsableKey> = keys.iter().flatten().collect();
+
+    let message = Message::new_literal_bytes("", plaintext.unsecure_ref())
+        .encrypt_to_keys_seipdv1(rand::thread_rng(), SymmetricKeyAlgorithm::AES256, &keys)
+        .map_err(Err::Encrypt)?;
+    Ok(Ciphertext::from(message.to_bytes().map_err(Err::Encrypt)?))
+}
+
+/// Decrypt ciphertext.
+///
+/// Passphrase protected secret keys are only used if a passphrase is configured.
+///
+/// - `config`: rpgp config
+/// - `ciphertext`: ciphertext to decrypt
+pub fn decrypt(config: &Config, ciphertext: Ciphertext) -> Result<Plaintext> {
+    let keyring = read_keyring(config)?;
+    let message = decrypt_message(config, &keyring, &ciphertext)?;
+    content(&message)
+}
+
+/// Decrypt ciphertext, and verify its signatures.
+///
+/// Returns the plaintext along with the primary key fingerprints of all keys in the keyring that
+/// made a valid signature. The list of signers is empty if the message is not signed.
+///
+/// - `config`: rpgp config
+/// - `ciphertext`: ciphertext to decrypt
+pub fn decrypt_verify(config: &Config, ciphertext: Ciphertext) -> Result<(Plaintext, Vec<String>)> {
+    let keyring = read_keyring(config)?;
+    let message = decrypt_message(config, &keyring, &ciphertext)?
+        .decompress()
+        .map_err(Err::Decrypt)?;
+    let signers = match message {
+        Message::Signed { .. } => signers(&keyring, |key| message.verify(key).is_ok()),
+        _ => vec![],
+    };
+    Ok((content(&message)?, signers))
+}
+
+/// Check whether we can decrypt ciphertext.
+///
+/// This checks whether we own a secret key the ciphertext is encrypted for, without decrypting.
+///
+/// - `config`: rpgp config
+/// - `ciphertext`: ciphertext to check
+pub fn can_decrypt(config: &Config, ciphertext: Ciphertext) -> Result<bool> {
+    let keyring = read_keyring(config)?;
+    let ids = recipient_ids(&parse_message(&ciphertext)?);
+    Ok(keyring
+        .secret
+        .iter()
+        .flat_map(secret_key_ids)
+        .any(|id| ids.contains(&id)))
+}
+
+/// Get the IDs of the keys a ciphertext is encrypted for, without decrypting it.
+///
+/// - `ciphertext`: ciphertext to check
+pub fn encrypted_key_ids(ciphertext: Ciphertext) -> Result<Vec<String>> {
+    Ok(recipient_ids(&parse_message(&ciphertext)?)
+        .iter()
+        .map(|id| format!("{id:X}"))
+        .collect())
+}
+
+/// Find the public key having a (sub)key with the given key ID.
+///
+/// - `config`: rpgp config
+/// - `key_id`: key ID of primary key or subkey
+pub fn find_key_by_id(config: &Config, key_id: &str) -> Result<Option<gpg::Key>> {
+    let matches = |id: KeyId| format!("{id:X}").eq_ignore_ascii_case(key_id.trim());
+    Ok(read_keyring(config)?
+        .public
+        .iter()
+        .find(|key| {
+            matches(key.key_id()) || key.public_subkeys.iter().any(|sub| matches(sub.key_id()))
+        })
+        .map(public_key))
+}
+
+/// Get all public keys from keyring.
+pub fn public_keys(config: &Config) -> Result<Vec<gpg::Key>> {
+    Ok(read_keyring(config)?
+        .public
+        .iter()
+        .map(public_key)
+        .collect())
+}
+
+/// Get all private keys from keyring.
+pub fn private_keys(config: &Config) -> Result<Vec<gpg::Key>> {
+    Ok(read_keyring(config)?
+        .secret
+        .iter()
+        .map(|key| {
+            let subkeys = key
+                .public_subkeys
+                .iter()
+                .map(|sub| (*sub.created_at(), sub.signatures.as_slice()))
+                .chain(
+                    key.secret_subkeys
+                        .iter()
+                        .map(|sub| (*sub.created_at(), sub.signatures.as_slice())),
+                )
+                .collect();
+            into_key(
+                &key.fingerprint(),
+                key.key_id(),
+                *key.created_at(),
+                &key.details,
+                subkeys,
+            )
+        })
+        .collect())
+}
+
+/// Import the given public key(s) into the keyring.
+///
+/// Each key is written to its own armored file in the keyring directory, named after its
+/// fingerprint. Secret keys are refused.
+pub fn import_key(config: &Config, key: &[u8]) -> Result<()> {
+    if config.keyring.is_file() {
+        return Err(Err::ReadOnlyKeyring(config.keyring.clone()).into());
+    }
+
+    let (keys, _) = from_reader_many(key).map_err(Err::Parse)?;
+    for key in keys {
+        let key = match key.map_err(Err::Parse)? {
+            PublicOrSecret::Public(key) => key,
+            PublicOrSecret::Secret(_) => return Err(Err::ImportSecret.into()),
+        };
+
+        let data = key
+            .to_armored_bytes(ArmorOptions::default())
+            .map_err(Err::Parse)?;
+        fs::create_dir_all(&config.keyring).map_err(Err::Write)?;
+        fs::write(
+            config
+                .keyring
+                .join(format!("{}.asc", fingerprint_hex(&key.fingerprint()))),
+            data,
+        )
+        .map_err(Err::Write)?;
+    }
+
+    Ok(())
+}
+
+/// Read the given public key(s), without importing them into the keyring.
+///
+/// Secret keys are refused.
+pub fn read_keys(key: &[u8]) -> Result<Vec<gpg::Key>> {
+    let (keys, _) = from_reader_many(key).map_err(Err::Parse)?;
+    keys.map(|key| match key.map_err(Err::Parse)? {
+        PublicOrSecret::Public(key) => Ok(public_key(&key)),
+        PublicOrSecret::Secret(_) => Err(Err::ImportSecret.into()),
+    })
+    .collect()
+}
+
+/// Export the given key from the keyring as bytes.
+///
+/// The key is exported as ASCII armored public key.
+pub fn export_key(config: &Config, fingerprint: &str) -> Result<Vec<u8>> {
+    read_keyring(config)?
+        .public
+        .iter()
+        .find(|key| fingerprint_hex(&key.fingerprint()) == fingerprint.to_uppercase())
+        .ok_or_else(|| Err::UnknownKey(fingerprint.to_string()))?
+        .to_armored_bytes(ArmorOptions::default())
+        .map_err(|err| Err::Parse(err).into())
+}
+
+/// Create a detached signature over data.
+///
+/// The primary key is used for signing. If it is passphrase protected, a passphrase must be
+/// configured.
+///
+/// - `config`: rpgp config
+/// - `fingerprint`: fingerprint of secret key to sign with
+/// - `data`: data to sign
+pub fn sign_detached(config: &Config, fingerprint: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let keyring = read_keyring(config)?;
+    let key = keyring
+        .secret
+        .iter()
+        .find(|key| fingerprint_hex(&key.fingerprint()) == fingerprint.to_uppercase())
+        .ok_or_else(|| Err::UnknownKey(fingerprint.to_string()))?;
+    let passphrase = passphrase(config)?;
+    if passphrase.is_none() && key.primary_key.secret_params().is_encrypted() {
+        return Err(Err::Protected.into());
+    }
+    let passphrase = passphrase.unwrap_or_default();
+
+    let mut signature = SignatureConfig::v4(SignatureType::Binary, key.algorithm(), key.hash_alg());
+    signature.hashed_subpackets = vec![Subpacket::regular(SubpacketData::SignatureCreationTime(
+        chrono::Utc::now().trunc_subsecs(0),
+    ))];
+    signature.unhashed_subpackets = vec![Subpacket::regular(SubpacketData::Issuer(key.key_id()))];
+    let signature = signature
+        .sign(key, || passphrase.to_string(), data)
+        .map_err(Err::Sign)?;
+
+    Ok(StandaloneSignature::new(signature)
+        .to_bytes()
+        .map_err(Err::Sign)?)
+}
+
+/// Verify a detached signature over data.
+///
+/// Returns the primary key fingerprints of all keys in the keyring that made a valid signature.
+/// Only signatures made by a valid, signing capable (sub)key are accepted.
+///
+/// - `config`: rpgp config
+/// - `data`: signed data
+/// - `signature`: detached signature, armored or binary
+pub fn verify_detached(config: &Config, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+    let keyring = read_keyring(config)?;
+    let (signature, _) = StandaloneSignature::from_reader_single(signature).map_err(Err::Parse)?;
+
+    Ok(signers(&keyring, |key| signature.verify(key, data).is_ok()))
+}
+
+/// Keys loaded from the keyring.
+struct Keyring {
+    /// Public keys, including those of secret keys.
+    public: Vec<SignedPublicKey>,
+
+    /// Secret keys.
+    secret: Vec<SignedSecretKey>,
+}
+
+/// Read all keys from the keyring.
+///
+/// If the keyring is a directory, all non-hidden files in it are read. A keyring that doesn't
+/// exist is empty.
+fn read_keyring(config: &Config) -> Result<Keyring> {
+    let mut keyring = Keyring {
+        public: vec![],
+        secret: vec![],
+    };
+
+    for path in keyring_files(&config.keyring)? {
+        let data = fs::read(&path).map_err(Err::Read)?;
+        let (keys, _) = from_reader_many(data.as_slice())
+            .map_err(|err| Err::ParseFile(path.display().to_string(), err))?;
+        for key in keys {
+            match key.map_err(|err| Err::ParseFile(path.display().to_string(), err))? {
+                PublicOrSecret::Public(key) => keyring.public.push(key),
+                PublicOrSecret::Secret(key) => {
+                    keyring.public.push(SignedPublicKey::from(key.clone()));
+                    keyring.secret.push(key);
+                }
+            }
+        }
+    }
+
+    // Dedupe public keys, secret keys may be listed as public key as well
+    let mut seen = vec![];
+    keyring.public.retain(|key| {
+        let fingerprint = key.fingerprint();
+        if seen.contains(&fingerprint) {
+            false
+        } else {
+            seen.push(fingerprint);
+            true
+        }
+    });
+
+    Ok(keyring)
+}
+
+/// List key files in keyring.
+fn keyring_files(keyring: &Path) -> Result<Vec<PathBuf>> {
+    if keyring.is_file() {
+        return Ok(vec![keyring.to_path_buf()]);
+    }
+    if !keyring.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(keyring)
+        .map_err(Err::Read)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && !path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|name| name.starts_with('.'))
+                    .unwrap_or(true)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Parse an OpenPGP message, either armored or binary.
+fn parse_message(ciphertext: &Ciphertext) -> Result<Message> {
+    Ok(Message::from_reader_single(ciphertext.unsecure_ref())
+        .map_err(Err::Parse)?
+        .0)
+}
+
+/// Decrypt an OpenPGP message with our secret keys.
+///
+/// Passphrase protected secret keys are only used if a passphrase is configured.
+fn decrypt_message(config: &Config, keyring: &Keyring, ciphertext: &Ciphertext) -> Result<Message> {
+    let message = parse_message(ciphertext)?;
+
+    // Select our secret keys the message is encrypted for
+    let ids = recipient_ids(&message);
+    let keys: Vec<&SignedSecretKey> = keyring
+        .secret
+        .iter()
+        .filter(|key| secret_key_ids(key).iter().any(|id| ids.contains(id)))
+        .collect();
+    if keys.is_empty() {
+        return Err(Err::NoSecretKey.into());
+    }
+    let passphrase = passphrase(config)?;
+    let keys: Vec<&SignedSecretKey> = keys
+        .into_iter()
+        .filter(|key| passphrase.is_some() || !is_protected(key))
+        .collect();
+    if keys.is_empty() {
+        return Err(Err::Protected.into());
+    }
+
+    let passphrase = passphrase.unwrap_or_default();
+    let (message, _) = message
+        .decrypt(|| passphrase.to_string(), &keys)
+        .map_err(Err::Decrypt)?;
+    Ok(message)
+}
+
+/// Get the plaintext content of a decrypted message.
+fn content(message: &Message) -> Result<Plaintext> {
+    let plaintext = message
+        .get_content()
+        .map_err(Err::Decrypt)?
+        .ok_or(Err::NoContent)?;
+    Ok(Plaintext::from(plaintext))
+}
+
+/// Find keys in the keyring that made a valid signature.
+///
+/// Each signing capable (sub)key of all valid keys is passed to `verify`. Returns the primary key
+/// fingerprints of all keys for which it succeeds.
+fn signers(keyring: &Keyring, verify: impl Fn(&UsableKey) -> bool) -> Vec<String> {
+    keyring
+        .public
+        .iter()
+        .filter(|key| signing_keys(key).iter().any(&verify))
+        .map(|key| fingerprint_hex(&key.fingerprint()))
+        .collect()
+}
+
+/// Get IDs of all keys a message is encrypted for.
+fn recipient_ids(message: &Message) -> Vec<KeyId> {
+    match message {
+        Message::Encrypted { esk, .. } => esk
+            .iter()
+            .filter_map(|esk| match esk {
+                Esk::PublicKeyEncryptedSessionKey(pkesk) => pkesk.id().ok().cloned(),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Get IDs of a secret key and all its subkeys.
+fn secret_key_ids(key: &SignedSecretKey) -> Vec<KeyId> {
+    std::iter::once(key.key_id())
+        .chain(key.secret_subkeys.iter().map(|sub| sub.key_id()))
+        .collect()
+}
+
+/// Get the configured passphrase to unlock protected secret keys with, if any.
+///
+/// This borrows the configured passphrase, no copy is made. rpgp takes a closure returning an
+/// owned `String` to unlock keys, only call `to_string` inside it, so a copy is only made when a
+/// protected key is actually unlocked. rpgp drops that copy itself right after deriving the key.
+fn passphrase(config: &Config) -> Result<Option<&str>> {
+    config
+        .passphrase
+        .as_ref()
+        .map(|passphrase| {
+            passphrase
+                .unsecure_to_str()
+                .map_err(|_| Err::PassphraseEncoding.into())
+        })
+        .transpose()
+}
+
+/// Check whether the secret key material is passphrase protected.
+fn is_protected(key: &SignedSecretKey) -> bool {
+    key.primary_key.secret_params().is_encrypted()
+        || key
+            .secret_subkeys
+            .iter()
+            .any(|sub| sub.key.secret_params().is_encrypted())
+}
+
+/// Transform public key into GPG key.
+fn public_key(key: &SignedPublicKey) -> gpg::Key {
+    let subkeys = key
+        .public_subkeys
+        .iter()
+        .map(|sub| (*sub.created_at(), sub.signatures.as_slice()))
+        .collect();
+    into_key(
+        &key.fingerprint(),
+        key.key_id(),
+        *key.created_at(),
+        &key.details,
+        subkeys,
+    )
+}
+
+/// Build GPG key with validity, expiry and capabilities from its signatures.
+///
+/// - `fingerprint`: primary key fingerprint
+/// - `key_id`: primary key ID, to find self-signatures
+/// - `created`: primary key creation time
+/// - `details`: primary key details with user IDs and signatures
+/// - `subkeys`: creation time and binding signatures of each subkey
+fn into_key(
+    fingerprint: &Fingerprint,
+    key_id: KeyId,
+    created: DateTime<Utc>,
+    details: &SignedKeyDetails,
+    subkeys: Vec<(DateTime<Utc>, &[Signature])>,
+) -> gpg::Key {
+    let now = Utc::now();
+    // Primary key expiry and capabilities come from the most recent self-signature
+    let self_signature = latest_signature(
+        details
+            .direct_signatures
+            .iter()
+            .chain(details.users.iter().flat_map(|user| user.signatures.iter()))
+            .filter(|sig| sig.issuer().contains(&&key_id)),
+    );
+    let expires = self_signature
+        .and_then(|sig| sig.key_expiration_time())
+        .map(|duration| created + *duration);
+    let mut flags: Vec<KeyFlags> = self_signature
+        .map(|sig| sig.key_flags())
+        .into_iter()
+        .collect();
+
+    // Add capabilities of subkeys that are not revoked or expired
+    for (created, signatures) in subkeys {
+        let sig = match latest_signature(signatures.iter()) {
+            Some(sig) => sig,
+            None => continue,
+        };
+        let expired = sig
+            .key_expiration_time()
+            .map(|duration| created + *duration <= now)
+            .unwrap_or(false);
+        if sig.typ() != SignatureType::SubkeyRevocation && !expired {
+            flags.push(sig.key_flags());
+        }
+    }
+
+    let validity = if !details.revocation_signatures.is_empty() {
+        Validity::Revoked
+    } else if expires.map(|expires| expires <= now).unwrap_or(false) {
+        Validity::Expired
+    } else {
+        Validity::Valid
+    };
+
+    // Like gnupg, keys that are not valid have no usable capabilities
+    if validity != Validity::Valid {
+        flags.clear();
+    }
+
+    gpg::Key {
+        fingerprint: fingerprint_hex(fingerprint),
+        user_ids: user_ids(details),
+        validity,
+        expires: expires.and_then(|expires| u64::try_from(expires.timestamp()).ok()),
+        capabilities: Capabilities {
+            encrypt: flags
+                .iter()
+                .any(|flags| flags.encrypt_comms() || flags.encrypt_storage()),
+            sign: flags.iter().any(|flags| flags.sign()),
+            certify: flags.iter().any(|flags| flags.certify()),
+            authenticate: flags.iter().any(|flags| flags.authentication()),
+        },
+    }
+}
+
+/// Get the most recently created signature.
+fn latest_signature<'a>(signatures: impl Iterator<Item = &'a Signature>) -> Option<&'a Signature> {
+    signatures.max_by_key(|sig| sig.created().copied())
+}
+
+/// Get displayable user IDs of a key.
+fn user_ids(details: &SignedKeyDetails) -> Vec<String> {
+    details
+        .users
+        .iter()
+        .map(|user| user.id.id().to_string())
+        .collect()
+}
+
+/// Format fingerprint as uppercase hexadecimal string.
+fn fingerprint_hex(fingerprint: &Fingerprint) -> String {
+    fingerprint
+        .as_bytes()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+/// Select the keys to encrypt to for a recipient.
+///
+/// Prefers subkeys flagged for encryption, falls back to the primary key if it is able to encrypt.
+fn encryption_keys(key: &SignedPublicKey) -> Result<Vec<UsableKey<'_>>> {
+    let subkeys: Vec<UsableKey> = key
+        .public_subkeys
+        .iter()
+        .filter(|sub| {
+            sub.is_encryption_key()
+                && sub.signatures.iter().any(|sig| {
+                    let flags = sig.key_flags();
+                    flags.encrypt_comms() || flags.encrypt_storage()
+                })
+        })
+        .map(UsableKey::Sub)
+        .collect();
+    if !subkeys.is_empty() {
+        return Ok(subkeys);
+    }
+    if key.is_encryption_key() {
+        return Ok(vec![UsableKey::Primary(key)]);
+    }
+    Err(Err::NoUsableKey(fingerprint_hex(&key.fingerprint())).into())
+}
+
+/// Select the keys to verify signatures of a key with.
+///
+/// These are the primary key and subkeys flagged for signing. Like gnupg, revoked or expired
+/// (sub)keys are not used.
+fn signing_keys(key: &SignedPublicKey) -> Vec<UsableKey<'_>> {
+    if public_key(key).validity != Validity::Valid {
+        return vec![];
+    }
+
+    let key_id = key.key_id();
+    let self_signature = latest_signature(
+        key.details
+            .direct_signatures
+            .iter()
+            .chain(
+                key.details
+                    .users
+                    .iter()
+                    .flat_map(|user| user.signatures.iter()),
+            )
+            .filter(|sig| sig.issuer().contains(&&key_id)),
+    );
+    let primary = self_signature
+        .filter(|sig| sig.key_flags().sign())
+        .map(|_| UsableKey::Primary(key));
+
+    let now = Utc::now();
+    let subkeys = key.public_subkeys.iter().filter(move |sub| {
+        latest_signature(sub.signatures.iter())
+            .map(|sig| {
+                let expired = sig
+                    .key_expiration_time()
+                    .map(|duration| *sub.created_at() + *duration <= now)
+                    .unwrap_or(false);
+                sig.typ() != SignatureType::SubkeyRevocation && !expired && sig.key_flags().sign()
+            })
+            .unwrap_or(false)
+    });
+
+    primary
+        .into_iter()
+        .chain(subkeys.map(UsableKey::Sub))
+        .collect()
+}
+
+/// A primary key or subkey to encrypt to, or to verify signatures with.
+///
+/// rpgp requires all keys to encrypt to to be of the same type.
+#[derive(Debug)]
+enum UsableKey<'a> {
+    Primary(&'a SignedPublicKey),
+    Sub(&'a SignedPublicSubKey),
+}
+
+macro_rules! delegate {
+    ($self:ident, $key:ident => $expr:expr) => {
+        match $self {
+            UsableKey::Primary($key) => $expr,
+            UsableKey::Sub($key) => $expr,
+        }
+    };
+}
+
+impl PublicKeyTrait for UsableKey<'_> {
+    fn version(&self) -> KeyVersion {
+        delegate!(self, key => key.version())
+    }
+
+    fn fingerprint(&self) -> Fingerprint {
+        delegate!(self, key => key.fingerprint())
+    }
+
+    fn key_id(&self) -> KeyId {
+        delegate!(self, key => key.key_id())
+    }
+
+    fn algorithm(&self) -> PublicKeyAlgorithm {
+        delegate!(self, key => key.algorithm())
+    }
+
+    fn created_at(&self) -> &chrono::DateTime<chrono::Utc> {
+        delegate!(self, key => key.created_at())
+    }
+
+    fn expiration(&self) -> Option<u16> {
+        delegate!(self, key => key.expiration())
+    }
+
+    fn verify_signature(
+        &self,
+        hash: HashAlgorithm,
+        data: &[u8],
+        sig: &SignatureBytes,
+    ) -> pgp::errors::Result<()> {
+        delegate!(self, key => key.verify_signature(hash, data, sig))
+    }
+
+    fn encrypt<R: CryptoRng + Rng>(
+        &self,
+        rng: R,
+        plain: &[u8],
+        typ: EskType,
+    ) -> pgp::errors::Result<PkeskBytes> {
+        delegate!(self, key => key.encrypt(rng, plain, typ))
+    }
+
+    fn serialize_for_hashing(&self, writer: &mut impl io::Write) -> pgp::errors::Result<()> {
+        delegate!(self, key => key.serialize_for_hashing(writer))
+    }
+
+    fn public_params(&self) -> &PublicParams {
+        delegate!(self, key => key.public_params())
+    }
+}
+
+/// rpgp backend error.
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to encrypt plaintext")]
+    Encrypt(#[source] pgp::errors::Error),
+
+    #[error("failed to decrypt ciphertext")]
+    Decrypt(#[source] pgp::errors::Error),
+
+    #[error("failed to decrypt ciphertext, no secret key available to decrypt with")]
+    NoSecretKey,
+
+    #[error("secret key is passphrase protected, no passphrase given")]
+    Protected,
+
+    #[error("passphrase is not valid UTF-8")]
+    PassphraseEncoding,
+
+    #[error("failed to decrypt ciphertext, message has no content")]
+    NoContent,
+
+    #[error("failed to sign data")]
+    Sign(#[source] pgp::errors::Error),
+
+    #[error("failed to parse OpenPGP data")]
+    Parse(#[source] pgp::errors::Error),
+
+    #[error("failed to parse OpenPGP keys from {0}")]
+    ParseFile(String, #[source] pgp::errors::Error),
+
+    #[error("key not found in keyring: {0}")]
+    UnknownKey(String),
+
+    #[error("key has no encryption capable (sub)key: {0}")]
+    NoUsableKey(String),
+
+    #[error("failed to import key, refusing to import secret key")]
+    ImportSecret,
+
+    #[error("failed to import key, keyring is a single file: {}", _0.display())]
+    ReadOnlyKeyring(PathBuf),
+
+    #[error("failed to read data")]
+    Read(#[source] io::Error),
+
+    #[error("failed to write data")]
+    Write(#[source] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use pgp::crypto::ecc_curve::ECCCurve;
+    use pgp::{KeyType, SecretKeyParamsBuilder, SubkeyParamsBuilder};
+
+    use super::*;
+
+    /// Generate a secret key, and write it to a new keyring directory.
+    ///
+    /// The primary key certifies, and signs if `sign` is set. A subkey is used for encryption.
+    fn keyring(passphrase: Option<&str>, sign: bool) -> (tempfile::TempDir, SignedSecretKey) {
+        let passphrase = passphrase.map(|passphrase| passphrase.to_string());
+        let params = SecretKeyParamsBuilder::default()
+            .key_type(KeyType::EdDSALegacy)
+            .can_certify(true)
+            .can_sign(sign)
+            .primary_user_id("Alice <alice@example.com>".into())
+            .passphrase(passphrase.clone())
+            .subkey(
+                SubkeyParamsBuilder::default()
+                    .key_type(KeyType::ECDH(ECCCurve::Curve25519))
+                    .can_encrypt(true)
+                    .passphrase(passphrase.clone())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let key = params
+            .generate(rand::thread_rng())
+            .unwrap()
+            .sign(rand::thread_rng(), || passphrase.unwrap_or_default())
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("alice.asc"),
+            key.to_armored_bytes(ArmorOptions::default()).unwrap(),
+        )
+        .unwrap();
+        (dir, key)
+    }
+
+    fn rpgp_config(keyring: &Path, passphrase: Option<&str>) -> Config {
+        Config {
+            keyring: keyring.to_path_buf(),
+            passphrase: passphrase.map(Plaintext::from),
+            verbose: false,
+        }
+    }
+
+    fn copy(ciphertext: &Ciphertext) -> Ciphertext {
+        Ciphertext::from(ciphertext.unsecure_ref().to_vec())
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let (dir, key) = keyring(None, true);
+        let config = rpgp_config(dir.path(), None);
+        let fingerprint = fingerprint_hex(&key.fingerprint());
+
+        let ciphertext = encrypt(&config, &[&fingerprint], Plaintext::from("secret")).unwrap();
+        assert!(can_decrypt(&config, copy(&ciphertext)).unwrap());
+        assert_eq!(
+            encrypted_key_ids(copy(&ciphertext)).unwrap(),
+            vec![format!("{:X}", key.secret_subkeys[0].key_id())],
+        );
+        assert_eq!(
+            decrypt(&config, copy(&ciphertext)).unwrap().unsecure_ref(),
+            b"secret",
+        );
+
+        // Unsigned message has no signers
+        let (plaintext, signers) = decrypt_verify(&config, ciphertext).unwrap();
+        assert_eq!(plaintext.unsecure_ref(), b"secret");
+        assert!(signers.is_empty());
+
+        // Cannot decrypt without secret key
+        let (other, _) = keyring(None, true);
+        let other = rpgp_config(other.path(), None);
+        let ciphertext = encrypt(&other, &[&fingerprint], Plaintext::from("secret"));
+        assert!(
+            ciphertext.is_err(),
+            "key of other keyring should be unknown"
+        );
+    }
+
+    #[test]
+    fn decrypt_protected() {
+        let (dir, key) = keyring(Some("hunter2"), true);
+        let fingerprint = fingerprint_hex(&key.fingerprint());
+        let ciphertext = encrypt(
+            &rpgp_config(dir.path(), None),
+            &[&fingerprint],
+            Plaintext::from("secret"),
+        )
+        .unwrap();
+
+        // Protected key is not used without passphrase
+        let err = decrypt(&rpgp_config(dir.path(), None), copy(&ciphertext))
+            .err()
+            .unwrap();
+        assert!(matches!(err.downcast_ref::<Err>(), Some(Err::Protected)));
+
+        let err = decrypt(&rpgp_config(dir.path(), Some("wrong")), copy(&ciphertext))
+            .err()
+            .unwrap();
+        assert!(matches!(err.downcast_ref::<Err>(), Some(Err::Decrypt(_))));
+
+        let plaintext = decrypt(&rpgp_config(dir.path(), Some("hunter2")), ciphertext).unwrap();
+        assert_eq!(plaintext.unsecure_ref(), b"secret");
+    }
+
+    #[test]
+    fn decrypt_verify_signed() {
+        let (dir, key) = keyring(None, true);
+        let config = rpgp_config(dir.path(), None);
+        let fingerprint = fingerprint_hex(&key.fingerprint());
+
+        let keyring = read_keyring(&config).unwrap();
+        let ciphertext = Message::new_literal_bytes("", b"secret")
+            .sign(
+                rand::thread_rng(),
+                &key,
+                String::new,
+                HashAlgorithm::SHA2_256,
+            )
+            .unwrap()
+            .encrypt_to_keys_seipdv1(
+                rand::thread_rng(),
+                SymmetricKeyAlgorithm::AES256,
+                &encryption_keys(&keyring.public[0])
+                    .unwrap()
+                    .iter()
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        let ciphertext = Ciphertext::from(ciphertext.to_bytes().unwrap());
+
+        let (plaintext, signers) = decrypt_verify(&config, ciphertext).unwrap();
+        assert_eq!(plaintext.unsecure_ref(), b"secret");
+        assert_eq!(signers, vec![fingerprint]);
+    }
+
+    #[test]
+    fn sign_verify_detached() {
+        let (dir, key) = keyring(None, true);
+        let config = rpgp_config(dir.path(), None);
+        let fingerprint = fingerprint_hex(&key.fingerprint());
+
+        let signature = sign_detached(&config, &fingerprint, b"data").unwrap();
+        assert_eq!(
+            verify_detached(&config, b"data", &signature).unwrap(),
+            vec![fingerprint],
+        );
+        assert!(verify_detached(&config, b"other", &signature)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn verify_detached_not_signing_key() {
+        // Signature by a key that is not flagged for signing is not accepted
+        let (dir, key) = keyring(None, false);
+        let config = rpgp_config(dir.path(), None);
+        let fingerprint = fingerprint_hex(&key.fingerprint());
+
+        let signature = sign_detached(&config, &fingerprint, b"data").unwrap();
+        assert!(verify_detached(&config, b"data", &signature)
+            .unwrap()
+            .is_empty());
+    }
+}
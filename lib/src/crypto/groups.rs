@@ -0,0 +1,282 @@
+//! Named groups of recipients for password stores.
+//!
+//! Groups are defined in the `.prs/groups.toml` file of a store, mapping a group name to a list
+//! of GPG fingerprints:
+//!
+//! ```toml
+//! ops = ["FINGERPRINT1", "FINGERPRINT2"]
+//! ```
+//!
+//! GPG IDs files reference a group by its name prefixed with `@`, such as `@ops`. References are
+//! expanded into the fingerprints of their members when recipients are loaded.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use thiserror::Error;
+
+use super::util;
+use crate::Store;
+
+/// Password store groups file.
+const STORE_GROUPS_FILE: &str = ".prs/groups.toml";
+
+/// Prefix for group references in GPG IDs files.
+pub const GROUP_PREFIX: char = '@';
+
+/// Get the groups file for a store.
+pub fn store_groups_file(store: &Store) -> PathBuf {
+    store.root.join(STORE_GROUPS_FILE)
+}
+
+/// Get the group name if the given GPG IDs entry references a group.
+pub fn group_ref(entry: &str) -> Option<&str> {
+    entry
+        .trim()
+        .strip_prefix(GROUP_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Format a reference to the given group for use in GPG IDs files.
+pub fn format_group_ref(name: &str) -> String {
+    format!("{GROUP_PREFIX}{name}")
+}
+
+/// Named recipient groups of a store.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Groups {
+    groups: BTreeMap<String, Vec<String>>,
+}
+
+impl Groups {
+    /// Load groups from the given store.
+    ///
+    /// Returns no groups if the store doesn't have a groups file. The file signature is verified
+    /// if signing is enabled.
+    pub fn load(store: &Store) -> Result<Self> {
+        let path = store_groups_file(store);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        super::store::verify_files(store, std::slice::from_ref(&path))?;
+        Self::read(&path)
+    }
+
+    /// Read groups from the given file.
+    fn read(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path).map_err(Err::Read)?;
+        let groups: BTreeMap<String, Vec<String>> = toml::from_str(&data).map_err(Err::Parse)?;
+        Ok(Self {
+            groups: groups
+                .into_iter()
+                .map(|(name, members)| {
+                    let members = members.iter().map(util::format_fingerprint).collect();
+                    (name, members)
+                })
+                .collect(),
+        })
+    }
+
+    /// Save groups to the given store.
+    ///
+    /// This does not sign the groups file, see `store_save_groups`.
+    pub(crate) fn save(&self, store: &Store) -> Result<PathBuf> {
+        let path = store_groups_file(store);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(Err::Write)?;
+        }
+        let data = toml::to_string(&self.groups).map_err(Err::Serialize)?;
+        fs::write(&path, data).map_err(Err::Write)?;
+        Ok(path)
+    }
+
+    /// Get the names of all groups.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.groups.keys()
+    }
+
+    /// Get the fingerprints of the members of a group, `None` if it doesn't exist.
+    pub fn members(&self, name: &str) -> Option<&[String]> {
+        self.groups.get(name).map(|members| members.as_slice())
+    }
+
+    /// Add a member to a group, the group is created if it doesn't exist.
+    ///
+    /// Returns false if the fingerprint already was a member.
+    pub fn add_member(&mut self, name: &str, fingerprint: &str) -> bool {
+        let members = self.groups.entry(name.into()).or_default();
+        if members
+            .iter()
+            .any(|member| util::fingerprints_equal(member, fingerprint))
+        {
+            return false;
+        }
+        members.push(util::format_fingerprint(fingerprint));
+        true
+    }
+
+    /// Remove a member from a group.
+    ///
+    /// Returns false if the fingerprint was not a member.
+    pub fn remove_member(&mut self, name: &str, fingerprint: &str) -> bool {
+        match self.groups.get_mut(name) {
+            Some(members) => {
+                let count = members.len();
+                members.retain(|member| !util::fingerprints_equal(member, fingerprint));
+                members.len() != count
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a group.
+    ///
+    /// Returns false if the group didn't exist.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    /// Expand group references in the given GPG IDs entries into the fingerprints of their members.
+    ///
+    /// The returned list is deduplicated. Errors if an unknown group is referenced. Groups can't
+    /// be nested, errors if a group has a group reference as member.
+    pub fn expand(&self, entries: &[String]) -> Result<Vec<String>> {
+        let mut fingerprints: Vec<String> = Vec::new();
+        for entry in entries {
+            let expanded = match group_ref(entry) {
+                Some(name) => {
+                    let members = self
+                        .members(name)
+                        .ok_or_else(|| Err::UnknownGroup(name.into()))?;
+                    if members.iter().any(|member| group_ref(member).is_some()) {
+                        return Err(Err::NestedGroup(name.into()).into());
+                    }
+                    members.to_vec()
+                }
+                None => vec![entry.clone()],
+            };
+            for fp in expanded {
+                if !fingerprints
+                    .iter()
+                    .any(|other| util::fingerprints_equal(other, &fp))
+                {
+                    fingerprints.push(fp);
+                }
+            }
+        }
+        Ok(fingerprints)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to read groups file")]
+    Read(#[source] std::io::Error),
+
+    #[error("failed to write groups file")]
+    Write(#[source] std::io::Error),
+
+    #[error("failed to parse groups file")]
+    Parse(#[source] toml::de::Error),
+
+    #[error("failed to serialize groups file")]
+    Serialize(#[source] toml::ser::Error),
+
+    #[error("unknown recipient group: {0}")]
+    UnknownGroup(String),
+
+    #[error("recipient group references another group, groups can't be nested: {0}")]
+    NestedGroup(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "68EA3744E3B9D85433B71104795FD9B5C876523F";
+    const BOB: &str = "830171328A8155DD8EB1353B52E538F1F886AF90";
+
+    #[test]
+    fn expand() {
+        let mut groups = Groups::default();
+        assert!(groups.add_member("ops", &ALICE.to_lowercase()));
+        assert!(!groups.add_member("ops", ALICE));
+        assert!(groups.add_member("ops", BOB));
+
+        // References are expanded and deduplicated, other entries are kept
+        let entries = vec![BOB.into(), " @ops ".into(), format_group_ref("ops")];
+        assert_eq!(groups.expand(&entries).unwrap(), vec![BOB, ALICE]);
+
+        let err = groups.expand(&["@dev".into()]).err().unwrap();
+        assert!(
+            matches!(err.downcast_ref::<Err>(), Some(Err::UnknownGroup(name)) if name == "dev")
+        );
+
+        // Groups can't be nested
+        groups.add_member("all", "@ops");
+        let err = groups.expand(&["@all".into()]).err().unwrap();
+        assert!(matches!(err.downcast_ref::<Err>(), Some(Err::NestedGroup(name)) if name == "all"));
+    }
+
+    #[cfg(feature = "backend-mock")]
+    #[test]
+    fn store_groups() {
+        use crate::crypto::backend::mock::{self, fixture::TempStore};
+        use crate::crypto::{store, Key};
+
+        let alice = mock::add_secret_key("groups-alice");
+        let bob = mock::add_public_key("groups-bob");
+        let carol = mock::add_public_key("groups-carol");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+        let fingerprints = |keys: &[Key]| -> Vec<String> {
+            keys.iter().map(|key| key.fingerprint(false)).collect()
+        };
+
+        // Unknown groups can't be referenced
+        assert!(store::store_add_group_at(&store, &store.root, "ops").is_err());
+
+        let mut groups = Groups::default();
+        groups.add_member("ops", &alice.fingerprint(false));
+        groups.add_member("ops", &bob.fingerprint(false));
+        store::store_save_groups(&store, &groups).unwrap();
+        assert_eq!(Groups::load(&store).unwrap(), groups);
+
+        // Group members become recipients
+        store::store_add_group_at(&store, &store.root, "ops").unwrap();
+        assert_eq!(
+            store::store_read_gpg_ids_at(&store, &store.root).unwrap(),
+            vec![alice.fingerprint(false), "@ops".into()],
+        );
+        assert_eq!(
+            fingerprints(&store::store_load_keys(&store).unwrap()),
+            fingerprints(&[alice.clone(), bob.clone()]),
+        );
+        assert_eq!(
+            store::store_group_dirs(&store, "ops").unwrap(),
+            vec![store.root.clone()],
+        );
+
+        // Reference is kept while all members are recipients
+        let keys = [alice.clone(), bob.clone(), carol.clone()];
+        store::store_save_keys_at(&store, &store.root, &keys).unwrap();
+        assert_eq!(
+            store::store_read_gpg_ids_at(&store, &store.root).unwrap(),
+            vec!["@ops".into(), carol.fingerprint(false)],
+        );
+
+        // Reference is replaced by remaining members if a member is removed
+        let keys = [alice.clone(), carol.clone()];
+        store::store_save_keys_at(&store, &store.root, &keys).unwrap();
+        assert_eq!(
+            store::store_read_gpg_ids_at(&store, &store.root).unwrap(),
+            fingerprints(&keys),
+        );
+
+        // Unknown group references fail loading recipients
+        store::store_write_gpg_fingerprints(&store, &["@dev"]).unwrap();
+        assert!(store::store_load_keys(&store).is_err());
+    }
+}
@@ -2,19 +2,25 @@
 //!
 //! This module provides an interface to all cryptography features that are used in prs.
 //!
-//! It supports multiple cryptography protocols (e.g. GPG) and multiple backends (e.g. GPGME,
-//! GnuPG). The list of supported protocols and backends may be extended in the future.
+//! It supports multiple cryptography protocols (e.g. GPG, age) and multiple backends (e.g. GPGME,
+//! GnuPG, age). The list of supported protocols and backends may be extended in the future.
 
+pub mod armor;
 pub mod backend;
+pub mod groups;
 pub mod proto;
 pub mod recipients;
 pub mod store;
 pub mod util;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
 use thiserror::Error;
@@ -30,6 +36,9 @@ use crate::{Ciphertext, Plaintext, Recipients};
 pub enum Proto {
     /// GPG crypto.
     Gpg,
+
+    /// age crypto.
+    Age,
 }
 
 impl Proto {
@@ -37,6 +46,7 @@ impl Proto {
     pub fn name(&self) -> &str {
         match self {
             Self::Gpg => "GPG",
+            Self::Age => "age",
         }
     }
 }
@@ -51,6 +61,29 @@ pub struct Config {
     /// Use TTY for password input with GPG.
     pub gpg_tty: bool,
 
+    /// Custom GnuPG home directory to use for public keys with GPG.
+    ///
+    /// Uses the global GnuPG keyring if not set. Secret keys are always used from the global
+    /// GnuPG home, through the users agent.
+    pub gpg_home: Option<PathBuf>,
+
+    /// Passphrase to unlock GPG secret keys with.
+    ///
+    /// If set, GPG uses loopback pinentry mode and is given this passphrase instead of asking for
+    /// it through pinentry. Uses pinentry if not set. The passphrase is zeroized when dropped.
+    pub gpg_passphrase: Option<Plaintext>,
+
+    /// Sign secrets when encrypting.
+    ///
+    /// Secrets are signed with the first recipient key we own the secret key for. Only supported
+    /// with GPG, and not with a custom GnuPG home.
+    pub sign: bool,
+
+    /// Socket of the decryption cache agent to use, if it is running.
+    ///
+    /// Only supported on Unix, see `agent`.
+    pub agent: Option<PathBuf>,
+
     /// Whether to show verbose output.
     pub verbose: bool,
 }
@@ -61,6 +94,10 @@ impl Config {
         Self {
             proto,
             gpg_tty: false,
+            gpg_home: None,
+            gpg_passphrase: None,
+            sign: false,
+            agent: None,
             verbose: false,
         }
     }
@@ -75,6 +112,10 @@ pub enum Key {
     /// An GPG key.
     #[cfg(feature = "_crypto-gpg")]
     Gpg(proto::gpg::Key),
+
+    /// An age key.
+    #[cfg(feature = "_crypto-age")]
+    Age(proto::age::Key),
 }
 
 impl Key {
@@ -83,6 +124,8 @@ impl Key {
         match self {
             #[cfg(feature = "_crypto-gpg")]
             Key::Gpg(_) => Proto::Gpg,
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(_) => Proto::Age,
         }
     }
 
@@ -91,6 +134,53 @@ impl Key {
         match self {
             #[cfg(feature = "_crypto-gpg")]
             Key::Gpg(key) => key.fingerprint(short),
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(key) => key.fingerprint(short),
+        }
+    }
+
+    /// Key expiry time, if the key expires.
+    pub fn expiry_time(&self) -> Option<SystemTime> {
+        match self {
+            #[cfg(feature = "_crypto-gpg")]
+            Key::Gpg(key) => key.expiry_time(),
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(_) => None,
+        }
+    }
+
+    /// Check whether the key expires within the given duration from now.
+    pub fn expires_within(&self, #[allow(unused_variables)] duration: Duration) -> bool {
+        match self {
+            #[cfg(feature = "_crypto-gpg")]
+            Key::Gpg(key) => key.expires_within(duration),
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(_) => false,
+        }
+    }
+
+    /// Key validity and capabilities for user, if the protocol has them.
+    pub fn display_status(&self) -> Option<String> {
+        match self {
+            #[cfg(feature = "_crypto-gpg")]
+            Key::Gpg(key) => Some(match key.capabilities.to_string() {
+                capabilities if capabilities.is_empty() => key.validity.to_string(),
+                capabilities => format!("{}, {capabilities}", key.validity),
+            }),
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(_) => None,
+        }
+    }
+
+    /// Check whether the key is usable to encrypt for.
+    ///
+    /// Errors with the reason if it is not, such as an expired or revoked key.
+    pub fn check_usable(&self) -> Result<()> {
+        match self {
+            #[cfg(feature = "_crypto-gpg")]
+            Key::Gpg(key) => key.check_usable().map_err(|err| err.into()),
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(_) => Ok(()),
         }
     }
 
@@ -99,6 +189,8 @@ impl Key {
         match self {
             #[cfg(feature = "_crypto-gpg")]
             Key::Gpg(key) => key.display_user(),
+            #[cfg(feature = "_crypto-age")]
+            Key::Age(key) => key.display_user(),
         }
     }
 }
@@ -117,17 +209,42 @@ impl fmt::Display for Key {
 
 /// Get crypto context for given proto type at runtime.
 ///
-/// This selects a compatible crypto context at runtime.
+/// This selects a compatible crypto context at runtime. Decryption goes through the agent if one
+/// is configured and running.
 ///
 /// # Errors
 ///
 /// Errors if no compatible crypto context is available for the selected protocol because no
 /// backend is providing it. Also errors if creating the context fails.
 pub fn context(config: &Config) -> Result<Context, Err> {
+    let context = backend_context(config)?;
+
+    #[cfg(unix)]
+    if let Some(client) = config
+        .agent
+        .as_ref()
+        .and_then(|socket| crate::agent::Client::connect(socket).ok())
+    {
+        return Ok(Context::from(Box::new(crate::agent::Context::new(
+            context, client,
+        ))));
+    }
+
+    Ok(context)
+}
+
+/// Get crypto context of a backend for given proto type at runtime.
+fn backend_context(config: &Config) -> Result<Context, Err> {
     // Select proper crypto backend
     match config.proto {
         #[allow(unreachable_code)]
         Proto::Gpg => {
+            #[cfg(feature = "backend-mock")]
+            if backend::mock::is_enabled() {
+                return Ok(Context::from(Box::new(
+                    backend::mock::context::context(config).map_err(Err::Context)?,
+                )));
+            }
             #[cfg(feature = "backend-gpgme")]
             return Ok(Context::from(Box::new(
                 backend::gpgme::context::context(config).map_err(|err| Err::Context(err.into()))?,
@@ -137,6 +254,27 @@ pub fn context(config: &Config) -> Result<Context, Err> {
                 backend::gnupg_bin::context::context(config)
                     .map_err(|err| Err::Context(err.into()))?,
             )));
+            #[cfg(feature = "backend-rpgp")]
+            {
+                if config.sign {
+                    return Err(Err::SignUnsupported);
+                }
+                return Ok(Context::from(Box::new(
+                    backend::rpgp::context::context(config).map_err(Err::Context)?,
+                )));
+            }
+        }
+        #[allow(unreachable_code)]
+        Proto::Age => {
+            #[cfg(feature = "backend-age")]
+            {
+                if config.sign {
+                    return Err(Err::SignUnsupported);
+                }
+                return Ok(Context::from(Box::new(
+                    backend::age::context::context(config).map_err(Err::Context)?,
+                )));
+            }
         }
     }
 
@@ -144,6 +282,36 @@ pub fn context(config: &Config) -> Result<Context, Err> {
     Err(Err::Unsupported(config.proto))
 }
 
+/// Find the key to sign a secret for the given recipients with.
+///
+/// This is the first recipient we own the secret key for. Errors if there is none.
+#[cfg(any(
+    feature = "backend-gnupg-bin",
+    feature = "backend-gpgme",
+    feature = "backend-mock"
+))]
+pub(crate) fn find_signing_key(
+    context: &mut impl IsContext,
+    recipients: &Recipients,
+) -> Result<Key> {
+    context
+        .keys_private()?
+        .into_iter()
+        .find(|key| recipients.has_fingerprint(&key.fingerprint(false)))
+        .ok_or_else(|| Err::NoSigningKey.into())
+}
+
+/// Decrypted plaintext, along with the signers of its ciphertext.
+pub struct Verified {
+    /// Decrypted plaintext.
+    pub plaintext: Plaintext,
+
+    /// Primary key fingerprints of all valid signatures, empty if not signed.
+    ///
+    /// Signatures made by keys that are not in the keychain are not valid.
+    pub signers: Vec<String>,
+}
+
 /// Generic context.
 pub struct Context {
     /// Inner context.
@@ -158,6 +326,12 @@ impl Context {
 
 impl IsContext for Context {
     fn encrypt(&mut self, recipients: &Recipients, plaintext: Plaintext) -> Result<Ciphertext> {
+        // Refuse unusable recipients, backends give unclear errors
+        for key in recipients.keys() {
+            key.check_usable()
+                .map_err(|err| Err::UnusableRecipient(key.to_string(), err))?;
+        }
+
         self.context.encrypt(recipients, plaintext)
     }
 
@@ -165,6 +339,10 @@ impl IsContext for Context {
         self.context.decrypt(ciphertext)
     }
 
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified> {
+        self.context.decrypt_verify(ciphertext)
+    }
+
     fn can_decrypt(&mut self, ciphertext: Ciphertext) -> Result<bool> {
         self.context.can_decrypt(ciphertext)
     }
@@ -181,10 +359,30 @@ impl IsContext for Context {
         self.context.import_key(key)
     }
 
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>> {
+        self.context.read_keys(key)
+    }
+
     fn export_key(&mut self, key: Key) -> Result<Vec<u8>> {
         self.context.export_key(key)
     }
 
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>> {
+        self.context.encrypted_key_ids(ciphertext)
+    }
+
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>> {
+        self.context.find_key_by_id(key_id)
+    }
+
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+        self.context.sign_detached(key, data)
+    }
+
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>> {
+        self.context.verify_detached(data, signature)
+    }
+
     fn supports_proto(&self, proto: Proto) -> bool {
         self.context.supports_proto(proto)
     }
@@ -205,8 +403,7 @@ pub trait IsContext {
         plaintext: Plaintext,
         path: &Path,
     ) -> Result<()> {
-        fs::write(path, self.encrypt(recipients, plaintext)?.unsecure_ref())
-            .map_err(|err| Err::WriteFile(err).into())
+        write_ciphertext_file(path, self.encrypt(recipients, plaintext)?)
     }
 
     /// Decrypt ciphertext.
@@ -214,7 +411,17 @@ pub trait IsContext {
 
     /// Decrypt ciphertext from file.
     fn decrypt_file(&mut self, path: &Path) -> Result<Plaintext> {
-        self.decrypt(fs::read(path).map_err(Err::ReadFile)?.into())
+        self.decrypt(read_ciphertext_file(path)?)
+    }
+
+    /// Decrypt ciphertext, and verify its signatures.
+    ///
+    /// Unsigned ciphertext is decrypted without any signers.
+    fn decrypt_verify(&mut self, ciphertext: Ciphertext) -> Result<Verified>;
+
+    /// Decrypt ciphertext from file, and verify its signatures.
+    fn decrypt_verify_file(&mut self, path: &Path) -> Result<Verified> {
+        self.decrypt_verify(read_ciphertext_file(path)?)
     }
 
     /// Check whether we can decrypt ciphertext.
@@ -222,9 +429,23 @@ pub trait IsContext {
 
     /// Check whether we can decrypt ciphertext from file.
     fn can_decrypt_file(&mut self, path: &Path) -> Result<bool> {
-        self.can_decrypt(fs::read(path).map_err(Err::ReadFile)?.into())
+        self.can_decrypt(read_ciphertext_file(path)?)
+    }
+
+    /// Get the IDs of the keys a ciphertext is encrypted for, without decrypting it.
+    ///
+    /// These are the IDs of the (sub)keys listed in the ciphertext, which may not be in the
+    /// keychain. See `find_key_by_id` to find the matching keys.
+    fn encrypted_key_ids(&mut self, ciphertext: Ciphertext) -> Result<Vec<String>>;
+
+    /// Get the IDs of the keys the ciphertext in a file is encrypted for.
+    fn encrypted_key_ids_file(&mut self, path: &Path) -> Result<Vec<String>> {
+        self.encrypted_key_ids(read_ciphertext_file(path)?)
     }
 
+    /// Find the public key in the keychain having a (sub)key with the given key ID.
+    fn find_key_by_id(&mut self, key_id: &str) -> Result<Option<Key>>;
+
     /// Obtain all public keys from keychain.
     fn keys_public(&mut self) -> Result<Vec<Key>>;
 
@@ -258,8 +479,20 @@ pub trait IsContext {
     fn import_key(&mut self, key: &[u8]) -> Result<()>;
 
     /// Import the given key from a file into keychain.
+    ///
+    /// See `read_key_file` for supported formats.
     fn import_key_file(&mut self, path: &Path) -> Result<()> {
-        self.import_key(&fs::read(path).map_err(Err::ReadFile)?)
+        self.import_key(&read_key_file(path)?)
+    }
+
+    /// Read the public keys from bytes, without importing them into the keychain.
+    fn read_keys(&mut self, key: &[u8]) -> Result<Vec<Key>>;
+
+    /// Read the public keys from a file, without importing them into the keychain.
+    ///
+    /// See `read_key_file` for supported formats.
+    fn read_keys_file(&mut self, path: &Path) -> Result<Vec<Key>> {
+        self.read_keys(&read_key_file(path)?)
     }
 
     /// Export the given key from the keychain as bytes.
@@ -270,10 +503,113 @@ pub trait IsContext {
         fs::write(path, self.export_key(key)?).map_err(|err| Err::WriteFile(err).into())
     }
 
+    /// Create a detached signature over data with the given secret key.
+    fn sign_detached(&mut self, key: &Key, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Verify a detached signature over data.
+    ///
+    /// Returns the primary key fingerprints of all valid signatures. Signatures made by keys that
+    /// are not in the keychain are not valid.
+    fn verify_detached(&mut self, data: &[u8], signature: &[u8]) -> Result<Vec<String>>;
+
     /// Check whether this context supports the given protocol.
     fn supports_proto(&self, proto: Proto) -> bool;
 }
 
+/// Read ciphertext from a file.
+///
+/// ASCII armored ciphertext is dearmored, so backends always get the binary form.
+pub fn read_ciphertext_file(path: &Path) -> Result<Ciphertext> {
+    read_ciphertext(fs::read(path).map_err(Err::ReadFile)?)
+}
+
+/// Read ciphertext from raw secret file data.
+///
+/// ASCII armored ciphertext is dearmored, so backends always get the binary form.
+pub fn read_ciphertext(data: Vec<u8>) -> Result<Ciphertext> {
+    let ciphertext: Ciphertext = data.into();
+    if !armor::is_armored(armor::Block::Message, ciphertext.unsecure_ref()) {
+        return Ok(ciphertext);
+    }
+    Ok(
+        armor::dearmor(armor::Block::Message, ciphertext.unsecure_ref())
+            .map_err(Err::Armor)?
+            .into(),
+    )
+}
+
+/// Read public keys from a file.
+///
+/// Binary OpenPGP public keys are ASCII armored, as backends expect armored keys. Other keys are
+/// returned as-is. Errors if the file holds a secret key or binary data that is not an OpenPGP
+/// public key.
+pub fn read_key_file(path: &Path) -> Result<Vec<u8>> {
+    let data = fs::read(path).map_err(Err::ReadFile)?;
+    #[cfg(feature = "_crypto-gpg")]
+    if proto::gpg::is_binary_public_key(&data) {
+        return Ok(armor::armor(armor::Block::PublicKey, &data));
+    }
+
+    let text = std::str::from_utf8(&data).map_err(|_| Err::NotPublicKey)?;
+    if text.contains("PRIVATE KEY") || text.contains("SECRET-KEY") {
+        return Err(Err::SecretKey.into());
+    }
+    Ok(data)
+}
+
+/// Read public keys from a file, split into separate key data for each key.
+///
+/// This allows importing only some of the keys in a file. OpenPGP keys are returned ASCII
+/// armored, age recipients are returned as recipients file. Other data is returned as-is as a
+/// single entry. See `read_key_file` for supported formats.
+pub fn read_key_file_split(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let data = read_key_file(path)?;
+
+    #[cfg(feature = "_crypto-gpg")]
+    if armor::is_armored(armor::Block::PublicKey, &data) {
+        let binary = armor::dearmor(armor::Block::PublicKey, &data).map_err(Err::Armor)?;
+        return Ok(proto::gpg::split_public_keys(&binary)?
+            .into_iter()
+            .map(|key| armor::armor(armor::Block::PublicKey, key))
+            .collect());
+    }
+
+    #[cfg(feature = "_crypto-age")]
+    if let Some(keys) = std::str::from_utf8(&data)
+        .ok()
+        .and_then(|data| proto::age::parse_recipients(data).ok())
+        .filter(|keys| !keys.is_empty())
+    {
+        return Ok(keys
+            .into_iter()
+            .map(|key| proto::age::format_recipients(&[key]).into_bytes())
+            .collect());
+    }
+
+    Ok(vec![data])
+}
+
+/// Write ciphertext to a file.
+///
+/// The ciphertext is ASCII armored if the file has the armored secret suffix, see
+/// `store::SECRET_SUFFIX_ARMOR`.
+pub fn write_ciphertext_file(path: &Path, ciphertext: Ciphertext) -> Result<()> {
+    let armored = path
+        .to_str()
+        .map(|path| path.ends_with(crate::store::SECRET_SUFFIX_ARMOR))
+        .unwrap_or(false);
+    let result = if armored && !armor::is_armored(armor::Block::Message, ciphertext.unsecure_ref())
+    {
+        fs::write(
+            path,
+            armor::armor(armor::Block::Message, ciphertext.unsecure_ref()),
+        )
+    } else {
+        fs::write(path, ciphertext.unsecure_ref())
+    };
+    result.map_err(|err| Err::WriteFile(err).into())
+}
+
 /// A pool of proto contexts.
 ///
 /// Makes using multiple contexts easy, by caching contexts by protocol type and initializing them
@@ -301,12 +637,92 @@ impl ContextPool {
             .entry(config.proto)
             .or_insert(context(config)?))
     }
+
+    /// Run a job for each item in parallel, on the given number of worker threads.
+    ///
+    /// Each worker thread uses its own context pool, contexts are never shared between threads.
+    /// Items are picked up in order, and results are passed to `handle` on the calling thread in
+    /// the same order as `items`. Processing stops early if `handle` returns `false`, jobs that
+    /// are still running are completed but their results are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a context for a worker thread could not be created.
+    pub fn parallel<T, R, J, H>(
+        config: &Config,
+        threads: usize,
+        items: &[T],
+        job: J,
+        mut handle: H,
+    ) -> Result<()>
+    where
+        T: Sync,
+        R: Send,
+        J: Fn(&mut Context, &T) -> R + Sync,
+        H: FnMut(&T, R) -> bool,
+    {
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let threads = threads.clamp(1, items.len().max(1));
+
+        thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel();
+            for _ in 0..threads {
+                let (tx, next, stop, job) = (tx.clone(), &next, &stop, &job);
+                scope.spawn(move || {
+                    let mut pool = ContextPool::empty();
+                    let context = match pool.get_mut(config) {
+                        Ok(context) => context,
+                        Err(err) => {
+                            let _ = tx.send(Err(err));
+                            return;
+                        }
+                    };
+
+                    while !stop.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= items.len() {
+                            break;
+                        }
+                        if tx.send(Ok((index, job(context, &items[index])))).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            // Handle results in order, buffer results that complete early
+            let mut results = BTreeMap::new();
+            let mut current = 0;
+            for result in rx {
+                let (index, result) = match result {
+                    Ok(result) => result,
+                    Err(err) => {
+                        stop.store(true, Ordering::Relaxed);
+                        return Err(err);
+                    }
+                };
+                results.insert(index, result);
+
+                while let Some(result) = results.remove(&current) {
+                    if !handle(&items[current], result) {
+                        stop.store(true, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                    current += 1;
+                }
+            }
+
+            Ok(())
+        })
+    }
 }
 
 /// Crypto error.
 #[derive(Debug, Error)]
 pub enum Err {
-    #[error("failed to obtain GPG cryptography context")]
+    #[error("failed to obtain cryptography context")]
     Context(#[source] anyhow::Error),
 
     #[error("failed to built context, protocol not supportd: {:?}", _0)]
@@ -318,8 +734,26 @@ pub enum Err {
     #[error("failed to read from file")]
     ReadFile(#[source] std::io::Error),
 
+    #[error("file does not contain a public key")]
+    NotPublicKey,
+
+    #[error("refusing to read secret key, only public keys are supported")]
+    SecretKey,
+
+    #[error("failed to read ASCII armored ciphertext")]
+    Armor(#[source] armor::Err),
+
     #[error("fingerprint does not match public key in keychain")]
     UnknownFingerprint,
+
+    #[error("cannot encrypt for recipient: {0}")]
+    UnusableRecipient(String, #[source] anyhow::Error),
+
+    #[error("signing secrets is not supported by the cryptography backend")]
+    SignUnsupported,
+
+    #[error("cannot sign secret, no secret key available for any of its recipients")]
+    NoSigningKey,
 }
 
 /// Prelude for common crypto traits.
@@ -0,0 +1,177 @@
+//! Crypto age protocol.
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use thiserror::Error;
+
+/// Represents an age recipient key.
+///
+/// This may be a native X25519 recipient (`age1...`) or an SSH public key recipient.
+#[derive(Clone)]
+pub struct Key {
+    /// Recipient string, without any comment.
+    pub recipient: String,
+
+    /// Optional comment describing the recipient.
+    pub comment: Option<String>,
+}
+
+impl Key {
+    /// Parse a recipient from the given string.
+    ///
+    /// Supports native X25519 recipients and `ssh-ed25519`/`ssh-rsa` public keys. A trailing SSH
+    /// key comment is used as comment.
+    pub fn parse(recipient: &str) -> Result<Self> {
+        let recipient = recipient.trim();
+
+        if let Ok(parsed) = age::x25519::Recipient::from_str(recipient) {
+            return Ok(Self {
+                recipient: parsed.to_string(),
+                comment: None,
+            });
+        }
+
+        match age::ssh::Recipient::from_str(recipient) {
+            Ok(parsed) => Ok(Self {
+                recipient: parsed.to_string(),
+                comment: recipient
+                    .splitn(3, char::is_whitespace)
+                    .nth(2)
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty()),
+            }),
+            Err(_) => Err(Err::Parse(recipient.into()).into()),
+        }
+    }
+
+    /// Key fingerprint.
+    ///
+    /// For age this is the recipient string itself.
+    pub fn fingerprint(&self, short: bool) -> String {
+        if short {
+            &self.recipient[self.recipient.len().saturating_sub(16)..]
+        } else {
+            &self.recipient
+        }
+        .trim()
+        .to_string()
+    }
+
+    /// Key displayable user data.
+    pub fn display_user(&self) -> String {
+        match &self.comment {
+            Some(comment) => comment.clone(),
+            None if self.is_ssh() => "SSH key".into(),
+            None => "X25519 key".into(),
+        }
+    }
+
+    /// Check whether this is an SSH recipient.
+    pub fn is_ssh(&self) -> bool {
+        self.recipient.starts_with("ssh-")
+    }
+
+    /// Transform into generic key.
+    pub fn into_key(self) -> crate::crypto::Key {
+        crate::crypto::Key::Age(self)
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.recipient.trim() == other.recipient.trim()
+    }
+}
+
+/// Parse recipient keys from an age recipients file.
+///
+/// Empty lines are ignored. A comment line directly above a recipient is used as its comment.
+pub fn parse_recipients(data: &str) -> Result<Vec<Key>> {
+    let mut keys = Vec::new();
+    let mut comment = None;
+
+    for line in data.lines().map(|line| line.trim()) {
+        if line.is_empty() {
+            comment = None;
+        } else if let Some(c) = line.strip_prefix('#') {
+            comment = Some(c.trim().to_string()).filter(|c| !c.is_empty());
+        } else {
+            let mut key = Key::parse(line)?;
+            if let Some(comment) = comment.take() {
+                key.comment = Some(comment);
+            }
+            keys.push(key);
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Format recipient keys as an age recipients file.
+///
+/// Comments are written on the line above each recipient.
+pub fn format_recipients(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|key| match &key.comment {
+            Some(comment) => format!("# {}\n{}\n", comment, key.recipient),
+            None => format!("{}\n", key.recipient),
+        })
+        .collect()
+}
+
+/// Age protocol error.
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to parse age recipient, not an X25519 or SSH key: {0}")]
+    Parse(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SSH_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIN/9kNXLHnl05WOG8I8kzocYOjYCZvKSAhBCDgrLDUZc";
+
+    #[test]
+    fn recipients_file() {
+        let x25519 = age::x25519::Identity::generate().to_public().to_string();
+        let data = format!(
+            "# Alice\n{x25519}\n\n# stale comment\n\n{SSH_KEY} alice@laptop\n#\n  {SSH_KEY}  \n",
+        );
+
+        let keys = parse_recipients(&data).unwrap();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0].recipient, x25519);
+        assert_eq!(keys[0].comment.as_deref(), Some("Alice"));
+        assert!(!keys[0].is_ssh());
+
+        // Comment separated by empty line is dropped, SSH key comment is used instead
+        assert_eq!(keys[1].recipient, SSH_KEY);
+        assert_eq!(keys[1].comment.as_deref(), Some("alice@laptop"));
+        assert!(keys[1].is_ssh());
+
+        // Empty comment is ignored, surrounding whitespace is trimmed
+        assert_eq!(keys[2].recipient, SSH_KEY);
+        assert_eq!(keys[2].comment, None);
+        assert_eq!(keys[2].display_user(), "SSH key");
+
+        // Formatting and parsing again is lossless
+        let formatted = format_recipients(&keys);
+        let reparsed = parse_recipients(&formatted).unwrap();
+        assert!(keys == reparsed);
+        assert!(reparsed
+            .iter()
+            .zip(&keys)
+            .all(|(a, b)| a.comment == b.comment));
+    }
+
+    #[test]
+    fn recipients_file_invalid() {
+        assert!(parse_recipients("").unwrap().is_empty());
+        assert!(parse_recipients("# only a comment\n").unwrap().is_empty());
+        assert!(parse_recipients("age1notarecipient\n").is_err());
+        assert!(parse_recipients("ssh-dss AAAAB3NzaC1kc3MAAACBAP\n").is_err());
+    }
+}
@@ -1,5 +1,11 @@
 //! Crypto GPG protocol.
 
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use thiserror::Error;
+
 /// Represents a GPG key.
 #[derive(Clone)]
 pub struct Key {
@@ -8,6 +14,15 @@ pub struct Key {
 
     /// Displayable user ID strings.
     pub user_ids: Vec<String>,
+
+    /// Key validity.
+    pub validity: Validity,
+
+    /// Expiry time as UNIX timestamp, if the key expires.
+    pub expires: Option<u64>,
+
+    /// Usable capabilities of the key and its subkeys.
+    pub capabilities: Capabilities,
 }
 
 impl Key {
@@ -31,6 +46,122 @@ impl Key {
     pub fn into_key(self) -> crate::crypto::Key {
         crate::crypto::Key::Gpg(self)
     }
+
+    /// Key expiry time, if the key expires.
+    pub fn expiry_time(&self) -> Option<SystemTime> {
+        self.expires
+            .map(|expires| UNIX_EPOCH + Duration::from_secs(expires))
+    }
+
+    /// Check whether the key expires within the given duration from now.
+    ///
+    /// Keys that have already expired are included.
+    pub fn expires_within(&self, duration: Duration) -> bool {
+        self.expiry_time()
+            .map(|expires| expires <= SystemTime::now() + duration)
+            .unwrap_or(false)
+    }
+
+    /// Check whether the key is usable to encrypt for.
+    ///
+    /// Errors with the reason if the key is not valid, has expired or cannot encrypt.
+    pub fn check_usable(&self) -> Result<(), Err> {
+        match self.validity {
+            Validity::Valid => {}
+            Validity::Expired => return Err(Err::Expired),
+            Validity::Revoked => return Err(Err::Revoked),
+            Validity::Disabled => return Err(Err::Disabled),
+            Validity::Invalid => return Err(Err::Invalid),
+        }
+        if self.expires_within(Duration::ZERO) {
+            return Err(Err::Expired);
+        }
+        if !self.capabilities.encrypt {
+            return Err(Err::CannotEncrypt);
+        }
+        Ok(())
+    }
+}
+
+/// GPG key validity.
+///
+/// This does not include trust, keys are always trusted by prs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Validity {
+    /// Key is valid.
+    Valid,
+
+    /// Key has expired.
+    Expired,
+
+    /// Key has been revoked.
+    Revoked,
+
+    /// Key has been disabled in the keychain.
+    Disabled,
+
+    /// Key is invalid, for example because of a missing self-signature.
+    Invalid,
+}
+
+impl fmt::Display for Validity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Valid => "valid",
+            Self::Expired => "expired",
+            Self::Revoked => "revoked",
+            Self::Disabled => "disabled",
+            Self::Invalid => "invalid",
+        })
+    }
+}
+
+/// GPG key capabilities.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Key can encrypt.
+    pub encrypt: bool,
+
+    /// Key can sign.
+    pub sign: bool,
+
+    /// Key can certify other keys.
+    pub certify: bool,
+
+    /// Key can authenticate.
+    pub authenticate: bool,
+}
+
+impl Capabilities {
+    /// Parse capabilities from GnuPG capability letters, such as `escaESCA`.
+    ///
+    /// Only uppercase letters are used, these represent the usable capabilities of the key
+    /// including its subkeys.
+    pub fn from_letters(letters: &str) -> Self {
+        Self {
+            encrypt: letters.contains('E'),
+            sign: letters.contains('S'),
+            certify: letters.contains('C'),
+            authenticate: letters.contains('A'),
+        }
+    }
+}
+
+impl fmt::Display for Capabilities {
+    /// Format as GnuPG capability letters, such as `ESC`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (capable, letter) in [
+            (self.encrypt, 'E'),
+            (self.sign, 'S'),
+            (self.certify, 'C'),
+            (self.authenticate, 'A'),
+        ] {
+            if capable {
+                write!(f, "{letter}")?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl PartialEq for Key {
@@ -38,3 +169,324 @@ impl PartialEq for Key {
         self.fingerprint.trim().to_uppercase() == other.fingerprint.trim().to_uppercase()
     }
 }
+
+/// Get the key IDs of all public key encrypted session key packets in an OpenPGP message.
+///
+/// This only walks the packet headers at the start of a binary message, and does not decrypt
+/// anything. Key IDs are formatted as uppercase hexadecimal. Anonymous recipients have an all zero
+/// key ID.
+pub fn pkesk_key_ids(message: &[u8]) -> Result<Vec<String>> {
+    let mut ids = vec![];
+    let mut data = message;
+
+    while let Some((packet, rest)) = next_packet(data)? {
+        match packet.tag {
+            // Public key encrypted session key
+            1 => ids.push(pkesk_key_id(packet.body)?),
+            // Symmetric key encrypted session key and marker packets may come before data
+            3 | 10 => {}
+            // Encrypted data packet follows session keys, we're done
+            _ => break,
+        }
+        data = rest;
+    }
+
+    Ok(ids)
+}
+
+/// Check whether the given data is a binary OpenPGP public key.
+///
+/// This only checks whether the first packet is a public key packet.
+pub fn is_binary_public_key(data: &[u8]) -> bool {
+    matches!(next_packet(data), Ok(Some((Packet { tag: 6, .. }, _))))
+}
+
+/// Split binary OpenPGP public key data into the keys it holds.
+///
+/// Each key starts at a public key packet, and holds all packets up to the next one. Errors if
+/// the data does not start with a public key packet, or if it is malformed.
+pub fn split_public_keys(data: &[u8]) -> Result<Vec<&[u8]>> {
+    let mut keys = vec![];
+    let mut start = 0;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let offset = data.len() - rest.len();
+        let (packet, next) = next_packet(rest)?.ok_or(Err::Parse)?;
+        match packet.tag {
+            6 if offset > 0 => {
+                keys.push(&data[start..offset]);
+                start = offset;
+            }
+            6 => {}
+            _ if offset == 0 => return Err(Err::Parse.into()),
+            _ => {}
+        }
+        rest = next;
+    }
+    if !data.is_empty() {
+        keys.push(&data[start..]);
+    }
+    Ok(keys)
+}
+
+/// An OpenPGP packet.
+struct Packet<'a> {
+    /// Packet tag.
+    tag: u8,
+
+    /// Packet body.
+    body: &'a [u8],
+}
+
+/// Parse the next OpenPGP packet header.
+///
+/// Returns the packet and the remaining data. Returns `None` at the end of the message or at a
+/// packet with indeterminate or partial length.
+fn next_packet(data: &[u8]) -> Result<Option<(Packet<'_>, &[u8])>> {
+    let header = match data.first() {
+        Some(header) => *header,
+        None => return Ok(None),
+    };
+    if header & 0x80 == 0 {
+        return Err(Err::Parse.into());
+    }
+
+    let (tag, len, offset) = if header & 0x40 != 0 {
+        // New packet format
+        let tag = header & 0x3f;
+        match *data.get(1).ok_or(Err::Parse)? {
+            o if o < 192 => (tag, o as usize, 2),
+            o if o < 224 => {
+                let o2 = *data.get(2).ok_or(Err::Parse)? as usize;
+                (tag, ((o as usize - 192) << 8) + o2 + 192, 3)
+            }
+            255 => (tag, be_len(data.get(2..6).ok_or(Err::Parse)?), 6),
+            _ => return Ok(None),
+        }
+    } else {
+        // Old packet format
+        let tag = (header >> 2) & 0x0f;
+        match header & 0x03 {
+            0 => (tag, *data.get(1).ok_or(Err::Parse)? as usize, 2),
+            1 => (tag, be_len(data.get(1..3).ok_or(Err::Parse)?), 3),
+            2 => (tag, be_len(data.get(1..5).ok_or(Err::Parse)?), 5),
+            _ => return Ok(None),
+        }
+    };
+
+    let end = len.checked_add(offset).ok_or(Err::Parse)?;
+    let body = data.get(offset..end).ok_or(Err::Parse)?;
+    Ok(Some((Packet { tag, body }, &data[end..])))
+}
+
+/// Parse the key ID from a public key encrypted session key packet body.
+fn pkesk_key_id(body: &[u8]) -> Result<String> {
+    let id = match body.first() {
+        // Version 3 holds the key ID
+        Some(3) => body.get(1..9).ok_or(Err::Parse)?,
+        // Version 6 holds the key version and fingerprint, derive the key ID from it
+        Some(6) => match *body.get(1).ok_or(Err::Parse)? as usize {
+            0 => &[0; 8],
+            len => {
+                let fingerprint = body.get(3..2 + len).ok_or(Err::Parse)?;
+                match body[2] {
+                    4 => &fingerprint[fingerprint.len().saturating_sub(8)..],
+                    _ => fingerprint.get(..8).ok_or(Err::Parse)?,
+                }
+            }
+        },
+        _ => return Err(Err::Parse.into()),
+    };
+    Ok(id.iter().map(|b| format!("{b:02X}")).collect())
+}
+
+/// Read a big endian packet length.
+fn be_len(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |len, b| (len << 8) | *b as usize)
+}
+
+/// GPG protocol error.
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("failed to parse OpenPGP message, malformed or not binary")]
+    Parse,
+
+    #[error("key has expired")]
+    Expired,
+
+    #[error("key has been revoked")]
+    Revoked,
+
+    #[error("key has been disabled")]
+    Disabled,
+
+    #[error("key is invalid")]
+    Invalid,
+
+    #[error("key cannot be used for encryption")]
+    CannotEncrypt,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Version 3 public key encrypted session key packet body for the given key ID.
+    fn pkesk_v3(id: [u8; 8]) -> Vec<u8> {
+        let mut body = vec![3];
+        body.extend_from_slice(&id);
+        body.extend_from_slice(&[18, 0xaa, 0xbb]);
+        body
+    }
+
+    /// Version 6 public key encrypted session key packet body for the given key fingerprint.
+    fn pkesk_v6(version: u8, fingerprint: &[u8]) -> Vec<u8> {
+        let mut body = vec![6, fingerprint.len() as u8 + 1, version];
+        body.extend_from_slice(fingerprint);
+        body.extend_from_slice(&[18, 0xaa, 0xbb]);
+        body
+    }
+
+    /// New format packet with a one octet length.
+    fn packet(tag: u8, body: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0xc0 | tag, body.len() as u8];
+        packet.extend_from_slice(body);
+        packet
+    }
+
+    #[test]
+    fn packet_old_format() {
+        let body = pkesk_v3([1; 8]);
+
+        // One, two and four octet lengths
+        let mut message = vec![0x84, body.len() as u8];
+        message.extend_from_slice(&body);
+        message.extend_from_slice(&[0x85, 0, body.len() as u8]);
+        message.extend_from_slice(&body);
+        message.extend_from_slice(&[0x86, 0, 0, 0, body.len() as u8]);
+        message.extend_from_slice(&body);
+
+        let (packet, rest) = next_packet(&message).unwrap().unwrap();
+        assert_eq!(packet.tag, 1);
+        assert_eq!(packet.body, body.as_slice());
+        assert_eq!(rest.len(), message.len() - 2 - body.len());
+        assert_eq!(
+            pkesk_key_ids(&message).unwrap(),
+            vec!["0101010101010101"; 3]
+        );
+
+        // Indeterminate length
+        assert!(next_packet(&[0x87, 3]).unwrap().is_none());
+    }
+
+    #[test]
+    fn packet_new_format() {
+        let body = pkesk_v3([2; 8]);
+
+        // One, two and five octet lengths
+        let mut message = packet(1, &body);
+        message.extend_from_slice(&[0xca, 192, 200 - 192]);
+        message.extend_from_slice(&[0; 200]);
+        message.extend_from_slice(&[0xc1, 255, 0, 0, 0, body.len() as u8]);
+        message.extend_from_slice(&body);
+
+        let ids = pkesk_key_ids(&message).unwrap();
+        assert_eq!(ids, vec!["0202020202020202"; 2]);
+
+        // Partial length
+        assert!(next_packet(&[0xc1, 224, 3]).unwrap().is_none());
+        assert!(next_packet(&[0xc1, 254, 3]).unwrap().is_none());
+    }
+
+    #[test]
+    fn pkesk_versions() {
+        let v4_fingerprint: Vec<u8> = (0..20).collect();
+        let v6_fingerprint: Vec<u8> = (0..32).collect();
+
+        let mut message = packet(1, &pkesk_v3([0xab; 8]));
+        message.extend(packet(1, &pkesk_v6(4, &v4_fingerprint)));
+        message.extend(packet(1, &pkesk_v6(6, &v6_fingerprint)));
+        message.extend(packet(1, &[6, 0, 18, 0xaa]));
+        message.extend(packet(10, b"PGP"));
+        message.extend(packet(3, &[4, 9, 2]));
+        message.extend(packet(18, &[1, 2, 3]));
+        message.extend(packet(1, &pkesk_v3([0xff; 8])));
+
+        assert_eq!(
+            pkesk_key_ids(&message).unwrap(),
+            vec![
+                "ABABABABABABABAB",
+                "0C0D0E0F10111213",
+                "0001020304050607",
+                "0000000000000000",
+            ],
+        );
+    }
+
+    #[test]
+    fn packet_truncated() {
+        let body = pkesk_v3([3; 8]);
+        let message = packet(1, &body);
+        for len in 1..message.len() {
+            assert!(
+                pkesk_key_ids(&message[..len]).is_err(),
+                "truncated at {} must error",
+                len,
+            );
+        }
+
+        // Truncated length octets
+        for message in [
+            &[0xc1, 192][..],
+            &[0xc1, 255, 0, 0],
+            &[0x85, 0],
+            &[0x86, 0, 0, 0],
+        ] {
+            assert!(next_packet(message).is_err());
+        }
+
+        // Length overflowing the message
+        assert!(next_packet(&[0xc1, 255, 0xff, 0xff, 0xff, 0xff, 1]).is_err());
+
+        // Truncated or unknown session key packet bodies
+        assert!(pkesk_key_id(&[]).is_err());
+        assert!(pkesk_key_id(&[3, 1, 2]).is_err());
+        assert!(pkesk_key_id(&[6]).is_err());
+        assert!(pkesk_key_id(&[6, 21, 4, 1, 2]).is_err());
+        assert!(pkesk_key_id(&[6, 33, 6, 1, 2]).is_err());
+        assert!(pkesk_key_id(&[5, 1, 2, 3, 4, 5, 6, 7, 8]).is_err());
+
+        // Not a packet header
+        assert!(pkesk_key_ids(b"-----BEGIN PGP MESSAGE-----").is_err());
+        assert!(pkesk_key_ids(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn split_keys() {
+        let mut data = packet(6, &[4, 1]);
+        data.extend(packet(13, b"alice"));
+        data.extend(packet(2, &[4, 0x13]));
+        data.extend(packet(14, &[4, 2]));
+        let bob = [&[0x99, 0, 2, 4, 3][..], &packet(13, b"bob")].concat();
+        data.extend_from_slice(&bob);
+
+        let keys = split_public_keys(&data).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0], &data[..data.len() - bob.len()]);
+        assert_eq!(keys[1], bob.as_slice());
+
+        assert!(split_public_keys(&[]).unwrap().is_empty());
+        assert!(split_public_keys(&packet(13, b"alice")).is_err());
+        assert!(split_public_keys(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn binary_public_key() {
+        assert!(is_binary_public_key(&[0x99, 0, 1, 4]));
+        assert!(is_binary_public_key(&packet(6, &[4])));
+        assert!(!is_binary_public_key(&packet(1, &pkesk_v3([0; 8]))));
+        assert!(!is_binary_public_key(
+            b"-----BEGIN PGP PUBLIC KEY BLOCK-----"
+        ));
+    }
+}
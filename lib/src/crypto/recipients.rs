@@ -63,12 +63,27 @@ impl Recipients {
             .iter()
             .any(|k| util::fingerprints_equal(k.fingerprint(false), fingerprint))
     }
+
+    /// Check whether this recipient list has the same keys as the other list.
+    ///
+    /// The order of keys is ignored.
+    pub fn has_same_keys(&self, other: &Recipients) -> bool {
+        self.keys.len() == other.keys.len()
+            && self
+                .keys
+                .iter()
+                .all(|k| other.has_fingerprint(&k.fingerprint(false)))
+    }
 }
 
 /// Check whether the given recipients contain any key that we have a secret key in our keychain
 /// for.
 pub fn contains_own_secret_key(recipients: &Recipients) -> Result<bool> {
-    let secrets = Recipients::from(crypto::context(&crate::CONFIG)?.keys_private()?);
+    let config = match recipients.keys().first() {
+        Some(key) => crypto::Config::from(key.proto()),
+        None => return Ok(false),
+    };
+    let secrets = Recipients::from(crypto::context(&config)?.keys_private()?);
     Ok(recipients
         .keys()
         .iter()
@@ -2,32 +2,238 @@
 
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
+use chrono::NaiveDate;
 use thiserror::Error;
+use walkdir::WalkDir;
 
-use super::{prelude::*, recipients::Recipients, util, Config, ContextPool, Key, Proto};
+use super::{
+    groups::{self, Groups},
+    prelude::*,
+    recipients::Recipients,
+    util, Config, ContextPool, Key, Proto,
+};
 use crate::Store;
 
 /// Password store GPG IDs file.
 const STORE_GPG_IDS_FILE: &str = ".gpg-id";
 
+/// Password store age recipients file.
+const STORE_AGE_RECIPIENTS_FILE: &str = ".age-recipients";
+
 /// Password store public key directory.
 const STORE_PUB_KEY_DIR: &str = ".public-keys/";
 
+/// Password store recipient expiry file, lives next to the recipients file it applies to.
+const STORE_EXPIRY_FILE: &str = ".recipients-expiry";
+
+/// Date format used in the recipient expiry file.
+const EXPIRY_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Password store directory with pending recipient requests.
+const STORE_REQUESTS_DIR: &str = ".requests/";
+
+/// Suffix for detached signature files.
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// Environment variable listing fingerprints of keys that sign GPG IDs and public key files.
+///
+/// Multiple fingerprints are separated by whitespace, like `pass` does.
+pub const ENV_SIGNING_KEYS: &str = "PASSWORD_STORE_SIGNING_KEY";
+
+/// Get the crypto protocol used by a store.
+///
+/// A store uses age if it has an age recipients file in its root, and GPG otherwise.
+pub fn store_proto(store: &Store) -> Proto {
+    if store.root.join(STORE_AGE_RECIPIENTS_FILE).is_file() {
+        Proto::Age
+    } else {
+        Proto::Gpg
+    }
+}
+
+/// Get the name of the recipients file for the given protocol.
+fn recipients_file_name(proto: Proto) -> &'static str {
+    match proto {
+        Proto::Gpg => STORE_GPG_IDS_FILE,
+        Proto::Age => STORE_AGE_RECIPIENTS_FILE,
+    }
+}
+
 /// Get the GPG IDs file for a store.
 pub fn store_gpg_ids_file(store: &Store) -> PathBuf {
     store.root.join(STORE_GPG_IDS_FILE)
 }
 
+/// Get the age recipients file for a store.
+pub fn store_age_recipients_file(store: &Store) -> PathBuf {
+    store.root.join(STORE_AGE_RECIPIENTS_FILE)
+}
+
+/// Get the GPG IDs file that applies to the given path in a store.
+///
+/// Like `pass`, this walks up from the given secret or directory path to the store root, and
+/// returns the nearest GPG IDs file. Falls back to the root GPG IDs file, which may not exist.
+pub fn store_gpg_ids_file_for(store: &Store, path: &Path) -> PathBuf {
+    nearest_file(store, path, STORE_GPG_IDS_FILE)
+}
+
+/// Get the recipients file that applies to the given path in a store.
+///
+/// This is the GPG IDs or age recipients file, depending on the store protocol. See
+/// `store_gpg_ids_file_for` on how the file is selected.
+pub fn store_recipients_file_for(store: &Store, path: &Path) -> PathBuf {
+    nearest_file(store, path, recipients_file_name(store_proto(store)))
+}
+
+/// Get all GPG IDs files in a store.
+///
+/// This includes the root GPG IDs file and the ones in any sub directory. Hidden directories are
+/// skipped.
+pub fn store_gpg_ids_files(store: &Store) -> Vec<PathBuf> {
+    find_files(store, STORE_GPG_IDS_FILE)
+}
+
+/// Get all age recipients files in a store.
+///
+/// This includes the root age recipients file and the ones in any sub directory. Hidden
+/// directories are skipped.
+pub fn store_age_recipients_files(store: &Store) -> Vec<PathBuf> {
+    find_files(store, STORE_AGE_RECIPIENTS_FILE)
+}
+
+/// Get all recipients files in a store, for the protocol the store uses.
+///
+/// This includes the root recipients file and the ones in any sub directory. Hidden directories
+/// are skipped.
+pub fn store_recipients_files(store: &Store) -> Vec<PathBuf> {
+    find_files(store, recipients_file_name(store_proto(store)))
+}
+
+/// Find the nearest file with the given name, walking up from the given path to the store root.
+///
+/// Falls back to the file in the store root, which may not exist.
+fn nearest_file(store: &Store, path: &Path, name: &str) -> PathBuf {
+    let path = store.root.join(path);
+    let dir = if path.is_dir() {
+        path.as_path()
+    } else {
+        path.parent().unwrap_or(&store.root)
+    };
+
+    dir.ancestors()
+        .take_while(|dir| dir.starts_with(&store.root))
+        .map(|dir| dir.join(name))
+        .find(|file| file.is_file())
+        .unwrap_or_else(|| store.root.join(name))
+}
+
+/// Find all files with the given name in a store, skipping hidden directories.
+fn find_files(store: &Store, name: &str) -> Vec<PathBuf> {
+    WalkDir::new(&store.root)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !e.file_name().to_string_lossy().starts_with('.')
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name() == name)
+        .map(|e| e.into_path())
+        .collect()
+}
+
 /// Get the public keys directory for a store.
 pub fn store_public_keys_dir(store: &Store) -> PathBuf {
     store.root.join(STORE_PUB_KEY_DIR)
 }
 
+/// Get the store-local GnuPG home directory for a store.
+///
+/// Uses `$XDG_DATA_HOME/prs/<store-id>`, and falls back to `~/.local/share/prs/<store-id>`. This
+/// is only used if the store has the isolated keyring option enabled.
+pub fn store_gpg_home(store: &Store) -> PathBuf {
+    crate::util::env::data_dir().join(store.id())
+}
+
+/// Get the crypto config to use for a store.
+///
+/// Uses the store-local GnuPG home for public keys if the store has the isolated keyring option
+/// enabled, see `store_gpg_home`. Enables signing secrets if the store has a signing policy.
+///
+/// Errors if signing secrets is combined with the isolated keyring option, which is not supported.
+pub fn store_crypto_config(store: &Store) -> Result<Config> {
+    let options = store.options()?;
+    let mut config = Config::from(store_proto(store));
+    if options.isolated_keyring {
+        if options.sign_secrets.is_some() {
+            return Err(Err::SignIsolatedKeyring.into());
+        }
+        config.gpg_home = Some(store_gpg_home(store));
+    }
+    config.sign = options.sign_secrets.is_some();
+    Ok(config)
+}
+
+/// Get the recipient requests directory for a store.
+pub fn store_requests_dir(store: &Store) -> PathBuf {
+    store.root.join(STORE_REQUESTS_DIR)
+}
+
 /// Read GPG fingerprints from store.
-pub fn store_read_gpg_fingerprints(store: &Store) -> Result<Vec<String>> {
-    let path = store_gpg_ids_file(store);
+///
+/// Group references are expanded using the given groups, see `Groups::load`.
+pub fn store_read_gpg_fingerprints(store: &Store, groups: &Groups) -> Result<Vec<String>> {
+    store_read_gpg_fingerprints_for(store, groups, &store.root)
+}
+
+/// Read GPG fingerprints that apply to the given path in a store.
+///
+/// See `store_gpg_ids_file_for` on how the GPG IDs file is selected.
+///
+/// Group references are expanded into the fingerprints of their members using the given groups.
+/// Load these once with `Groups::load` and reuse them, as loading verifies the groups file.
+pub fn store_read_gpg_fingerprints_for(
+    store: &Store,
+    groups: &Groups,
+    path: &Path,
+) -> Result<Vec<String>> {
+    let path = store_gpg_ids_file_for(store, path);
+    if path.is_file() {
+        groups.expand(&read_fingerprints(path)?)
+    } else {
+        Ok(vec![])
+    }
+}
+
+/// Read GPG fingerprints from all GPG IDs files in a store.
+///
+/// Group references are expanded into the fingerprints of their members using the given groups.
+/// The returned list is deduplicated.
+pub fn store_read_all_gpg_fingerprints(store: &Store, groups: &Groups) -> Result<Vec<String>> {
+    let mut fingerprints: Vec<String> = Vec::new();
+    for path in store_gpg_ids_files(store) {
+        for fp in groups.expand(&read_fingerprints(path)?)? {
+            if !fingerprints
+                .iter()
+                .any(|other| util::fingerprints_equal(other, &fp))
+            {
+                fingerprints.push(fp);
+            }
+        }
+    }
+    Ok(fingerprints)
+}
+
+/// Read the entries of the GPG IDs file in the given store directory.
+///
+/// Unlike `store_read_gpg_fingerprints_for`, group references are not expanded. Returns an empty
+/// list if the directory has no GPG IDs file.
+pub fn store_read_gpg_ids_at(store: &Store, dir: &Path) -> Result<Vec<String>> {
+    let path = store.root.join(dir).join(STORE_GPG_IDS_FILE);
     if path.is_file() {
         read_fingerprints(path)
     } else {
@@ -35,6 +241,55 @@ pub fn store_read_gpg_fingerprints(store: &Store) -> Result<Vec<String>> {
     }
 }
 
+/// Find the directories with a GPG IDs file that references the given group.
+pub fn store_group_dirs(store: &Store, name: &str) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+    for path in store_gpg_ids_files(store) {
+        if read_fingerprints(&path)?
+            .iter()
+            .any(|entry| groups::group_ref(entry) == Some(name))
+        {
+            dirs.push(path.parent().unwrap_or(&store.root).to_path_buf());
+        }
+    }
+    Ok(dirs)
+}
+
+/// Add a reference to the given group to the GPG IDs file in the given store directory.
+///
+/// If the directory has no GPG IDs file yet, it is created with the recipients that currently
+/// apply to it.
+pub fn store_add_group_at(store: &Store, dir: &Path, name: &str) -> Result<()> {
+    let groups = Groups::load(store)?;
+    if groups.members(name).is_none() {
+        return Err(groups::Err::UnknownGroup(name.into()).into());
+    }
+
+    let mut entries = if store.root.join(dir).join(STORE_GPG_IDS_FILE).is_file() {
+        store_read_gpg_ids_at(store, dir)?
+    } else {
+        store_read_gpg_fingerprints_for(store, &groups, dir)?
+    };
+    if !entries
+        .iter()
+        .any(|entry| groups::group_ref(entry) == Some(name))
+    {
+        entries.push(groups::format_group_ref(name));
+    }
+    write_gpg_ids_at(store, &groups, dir, &entries, &[])
+}
+
+/// Save recipient groups for the given store.
+///
+/// This signs the groups file if signing is enabled, and syncs public key files as group
+/// membership affects the store recipients.
+pub fn store_save_groups(store: &Store, groups: &Groups) -> Result<()> {
+    ensure_can_sign(store)?;
+    let path = groups.save(store)?;
+    sign_files(store, &[path])?;
+    store_sync_public_key_files(store, &load_all_gpg_keys(store, groups)?)
+}
+
 /// Write GPG fingerprints to a store.
 ///
 /// Overwrites any existing file.
@@ -42,7 +297,82 @@ pub fn store_write_gpg_fingerprints<S: AsRef<str>>(
     store: &Store,
     fingerprints: &[S],
 ) -> Result<()> {
-    write_fingerprints(store_gpg_ids_file(store), fingerprints)
+    store_write_gpg_fingerprints_at(store, &store.root, fingerprints)
+}
+
+/// Write GPG fingerprints to the GPG IDs file in the given store directory.
+///
+/// Overwrites any existing file. Creates the directory if it does not exist.
+pub fn store_write_gpg_fingerprints_at<S: AsRef<str>>(
+    store: &Store,
+    dir: &Path,
+    fingerprints: &[S],
+) -> Result<()> {
+    let dir = store.root.join(dir);
+    fs::create_dir_all(&dir).map_err(Err::WriteFile)?;
+    write_fingerprints(dir.join(STORE_GPG_IDS_FILE), fingerprints)
+}
+
+/// Read age recipients that apply to the given path in a store.
+///
+/// See `store_gpg_ids_file_for` on how the age recipients file is selected.
+#[cfg(feature = "_crypto-age")]
+pub fn store_read_age_recipients_for(store: &Store, path: &Path) -> Result<Vec<Key>> {
+    let path = nearest_file(store, path, STORE_AGE_RECIPIENTS_FILE);
+    if path.is_file() {
+        read_age_recipients(path)
+    } else {
+        Ok(vec![])
+    }
+}
+
+/// Read age recipients from all age recipients files in a store.
+///
+/// The returned list is deduplicated.
+#[cfg(feature = "_crypto-age")]
+pub fn store_read_all_age_recipients(store: &Store) -> Result<Vec<Key>> {
+    let mut keys: Vec<Key> = Vec::new();
+    for path in store_age_recipients_files(store) {
+        for key in read_age_recipients(path)? {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Write age recipients to the age recipients file in the given store directory.
+///
+/// Overwrites any existing file. Creates the directory if it does not exist.
+#[cfg(feature = "_crypto-age")]
+pub fn store_write_age_recipients_at(store: &Store, dir: &Path, keys: &[Key]) -> Result<()> {
+    let dir = store.root.join(dir);
+    fs::create_dir_all(&dir).map_err(Err::WriteFile)?;
+    let keys: Vec<_> = keys
+        .iter()
+        .filter_map(|key| match key {
+            Key::Age(key) => Some(key.clone()),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        })
+        .collect();
+    fs::write(
+        dir.join(STORE_AGE_RECIPIENTS_FILE),
+        super::proto::age::format_recipients(&keys),
+    )
+    .map_err(|err| Err::WriteFile(err).into())
+}
+
+/// Read age recipients from the given file.
+#[cfg(feature = "_crypto-age")]
+fn read_age_recipients<P: AsRef<Path>>(path: P) -> Result<Vec<Key>> {
+    Ok(
+        super::proto::age::parse_recipients(&fs::read_to_string(path).map_err(Err::ReadFile)?)?
+            .into_iter()
+            .map(|key| key.into_key())
+            .collect(),
+    )
 }
 
 /// Read fingerprints from the given file.
@@ -78,25 +408,69 @@ fn write_fingerprints<P: AsRef<Path>, S: AsRef<str>>(path: P, fingerprints: &[S]
 ///
 /// This will try to load the keys for all configured protocols, and errors if it fails.
 pub fn store_load_keys(store: &Store) -> Result<Vec<Key>> {
-    let mut keys = Vec::new();
+    store_load_keys_for(store, &store.root)
+}
 
+/// Load the keys that apply to the given path in a store.
+///
+/// This will try to load the keys for all configured protocols, and errors if it fails.
+pub fn store_load_keys_for(store: &Store, path: &Path) -> Result<Vec<Key>> {
     // TODO: what to do if ids file does not exist?
     // TODO: what to do if recipients is empty?
     // TODO: what to do if key listed in file is not found, attempt to install?
 
-    // Load GPG keys
-    // TODO: do not crash here if GPG ids file is not found!
-    let fingerprints = store_read_gpg_fingerprints(store)?;
+    match store_proto(store) {
+        // Load GPG keys
+        // TODO: do not crash here if GPG ids file is not found!
+        Proto::Gpg => {
+            verify_gpg_ids_files(store, &[store_gpg_ids_file_for(store, path)])?;
+            let groups = Groups::load(store)?;
+            find_gpg_keys(
+                store,
+                &store_read_gpg_fingerprints_for(store, &groups, path)?,
+            )
+        }
+
+        // Load age keys, recipients file holds the keys themselves
+        #[cfg(feature = "_crypto-age")]
+        Proto::Age => store_read_age_recipients_for(store, path),
 
-    if !fingerprints.is_empty() {
-        let mut context = super::context(&crate::CONFIG)?;
-        let fingerprints: Vec<_> = fingerprints.iter().map(|fp| fp.as_str()).collect();
-        keys.extend(context.find_public_keys(&fingerprints)?);
+        // NEWPROTO: if a new proto is added, keys for a store should be loaded here
+        #[allow(unreachable_patterns)]
+        proto => Err(super::Err::Unsupported(proto).into()),
     }
+}
 
-    // NEWPROTO: if a new proto is added, keys for a store should be loaded here
+/// Load the keys from all GPG IDs files in the given store.
+///
+/// This will try to load the keys for all configured protocols, and errors if it fails.
+pub fn store_load_all_keys(store: &Store) -> Result<Vec<Key>> {
+    match store_proto(store) {
+        Proto::Gpg => load_all_gpg_keys(store, &Groups::load(store)?),
+        #[cfg(feature = "_crypto-age")]
+        Proto::Age => store_read_all_age_recipients(store),
 
-    Ok(keys)
+        // NEWPROTO: if a new proto is added, keys for a store should be loaded here
+        #[allow(unreachable_patterns)]
+        proto => Err(super::Err::Unsupported(proto).into()),
+    }
+}
+
+/// Load the GPG keys from all GPG IDs files in the given store, using the given groups.
+fn load_all_gpg_keys(store: &Store, groups: &Groups) -> Result<Vec<Key>> {
+    verify_gpg_ids_files(store, &store_gpg_ids_files(store))?;
+    find_gpg_keys(store, &store_read_all_gpg_fingerprints(store, groups)?)
+}
+
+/// Find the public GPG keys in the keychain for the given fingerprints.
+fn find_gpg_keys(store: &Store, fingerprints: &[String]) -> Result<Vec<Key>> {
+    if fingerprints.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut context = super::context(&store_crypto_config(store)?)?;
+    let fingerprints: Vec<_> = fingerprints.iter().map(|fp| fp.as_str()).collect();
+    context.find_public_keys(&fingerprints)
 }
 
 /// Load the recipients for the given store.
@@ -106,26 +480,115 @@ pub fn store_load_recipients(store: &Store) -> Result<Recipients> {
     Ok(Recipients::from(store_load_keys(store)?))
 }
 
+/// Load the recipients that apply to the given path in a store.
+///
+/// This will try to load the recipient keys for all configured protocols, and errors if it fails.
+pub fn store_load_recipients_for(store: &Store, path: &Path) -> Result<Recipients> {
+    Ok(Recipients::from(store_load_keys_for(store, path)?))
+}
+
 /// Save the keys for the given store.
 ///
 /// This overwrites any existing recipient keys.
 pub fn store_save_keys(store: &Store, keys: &[Key]) -> Result<()> {
-    // Save GPG keys
-    let gpg_fingerprints: Vec<_> = keys
-        .iter()
-        .filter(|key| key.proto() == Proto::Gpg)
-        .map(|key| key.fingerprint(false))
-        .collect();
-    store_write_gpg_fingerprints(store, &gpg_fingerprints)?;
+    store_save_keys_at(store, &store.root, keys)
+}
+
+/// Save the keys for the given directory in a store.
+///
+/// This writes the GPG IDs or age recipients file in the given directory, and overwrites any
+/// existing recipient keys in it.
+pub fn store_save_keys_at(store: &Store, dir: &Path, keys: &[Key]) -> Result<()> {
+    // Forget expiry dates of removed recipients
+    prune_expiry_at(store, dir, keys)?;
+
+    match store_proto(store) {
+        Proto::Gpg => {}
 
-    // Sync public keys for all proto's
-    store_sync_public_key_files(store, keys)?;
+        // Save age keys, no public key files to sync
+        #[cfg(feature = "_crypto-age")]
+        Proto::Age => return store_write_age_recipients_at(store, dir, keys),
+
+        // NEWPROTO: if a new proto is added, keys for a store should be saved here
+        #[allow(unreachable_patterns)]
+        proto => return Err(super::Err::Unsupported(proto).into()),
+    }
+
+    // Keep group references if all group members are still recipients
+    let groups = Groups::load(store)?;
+    let mut entries: Vec<String> = vec![];
+    let mut grouped: Vec<&String> = vec![];
+    for entry in store_read_gpg_ids_at(store, dir)? {
+        let members = match groups::group_ref(&entry).and_then(|name| groups.members(name)) {
+            Some(members) => members,
+            None => continue,
+        };
+        if members
+            .iter()
+            .all(|fp| util::keys_contain_fingerprint(keys, fp))
+        {
+            entries.push(entry);
+            grouped.extend(members);
+        }
+    }
+
+    // Save GPG keys not covered by a group
+    entries.extend(
+        keys.iter()
+            .filter(|key| key.proto() == Proto::Gpg)
+            .map(|key| key.fingerprint(false))
+            .filter(|fp| {
+                !grouped
+                    .iter()
+                    .any(|other| util::fingerprints_equal(other, fp))
+            }),
+    );
+    write_gpg_ids_at(store, &groups, dir, &entries, keys)?;
 
     // TODO: import missing keys to system?
 
     Ok(())
 }
 
+/// Write entries to the GPG IDs file in the given store directory.
+///
+/// Signs the file if signing is enabled, and syncs public key files. The given keys are included
+/// in the synced public keys, along with the keys from all GPG IDs files using the given groups.
+fn write_gpg_ids_at(
+    store: &Store,
+    groups: &Groups,
+    dir: &Path,
+    entries: &[String],
+    keys: &[Key],
+) -> Result<()> {
+    // Make sure we can sign before changing anything
+    ensure_can_sign(store)?;
+
+    store_write_gpg_fingerprints_at(store, dir, entries)?;
+    sign_files(store, &[store.root.join(dir).join(STORE_GPG_IDS_FILE)])?;
+
+    // Sync public keys for all proto's, include keys from other GPG IDs files
+    let mut all_keys = load_all_gpg_keys(store, groups)?;
+    for key in keys {
+        if !all_keys.contains(key) {
+            all_keys.push(key.clone());
+        }
+    }
+    store_sync_public_key_files(store, &all_keys)
+}
+
+/// Make sure we can sign if signing is enabled, fails if we own none of the signing keys.
+fn ensure_can_sign(store: &Store) -> Result<()> {
+    let signing_keys = signing_keys();
+    if !signing_keys.is_empty() {
+        find_signing_key(
+            &mut super::context(&store_crypto_config(store)?)?,
+            &signing_keys,
+        )?;
+    }
+    Ok(())
+}
+
 /// Save the keys for the given store.
 ///
 /// This overwrites any existing recipient keys.
@@ -133,6 +596,13 @@ pub fn store_save_recipients(store: &Store, recipients: &Recipients) -> Result<(
     store_save_keys(store, recipients.keys())
 }
 
+/// Save the recipients for the given directory in a store.
+///
+/// This overwrites any existing recipient keys in the GPG IDs file of that directory.
+pub fn store_save_recipients_at(store: &Store, dir: &Path, recipients: &Recipients) -> Result<()> {
+    store_save_keys_at(store, dir, recipients.keys())
+}
+
 /// Sync public key files in store with selected recipients.
 ///
 /// - Removes obsolete keys that are not a selected recipient
@@ -141,7 +611,16 @@ pub fn store_save_recipients(store: &Store, recipients: &Recipients) -> Result<(
 /// This syncs public key files for all protocols. This is because the public key files themselves
 /// don't specify what protocol they use. All public key files and keys must therefore be taken
 /// into consideration all at once.
+///
+/// The given keys must include recipients from all GPG IDs files in the store, see
+/// `store_load_all_keys`.
+///
+/// Stores using age are skipped, as their recipients files hold the public keys themselves.
 pub fn store_sync_public_key_files(store: &Store, keys: &[Key]) -> Result<()> {
+    if store_proto(store) == Proto::Age {
+        return Ok(());
+    }
+
     // Get public keys directory, ensure it exists
     let dir = store_public_keys_dir(store);
     fs::create_dir_all(&dir).map_err(Err::SyncKeyFiles)?;
@@ -155,28 +634,38 @@ pub fn store_sync_public_key_files(store: &Store, keys: &[Key]) -> Result<()> {
         .filter_map(|e| {
             e.file_name()
                 .to_str()
+                .filter(|name| !name.ends_with(SIGNATURE_SUFFIX))
                 .map(|fp| (e.path(), util::format_fingerprint(fp)))
         })
         .collect();
 
-    // Remove unused keys
+    // Remove unused keys, along with their signature
     for (path, _) in files
         .iter()
         .filter(|(_, fp)| !util::keys_contain_fingerprint(keys, fp))
     {
         fs::remove_file(path).map_err(Err::SyncKeyFiles)?;
+        let sig_path = signature_path(path);
+        if sig_path.is_file() {
+            fs::remove_file(sig_path).map_err(Err::SyncKeyFiles)?;
+        }
     }
 
-    // Add missing keys
+    // Add missing keys, re-export unsigned keys from the keychain so we never sign a key file as
+    // it is found on disk
+    let signing = !signing_keys().is_empty();
     let mut contexts = ContextPool::empty();
+    let mut config = store_crypto_config(store)?;
     for (key, fp) in keys
         .iter()
         .map(|k| (k, k.fingerprint(false)))
-        .filter(|(_, fp)| !files.iter().any(|(_, other)| fp == other))
+        .filter(|(_, fp)| {
+            !files.iter().any(|(_, other)| fp == other)
+                || (signing && !signature_path(&dir.join(fp)).is_file())
+        })
     {
         // Lazy load compatible context
-        let proto = key.proto();
-        let config = Config::from(proto);
+        config.proto = key.proto();
         let context = contexts.get_mut(&config)?;
 
         // Export public key to disk
@@ -184,12 +673,23 @@ pub fn store_sync_public_key_files(store: &Store, keys: &[Key]) -> Result<()> {
         context.export_key_file(key.clone(), &path)?;
     }
 
+    // Sign key files that don't have a signature yet
+    let unsigned: Vec<PathBuf> = keys
+        .iter()
+        .map(|key| dir.join(key.fingerprint(false)))
+        .filter(|path| !signature_path(path).is_file())
+        .collect();
+    sign_files(store, &unsigned)?;
+
     // NEWPROTO: if a new proto is added, public keys should be synced here
 
     Ok(())
 }
 
 /// Import keys from store that are missing in the keychain.
+///
+/// Keys are imported into the store-local keyring if the store has the isolated keyring option
+/// enabled.
 pub fn import_missing_keys_from_store(store: &Store) -> Result<Vec<ImportResult>> {
     // Get public keys directory, ensure it exists
     let dir = store_public_keys_dir(store);
@@ -199,15 +699,18 @@ pub fn import_missing_keys_from_store(store: &Store) -> Result<Vec<ImportResult>
 
     // Cache protocol contexts
     let mut contexts = ContextPool::empty();
+    let config = store_crypto_config(store)?;
     let mut results = Vec::new();
 
     // Check for missing GPG keys based on fingerprint, import them
-    let gpg_fingerprints = store_read_gpg_fingerprints(store)?;
+    verify_gpg_ids_files(store, &store_gpg_ids_files(store))?;
+    let gpg_fingerprints = store_read_all_gpg_fingerprints(store, &Groups::load(store)?)?;
     for fingerprint in gpg_fingerprints {
-        let context = contexts.get_mut(&crate::CONFIG)?;
+        let context = contexts.get_mut(&config)?;
         if context.get_public_key(&fingerprint).is_err() {
             let path = &store_public_keys_dir(store).join(&fingerprint);
             if path.is_file() {
+                verify_files(store, std::slice::from_ref(path))?;
                 context.import_key_file(path)?;
                 results.push(ImportResult::Imported(fingerprint));
             } else {
@@ -221,6 +724,351 @@ pub fn import_missing_keys_from_store(store: &Store) -> Result<Vec<ImportResult>
     Ok(results)
 }
 
+/// A pending request to become a store recipient.
+///
+/// This is a public key file in the requests directory of a store, named after the fingerprint
+/// of the requesting key.
+pub struct Request {
+    /// Fingerprint of the requesting key.
+    pub fingerprint: String,
+
+    /// Path to the public key file.
+    pub path: PathBuf,
+}
+
+/// Read all pending recipient requests in a store.
+///
+/// Files not named after a full fingerprint are ignored.
+pub fn store_read_requests(store: &Store) -> Result<Vec<Request>> {
+    let dir = store_requests_dir(store);
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut requests: Vec<Request> = dir
+        .read_dir()
+        .map_err(Err::ReadFile)?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|f| f.is_file()).unwrap_or(false))
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()
+                .filter(|fp| util::is_full_fingerprint(fp))
+                .map(|fp| Request {
+                    fingerprint: util::format_fingerprint(fp),
+                    path: e.path(),
+                })
+        })
+        .collect();
+    requests.sort_by(|a, b| a.fingerprint.cmp(&b.fingerprint));
+    Ok(requests)
+}
+
+/// Add a request for the given key to become a store recipient.
+///
+/// Exports the public key into the requests directory of the store, any existing request for
+/// the same key is replaced.
+pub fn store_write_request(store: &Store, key: &Key) -> Result<Request> {
+    match key.proto() {
+        Proto::Gpg => {}
+
+        // NEWPROTO: if a new proto is added, requests may be supported here
+        #[allow(unreachable_patterns)]
+        proto => return Err(super::Err::Unsupported(proto).into()),
+    }
+
+    let dir = store_requests_dir(store);
+    fs::create_dir_all(&dir).map_err(Err::WriteFile)?;
+
+    let fingerprint = key.fingerprint(false);
+    let path = dir.join(&fingerprint);
+    super::context(&store_crypto_config(store)?)?.export_key_file(key.clone(), &path)?;
+    Ok(Request { fingerprint, path })
+}
+
+/// Import the key of a recipient request into the keychain.
+///
+/// Returns the imported key. The public key file is checked before importing anything, it must
+/// hold exactly one key having the requested fingerprint.
+pub fn store_import_request(store: &Store, request: &Request) -> Result<Key> {
+    if !util::is_full_fingerprint(&request.fingerprint) {
+        return Err(Err::RequestKeyMismatch(request.fingerprint.clone()).into());
+    }
+
+    let mut context = super::context(&store_crypto_config(store)?)?;
+    let data = fs::read(&request.path).map_err(Err::ReadFile)?;
+    let keys = context.read_keys(&data)?;
+    match keys.as_slice() {
+        [key] if util::fingerprints_equal(key.fingerprint(false), &request.fingerprint) => {}
+        _ => return Err(Err::RequestKeyMismatch(request.fingerprint.clone()).into()),
+    }
+
+    context.import_key(&data)?;
+    context.get_public_key(&request.fingerprint)
+}
+
+/// Remove a recipient request from the store.
+pub fn store_remove_request(request: &Request) -> Result<()> {
+    fs::remove_file(&request.path).map_err(|err| Err::WriteFile(err).into())
+}
+
+/// Date until which a recipient has access to a store directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expiry {
+    /// Fingerprint of the recipient key.
+    pub fingerprint: String,
+
+    /// Last day of access, in UTC.
+    pub until: NaiveDate,
+}
+
+impl Expiry {
+    /// Get the time of expiry, at the end of the last day of access.
+    pub fn time(&self) -> SystemTime {
+        expiry_time(self.until)
+    }
+
+    /// Get the remaining time of access, `None` if expired.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time().duration_since(SystemTime::now()).ok()
+    }
+
+    /// Check whether access has expired.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_none()
+    }
+}
+
+/// Parse a recipient expiry date, in `YYYY-MM-DD` format.
+pub fn parse_expiry_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), EXPIRY_DATE_FORMAT)
+        .map_err(|err| Err::ExpiryDate(date.into(), err).into())
+}
+
+/// Get the time of expiry for the given last day of access, at the end of that day in UTC.
+pub fn expiry_time(until: NaiveDate) -> SystemTime {
+    let end = until
+        .succ_opt()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc().timestamp())
+        .unwrap_or(i64::MAX);
+    UNIX_EPOCH + Duration::from_secs(end.max(0) as u64)
+}
+
+/// Get the expiry file that applies to the given path in a store.
+///
+/// This is the expiry file next to the recipients file that applies to the path, it may not exist.
+fn store_expiry_file_for(store: &Store, path: &Path) -> PathBuf {
+    store_recipients_file_for(store, path)
+        .parent()
+        .unwrap_or(&store.root)
+        .join(STORE_EXPIRY_FILE)
+}
+
+/// Get all recipient expiry files in a store.
+///
+/// Hidden directories are skipped.
+pub fn store_expiry_files(store: &Store) -> Vec<PathBuf> {
+    find_files(store, STORE_EXPIRY_FILE)
+}
+
+/// Read recipient expiry dates that apply to the given path in a store.
+///
+/// Returns an empty list if no recipient has an expiry date.
+pub fn store_read_expiry_for(store: &Store, path: &Path) -> Result<Vec<Expiry>> {
+    read_expiry(store_expiry_file_for(store, path))
+}
+
+/// Read recipient expiry dates from the expiry file in the given store directory.
+///
+/// Returns an empty list if the directory has no expiry file.
+pub fn store_read_expiry_at(store: &Store, dir: &Path) -> Result<Vec<Expiry>> {
+    read_expiry(store.root.join(dir).join(STORE_EXPIRY_FILE))
+}
+
+/// Set or clear the expiry date of a recipient in the given store directory.
+///
+/// The directory should have its own recipients file.
+pub fn store_set_expiry_at(
+    store: &Store,
+    dir: &Path,
+    fingerprint: &str,
+    until: Option<NaiveDate>,
+) -> Result<()> {
+    let path = store.root.join(dir).join(STORE_EXPIRY_FILE);
+    let mut expiry = read_expiry(&path)?;
+    expiry.retain(|e| !util::fingerprints_equal(&e.fingerprint, fingerprint));
+    if let Some(until) = until {
+        expiry.push(Expiry {
+            fingerprint: util::format_fingerprint(fingerprint),
+            until,
+        });
+    }
+    write_expiry(path, &expiry)
+}
+
+/// Remove expiry dates of keys that are not in the given list of recipient keys.
+fn prune_expiry_at(store: &Store, dir: &Path, keys: &[Key]) -> Result<()> {
+    let path = store.root.join(dir).join(STORE_EXPIRY_FILE);
+    let mut expiry = read_expiry(&path)?;
+    let count = expiry.len();
+    expiry.retain(|e| util::keys_contain_fingerprint(keys, &e.fingerprint));
+    if expiry.len() == count {
+        return Ok(());
+    }
+    write_expiry(path, &expiry)
+}
+
+/// Read recipient expiry dates from a file.
+///
+/// Each line holds an expiry date and fingerprint, separated by whitespace. Returns an empty list
+/// if the file doesn't exist.
+fn read_expiry<P: AsRef<Path>>(path: P) -> Result<Vec<Expiry>> {
+    let path = path.as_ref();
+    if !path.is_file() {
+        return Ok(vec![]);
+    }
+
+    fs::read_to_string(path)
+        .map_err(Err::ReadFile)?
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (date, fingerprint) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| Err::ExpiryLine(line.into()))?;
+            Ok(Expiry {
+                fingerprint: util::format_fingerprint(fingerprint),
+                until: parse_expiry_date(date)?,
+            })
+        })
+        .collect()
+}
+
+/// Write recipient expiry dates to a file.
+///
+/// The file is removed if the list is empty.
+fn write_expiry<P: AsRef<Path>>(path: P, expiry: &[Expiry]) -> Result<()> {
+    let path = path.as_ref();
+    if expiry.is_empty() {
+        if path.is_file() {
+            fs::remove_file(path).map_err(Err::WriteFile)?;
+        }
+        return Ok(());
+    }
+
+    let contents: String = expiry
+        .iter()
+        .map(|e| format!("{} {}\n", e.until.format(EXPIRY_DATE_FORMAT), e.fingerprint))
+        .collect();
+    fs::write(path, contents).map_err(|err| Err::WriteFile(err).into())
+}
+
+/// Get the fingerprints of keys that sign GPG IDs and public key files.
+///
+/// These are read from the `PASSWORD_STORE_SIGNING_KEY` environment variable. Returns an empty
+/// list if signing is not enabled.
+pub fn signing_keys() -> Vec<String> {
+    std::env::var(ENV_SIGNING_KEYS)
+        .map(|keys| {
+            keys.split_whitespace()
+                .map(util::format_fingerprint)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get the path of the detached signature file for a file.
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(SIGNATURE_SUFFIX);
+    PathBuf::from(path)
+}
+
+/// Verify the signatures of the given GPG IDs files.
+///
+/// Files that don't exist are skipped. See `verify_files`.
+fn verify_gpg_ids_files(store: &Store, paths: &[PathBuf]) -> Result<()> {
+    let paths: Vec<PathBuf> = paths.iter().filter(|p| p.is_file()).cloned().collect();
+    verify_files(store, &paths)
+}
+
+/// Verify the detached signatures of the given files.
+///
+/// Each file must have a valid signature, made by any of the configured signing keys. Does
+/// nothing if no signing keys are configured.
+pub fn verify_files(store: &Store, paths: &[PathBuf]) -> Result<()> {
+    verify_files_with(store, &signing_keys(), paths)
+}
+
+/// Verify the detached signatures of the given files, made by any of the given signing keys.
+fn verify_files_with(store: &Store, signing_keys: &[String], paths: &[PathBuf]) -> Result<()> {
+    if signing_keys.is_empty() || paths.is_empty() {
+        return Ok(());
+    }
+
+    let mut context = super::context(&store_crypto_config(store)?)?;
+    for path in paths {
+        let sig_path = signature_path(path);
+        if !sig_path.is_file() {
+            return Err(Err::MissingSignature(path.clone()).into());
+        }
+
+        let data = fs::read(path).map_err(Err::ReadFile)?;
+        let signature = fs::read(&sig_path).map_err(Err::ReadFile)?;
+        let signers = context.verify_detached(&data, &signature)?;
+        if !signers.iter().any(|signer| {
+            signing_keys
+                .iter()
+                .any(|fp| util::fingerprints_equal(fp, signer))
+        }) {
+            return Err(Err::InvalidSignature(path.clone()).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Sign the given files with a detached signature.
+///
+/// Signs with the first configured signing key we own the secret key for, and writes the
+/// signature next to each file. Does nothing if no signing keys are configured.
+fn sign_files(store: &Store, paths: &[PathBuf]) -> Result<()> {
+    sign_files_with(store, &signing_keys(), paths)
+}
+
+/// Sign the given files with a detached signature, using the first of the given signing keys we
+/// own the secret key for.
+fn sign_files_with(store: &Store, signing_keys: &[String], paths: &[PathBuf]) -> Result<()> {
+    if signing_keys.is_empty() || paths.is_empty() {
+        return Ok(());
+    }
+
+    let mut context = super::context(&store_crypto_config(store)?)?;
+    let key = find_signing_key(&mut context, signing_keys)?;
+    for path in paths {
+        let data = fs::read(path).map_err(Err::ReadFile)?;
+        let signature = context.sign_detached(&key, &data)?;
+        fs::write(signature_path(path), signature).map_err(Err::WriteFile)?;
+    }
+
+    Ok(())
+}
+
+/// Find the first of the given signing keys we own the secret key for.
+fn find_signing_key(context: &mut super::Context, signing_keys: &[String]) -> Result<Key> {
+    context
+        .keys_private()?
+        .into_iter()
+        .find(|key| {
+            signing_keys
+                .iter()
+                .any(|fp| util::fingerprints_equal(key.fingerprint(false), fp))
+        })
+        .ok_or_else(|| Err::NoSigningKey.into())
+}
+
 /// Missing key import results.
 pub enum ImportResult {
     /// Key with given fingerprint was imported into keychain.
@@ -235,8 +1083,14 @@ pub trait StoreRecipients {
     /// Load recipients from given store.
     fn load(store: &Store) -> Result<Recipients>;
 
+    /// Load recipients that apply to the given path in a store.
+    fn load_for(store: &Store, path: &Path) -> Result<Recipients>;
+
     /// Save recipients to given store.
     fn save(&self, store: &Store) -> Result<()>;
+
+    /// Save recipients to the given directory in a store.
+    fn save_at(&self, store: &Store, dir: &Path) -> Result<()>;
 }
 
 impl StoreRecipients for Recipients {
@@ -245,10 +1099,20 @@ impl StoreRecipients for Recipients {
         store_load_recipients(store)
     }
 
+    /// Load recipients that apply to the given path in a store.
+    fn load_for(store: &Store, path: &Path) -> Result<Recipients> {
+        store_load_recipients_for(store, path)
+    }
+
     /// Save recipients to given store.
     fn save(&self, store: &Store) -> Result<()> {
         store_save_recipients(store, self)
     }
+
+    /// Save recipients to the given directory in a store.
+    fn save_at(&self, store: &Store, dir: &Path) -> Result<()> {
+        store_save_recipients_at(store, dir, self)
+    }
 }
 
 /// Store crypto error.
@@ -262,4 +1126,200 @@ pub enum Err {
 
     #[error("failed to sync public key files")]
     SyncKeyFiles(#[source] std::io::Error),
+
+    #[error("refusing to use {}, signature file is missing while signing is enabled through PASSWORD_STORE_SIGNING_KEY", _0.display())]
+    MissingSignature(PathBuf),
+
+    #[error("refusing to use {}, signature is invalid or not made by a key in PASSWORD_STORE_SIGNING_KEY", _0.display())]
+    InvalidSignature(PathBuf),
+
+    #[error("failed to sign, no secret key available for any key in PASSWORD_STORE_SIGNING_KEY")]
+    NoSigningKey,
+
+    #[error("signing secrets cannot be combined with the isolated keyring store option")]
+    SignIsolatedKeyring,
+
+    #[error("recipient request must only contain the requested key: {0}")]
+    RequestKeyMismatch(String),
+
+    #[error("invalid recipient expiry date '{0}', expected YYYY-MM-DD")]
+    ExpiryDate(String, #[source] chrono::ParseError),
+
+    #[error("invalid line in recipient expiry file: {0}")]
+    ExpiryLine(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "68EA3744E3B9D85433B71104795FD9B5C876523F";
+    const BOB: &str = "830171328A8155DD8EB1353B52E538F1F886AF90";
+
+    #[test]
+    fn expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().to_string_lossy()).unwrap();
+        let team = Path::new("team");
+        fs::create_dir_all(store.root.join(team)).unwrap();
+        fs::write(store.root.join("team/.gpg-id"), format!("{ALICE}\n{BOB}")).unwrap();
+        let past = parse_expiry_date("2020-01-01").unwrap();
+        let future = parse_expiry_date(" 9999-12-31 ").unwrap();
+
+        // Dates are stored per recipient, setting again replaces the date
+        store_set_expiry_at(&store, team, &ALICE.to_lowercase(), Some(future)).unwrap();
+        store_set_expiry_at(&store, team, BOB, Some(future)).unwrap();
+        store_set_expiry_at(&store, team, ALICE, Some(past)).unwrap();
+        let expiry = store_read_expiry_at(&store, team).unwrap();
+        assert_eq!(
+            expiry,
+            vec![
+                Expiry {
+                    fingerprint: BOB.into(),
+                    until: future,
+                },
+                Expiry {
+                    fingerprint: ALICE.into(),
+                    until: past,
+                },
+            ],
+        );
+        assert!(!expiry[0].is_expired());
+        assert!(expiry[1].is_expired());
+        assert_eq!(
+            expiry[1].time(),
+            UNIX_EPOCH + Duration::from_secs(1577923200)
+        );
+
+        // Secrets use the expiry file next to their recipients file
+        assert_eq!(
+            store_read_expiry_for(&store, Path::new("team/secret.gpg")).unwrap(),
+            expiry,
+        );
+        assert!(store_read_expiry_for(&store, Path::new("secret.gpg"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store_expiry_files(&store),
+            vec![store.root.join("team").join(STORE_EXPIRY_FILE)],
+        );
+
+        // File is removed once no recipient has an expiry date
+        store_set_expiry_at(&store, team, ALICE, None).unwrap();
+        store_set_expiry_at(&store, team, BOB, None).unwrap();
+        assert!(store_expiry_files(&store).is_empty());
+    }
+
+    #[test]
+    fn expiry_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STORE_EXPIRY_FILE);
+        assert!(read_expiry(&path).unwrap().is_empty());
+
+        // Comments and empty lines are skipped
+        fs::write(&path, format!("# comment\n\n  2020-01-01 {ALICE}  \n")).unwrap();
+        assert_eq!(read_expiry(&path).unwrap().len(), 1);
+
+        fs::write(&path, "2020-01-01\n").unwrap();
+        assert!(read_expiry(&path).is_err());
+        fs::write(&path, format!("2020-13-01 {ALICE}\n")).unwrap();
+        assert!(read_expiry(&path).is_err());
+        assert!(parse_expiry_date("01-01-2020").is_err());
+    }
+
+    #[cfg(feature = "backend-mock")]
+    #[test]
+    fn expiry_prune() {
+        use crate::crypto::backend::mock::{self, fixture::TempStore};
+
+        let alice = mock::add_secret_key("expiry-alice");
+        let bob = mock::add_public_key("expiry-bob");
+        let keys = [alice.clone(), bob.clone()];
+        let store = TempStore::new(&keys).unwrap();
+        let until = parse_expiry_date("2020-01-01").unwrap();
+        store_set_expiry_at(&store, &store.root, &alice.fingerprint(false), Some(until)).unwrap();
+        store_set_expiry_at(&store, &store.root, &bob.fingerprint(false), Some(until)).unwrap();
+
+        // Expiry dates are kept while recipients are kept
+        store_save_keys(&store, &keys).unwrap();
+        assert_eq!(store_read_expiry_at(&store, &store.root).unwrap().len(), 2);
+
+        // Expiry dates of removed recipients are forgotten
+        store_save_keys(&store, std::slice::from_ref(&alice)).unwrap();
+        let expiry = store_read_expiry_at(&store, &store.root).unwrap();
+        assert_eq!(expiry.len(), 1);
+        assert_eq!(expiry[0].fingerprint, alice.fingerprint(false));
+    }
+
+    #[cfg(feature = "backend-mock")]
+    #[test]
+    fn sign_verify_files() {
+        use crate::crypto::backend::mock::{self, fixture::TempStore};
+
+        let alice = mock::add_secret_key("sign-files-alice");
+        let bob = mock::add_public_key("sign-files-bob");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+        let path = store.root.join(STORE_GPG_IDS_FILE);
+        let alice_keys = vec![alice.fingerprint(false)];
+
+        // Missing signatures are rejected
+        assert!(verify_files_with(&store, &alice_keys, std::slice::from_ref(&path)).is_err());
+
+        // Signed files verify with the signing key only
+        sign_files_with(&store, &alice_keys, std::slice::from_ref(&path)).unwrap();
+        assert!(signature_path(&path).is_file());
+        verify_files_with(&store, &alice_keys, std::slice::from_ref(&path)).unwrap();
+        let bob_keys = vec![bob.fingerprint(false)];
+        assert!(verify_files_with(&store, &bob_keys, std::slice::from_ref(&path)).is_err());
+
+        // Changed files are rejected
+        fs::write(&path, "changed").unwrap();
+        assert!(verify_files_with(&store, &alice_keys, std::slice::from_ref(&path)).is_err());
+
+        // Can't sign without owning a signing key
+        assert!(sign_files_with(&store, &bob_keys, std::slice::from_ref(&path)).is_err());
+    }
+
+    #[cfg(feature = "backend-mock")]
+    #[test]
+    fn nearest_gpg_ids() {
+        use crate::crypto::backend::mock::{self, fixture::TempStore};
+        use crate::Plaintext;
+
+        let alice = mock::add_secret_key("nearest-alice");
+        let bob = mock::add_secret_key("nearest-bob");
+        let store = TempStore::new(std::slice::from_ref(&alice)).unwrap();
+        store_save_keys_at(&store, Path::new("team"), std::slice::from_ref(&bob)).unwrap();
+        let key_ids = |path: &Path| {
+            crate::crypto::context(&store_crypto_config(&store).unwrap())
+                .unwrap()
+                .encrypted_key_ids_file(path)
+                .unwrap()
+        };
+        let key_id = |key: &Key| key.fingerprint(false)[24..].to_string();
+
+        // Secrets in sub directories use the nearest GPG IDs file
+        let root = store.add_secret("root", Plaintext::from("a")).unwrap();
+        let team = store
+            .add_secret("team/secret", Plaintext::from("b"))
+            .unwrap();
+        let nested = store
+            .add_secret("team/nested/secret", Plaintext::from("c"))
+            .unwrap();
+        assert_eq!(key_ids(&root.path), vec![key_id(&alice)]);
+        assert_eq!(key_ids(&team.path), vec![key_id(&bob)]);
+        assert_eq!(key_ids(&nested.path), vec![key_id(&bob)]);
+        assert_eq!(
+            store_gpg_ids_file_for(&store, &nested.path),
+            store.root.join("team").join(STORE_GPG_IDS_FILE),
+        );
+
+        // Falls back to the root GPG IDs file
+        let other = store
+            .add_secret("other/secret", Plaintext::from("d"))
+            .unwrap();
+        assert_eq!(key_ids(&other.path), vec![key_id(&alice)]);
+        assert!(store_load_keys_for(&store, Path::new("other")).unwrap() == [alice.clone()]);
+        assert!(store_load_keys_for(&store, Path::new("team")).unwrap() == [bob.clone()]);
+    }
 }
@@ -11,6 +11,16 @@ pub fn format_fingerprint<S: AsRef<str>>(fingerprint: S) -> String {
     fingerprint.as_ref().trim().to_uppercase()
 }
 
+/// Check whether the given string is a full fingerprint.
+///
+/// This is 40 hexadecimal characters for v4 keys, or 64 for v5 and v6 keys. Short or long key IDs
+/// are not full fingerprints.
+pub fn is_full_fingerprint<S: AsRef<str>>(fingerprint: S) -> bool {
+    let fingerprint = fingerprint.as_ref().trim();
+    (fingerprint.len() == 40 || fingerprint.len() == 64)
+        && fingerprint.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// Check whether two fingerprints match.
 pub fn fingerprints_equal<S: AsRef<str>, T: AsRef<str>>(a: S, b: T) -> bool {
     !a.as_ref().trim().is_empty()
@@ -252,6 +252,48 @@ pub fn git_ref_hash<S: AsRef<str>>(repo: &Path, reference: S) -> Result<String>
     Ok(hash)
 }
 
+/// Git log of commits changing the given paths, newest first.
+///
+/// Each commit is formatted with `format`, followed by the status and names of changed files.
+/// Dates are formatted in local time.
+pub fn git_log_name_status(repo: &Path, format: &str, paths: &[String]) -> Result<String> {
+    let format = format!("--format={format}");
+    let mut args = vec![
+        "log",
+        "--name-status",
+        "--date=format-local:%Y-%m-%d %H:%M",
+        &format,
+        "--",
+    ];
+    args.extend(paths.iter().map(|path| path.as_str()));
+    git_stdout_ok(repo, &args, false)
+}
+
+/// Resolve a revision to a commit hash.
+pub fn git_rev_commit(repo: &Path, rev: &str) -> Result<String> {
+    git_stdout_ok(
+        repo,
+        ["rev-parse", "--verify", "-q", &format!("{rev}^{{commit}}")],
+        false,
+    )
+}
+
+/// Check whether a file exists at the given revision.
+pub fn git_has_file(repo: &Path, rev: &str, path: &str) -> Result<bool> {
+    Ok(
+        git_output(repo, ["cat-file", "-e", &format!("{rev}:{path}")], false)?
+            .status
+            .success(),
+    )
+}
+
+/// Get the raw contents of a file at the given revision.
+pub fn git_file_at(repo: &Path, rev: &str, path: &str) -> Result<Vec<u8>> {
+    let output = git_output(repo, ["cat-file", "blob", &format!("{rev}:{path}")], false)?;
+    cmd_assert_status(output.status)?;
+    Ok(output.stdout)
+}
+
 /// Get system time the repository was last pulled.
 /// See: https://stackoverflow.com/a/9229377/1000145 (stat -c %Y .git/FETCH_HEAD)
 pub fn git_last_pull_time(repo: &Path) -> Result<SystemTime> {
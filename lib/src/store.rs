@@ -3,6 +3,7 @@
 use std::ffi::OsString;
 use std::fs;
 use std::path::{self, Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::{ensure, Result};
 use thiserror::Error;
@@ -13,12 +14,26 @@ use crate::tomb::Tomb;
 use crate::{
     crypto::{self, prelude::*},
     sync::Sync,
+    trash::{Trash, TRASH_RETENTION_DAYS},
     Recipients,
 };
 
 /// Password store secret file suffix.
 pub const SECRET_SUFFIX: &str = ".gpg";
 
+/// Password store secret file suffix for secrets encrypted with age.
+pub const SECRET_SUFFIX_AGE: &str = ".age";
+
+/// Password store secret file suffix for ASCII armored secrets encrypted with GPG.
+pub const SECRET_SUFFIX_ARMOR: &str = ".asc";
+
+/// All known password store secret file suffixes.
+pub(crate) const SECRET_SUFFIXES: [&str; 3] =
+    [SECRET_SUFFIX, SECRET_SUFFIX_ARMOR, SECRET_SUFFIX_AGE];
+
+/// Password store options file.
+const STORE_OPTIONS_FILE: &str = ".prs/config.toml";
+
 /// Represents a password store.
 #[derive(Clone)]
 pub struct Store {
@@ -45,11 +60,55 @@ impl Store {
         Ok(Self { root })
     }
 
+    /// Get the crypto protocol used by this store.
+    pub fn proto(&self) -> crypto::Proto {
+        crypto::store::store_proto(self)
+    }
+
+    /// Get a stable identifier for this store.
+    ///
+    /// This is derived from the store root path, and is unique for each store on this machine.
+    pub fn id(&self) -> String {
+        // FNV-1a hash, stable across platforms and Rust versions
+        let hash = self
+            .root
+            .to_string_lossy()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        format!("{hash:016x}")
+    }
+
+    /// Get the options for this store.
+    pub fn options(&self) -> Result<StoreOptions> {
+        StoreOptions::load(self)
+    }
+
+    /// Get the secret file suffix used for new secrets in this store.
+    ///
+    /// GPG secrets are ASCII armored if the `armor` store option is set, see `StoreOptions`.
+    pub fn secret_suffix(&self) -> &'static str {
+        match self.proto() {
+            crypto::Proto::Age => SECRET_SUFFIX_AGE,
+            _ if self.options().map(|o| o.armor).unwrap_or(false) => SECRET_SUFFIX_ARMOR,
+            _ => SECRET_SUFFIX,
+        }
+    }
+
     /// Get the recipient keys for this store.
     pub fn recipients(&self) -> Result<Recipients> {
         Recipients::load(self)
     }
 
+    /// Get the recipient keys that apply to the given secret or directory path in this store.
+    ///
+    /// This uses the nearest `.gpg-id` or `.age-recipients` file walking up from the path, like
+    /// `pass` does.
+    pub fn recipients_for<P: AsRef<Path>>(&self, path: P) -> Result<Recipients> {
+        Recipients::load_for(self, path.as_ref())
+    }
+
     /// Get a sync helper for this store.
     pub fn sync(&self) -> Sync {
         Sync::new(self)
@@ -61,6 +120,11 @@ impl Store {
         Tomb::new(self, quiet, verbose, force)
     }
 
+    /// Get a trash helper for this store.
+    pub fn trash(&self) -> Trash<'_> {
+        Trash::new(self)
+    }
+
     /// Create secret iterator for this store.
     pub fn secret_iter(&self) -> SecretIter {
         self.secret_iter_config(SecretIterConfig::default())
@@ -83,9 +147,11 @@ impl Store {
         let path = path.to_str()?;
 
         // Try path with secret file suffix
-        let with_suffix = PathBuf::from(format!("{path}{SECRET_SUFFIX}"));
-        if with_suffix.is_file() {
-            return Some(Secret::from(self, with_suffix));
+        for suffix in SECRET_SUFFIXES {
+            let with_suffix = PathBuf::from(format!("{path}{suffix}"));
+            if with_suffix.is_file() {
+                return Some(Secret::from(self, with_suffix));
+            }
         }
 
         // Try path without secret file suffix
@@ -164,10 +230,11 @@ impl Store {
         }
 
         // Add secret extension if non existent
-        let ext: OsString = SECRET_SUFFIX.trim_start_matches('.').into();
+        let suffix = self.secret_suffix();
+        let ext: OsString = suffix.trim_start_matches('.').into();
         if path.extension() != Some(&ext) {
             let mut tmp = path.as_os_str().to_owned();
-            tmp.push(SECRET_SUFFIX);
+            tmp.push(suffix);
             path = PathBuf::from(tmp);
         }
 
@@ -183,6 +250,122 @@ impl Store {
     }
 }
 
+/// Password store options.
+///
+/// These are read from the `.prs/config.toml` file in the store, and apply to everyone using the
+/// store:
+///
+/// ```toml
+/// isolated-keyring = true
+/// sign-secrets = "require"
+/// armor = true
+/// trash-retention = 14
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StoreOptions {
+    /// Use a store-local GnuPG keyring for public keys of recipients.
+    ///
+    /// This prevents importing public keys of all recipients into the users global keyring. See
+    /// `crypto::store::store_gpg_home`.
+    pub isolated_keyring: bool,
+
+    /// Sign secrets when encrypting, and verify signatures when showing them.
+    ///
+    /// Secrets are not signed if not set.
+    pub sign_secrets: Option<SignPolicy>,
+
+    /// Write new GPG secrets ASCII armored, with the `.asc` suffix.
+    ///
+    /// Both armored and binary secrets are always read. Existing secrets keep their format, see
+    /// `housekeeping convert` to convert them.
+    pub armor: bool,
+
+    /// Number of days to keep removed secrets in the trash.
+    ///
+    /// `housekeeping run` permanently deletes secrets that have been in the trash for longer. Uses
+    /// `trash::TRASH_RETENTION_DAYS` if not set.
+    pub trash_retention: Option<u64>,
+}
+
+impl StoreOptions {
+    /// Load options from the given store.
+    ///
+    /// Returns default options if the store doesn't have an options file.
+    pub fn load(store: &Store) -> Result<Self> {
+        let path = store.root.join(STORE_OPTIONS_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(&path).map_err(Err::ReadOptions)?;
+        let table: toml::value::Table = toml::from_str(&data).map_err(Err::ParseOptions)?;
+        Ok(Self {
+            isolated_keyring: option_bool(&table, "isolated-keyring")?.unwrap_or(false),
+            sign_secrets: match option_str(&table, "sign-secrets")? {
+                None => None,
+                Some("warn") => Some(SignPolicy::Warn),
+                Some("require") => Some(SignPolicy::Require),
+                Some(_) => return Err(Err::InvalidOption("sign-secrets".into()).into()),
+            },
+            armor: option_bool(&table, "armor")?.unwrap_or(false),
+            trash_retention: match option_int(&table, "trash-retention")? {
+                None => None,
+                Some(days) if days >= 0 => Some(days as u64),
+                Some(_) => return Err(Err::InvalidOption("trash-retention".into()).into()),
+            },
+        })
+    }
+
+    /// Get how long removed secrets are kept in the trash.
+    pub fn trash_retention(&self) -> Duration {
+        let days = self.trash_retention.unwrap_or(TRASH_RETENTION_DAYS);
+        Duration::from_secs(days.saturating_mul(24 * 60 * 60))
+    }
+}
+
+/// Policy for signed secrets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignPolicy {
+    /// Sign secrets, warn when showing a secret that is unsigned or not signed by a recipient.
+    Warn,
+
+    /// Sign secrets, refuse to show a secret that is unsigned or not signed by a recipient.
+    Require,
+}
+
+/// Get a boolean option from an options table.
+///
+/// Errors if the option is set to a value of a different type.
+fn option_bool(table: &toml::value::Table, name: &str) -> Result<Option<bool>> {
+    match table.get(name) {
+        None => Ok(None),
+        Some(toml::Value::Boolean(value)) => Ok(Some(*value)),
+        Some(_) => Err(Err::InvalidOption(name.into()).into()),
+    }
+}
+
+/// Get a string option from an options table.
+///
+/// Errors if the option is set to a value of a different type.
+fn option_str<'a>(table: &'a toml::value::Table, name: &str) -> Result<Option<&'a str>> {
+    match table.get(name) {
+        None => Ok(None),
+        Some(toml::Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(Err::InvalidOption(name.into()).into()),
+    }
+}
+
+/// Get an integer option from an options table.
+///
+/// Errors if the option is set to a value of a different type.
+fn option_int(table: &toml::value::Table, name: &str) -> Result<Option<i64>> {
+    match table.get(name) {
+        None => Ok(None),
+        Some(toml::Value::Integer(value)) => Ok(Some(*value)),
+        Some(_) => Err(Err::InvalidOption(name.into()).into()),
+    }
+}
+
 /// Find secret result.
 pub enum FindSecret {
     /// Found exact secret match.
@@ -213,7 +396,12 @@ impl Secret {
         let name: String = relative_path(root, &path)
             .ok()
             .and_then(|f| f.to_str())
-            .map(|f| f.trim_end_matches(SECRET_SUFFIX))
+            .map(|f| {
+                SECRET_SUFFIXES
+                    .iter()
+                    .find_map(|suffix| f.strip_suffix(suffix))
+                    .unwrap_or(f)
+            })
             .unwrap_or_else(|| "?")
             .to_string();
         Self { name, path }
@@ -320,6 +508,8 @@ impl Iterator for SecretIter {
 }
 
 /// Check if given WalkDir DirEntry is hidden sub-directory.
+///
+/// This also excludes the trash, see `trash::TRASH_DIR`.
 fn is_hidden_subdir(entry: &DirEntry) -> bool {
     entry.depth() > 0
         && entry
@@ -335,7 +525,7 @@ fn is_secret_file(entry: &DirEntry) -> bool {
         && entry
             .file_name()
             .to_str()
-            .map(|s| s.ends_with(SECRET_SUFFIX))
+            .map(|s| SECRET_SUFFIXES.iter().any(|suffix| s.ends_with(suffix)))
             .unwrap_or(false)
 }
 
@@ -370,12 +560,12 @@ fn filter_by_config(entry: &DirEntry, config: &SecretIterConfig) -> bool {
 ///
 /// Returns true if there is no secret.
 pub fn can_decrypt(store: &Store) -> bool {
-    // Try all proto's here once we support more
+    let config = crypto::Config::from(store.proto());
     store
         .secret_iter()
         .next()
         .map(|secret| {
-            crypto::context(&crate::CONFIG)
+            crypto::context(&config)
                 .map(|mut context| context.can_decrypt_file(&secret.path).unwrap_or(true))
                 .unwrap_or(false)
         })
@@ -436,4 +626,13 @@ pub enum Err {
 
     #[error("cannot use directory as target without name hint")]
     TargetDirWithoutNamehint(PathBuf),
+
+    #[error("failed to read store options file")]
+    ReadOptions(#[source] std::io::Error),
+
+    #[error("failed to parse store options file")]
+    ParseOptions(#[source] toml::de::Error),
+
+    #[error("invalid value for store option: {0}")]
+    InvalidOption(String),
 }
@@ -1,13 +1,16 @@
 //! Password store synchronization functionality.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::Result;
+use thiserror::Error;
 
 use crate::{
+    crypto,
     git::{self, RepositoryState},
-    Store,
+    store::SECRET_SUFFIXES,
+    Ciphertext, Store,
 };
 
 /// Store git directory.
@@ -260,6 +263,63 @@ impl<'a> Sync<'a> {
         git::git_reset_hard(path)
     }
 
+    /// Get the history of a secret, newest revision first.
+    ///
+    /// The secret is given by name, it may have been removed from the store.
+    pub fn secret_history(&self, name: &str) -> Result<Vec<Revision>> {
+        let log = git::git_log_name_status(
+            self.path(),
+            "%H%x1f%an <%ae>%x1f%ad%x1f%s",
+            &secret_files(name),
+        )?;
+
+        let mut revisions: Vec<Revision> = vec![];
+        for line in log.lines() {
+            let mut parts = line.splitn(4, '\x1f');
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(hash), Some(author), Some(date), Some(message)) => revisions.push(Revision {
+                    hash: hash.into(),
+                    author: author.into(),
+                    date: date.into(),
+                    message: message.into(),
+                    removed: false,
+                }),
+                _ if line.starts_with("D\t") => {
+                    if let Some(revision) = revisions.last_mut() {
+                        revision.removed = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(revisions)
+    }
+
+    /// Resolve a revision, such as `HEAD~2` or an abbreviated hash, to its full commit hash.
+    pub fn resolve_revision(&self, rev: &str) -> Result<String> {
+        if rev.starts_with('-') {
+            return Err(Err::Revision(rev.into()).into());
+        }
+        git::git_rev_commit(self.path(), rev).map_err(|_| Err::Revision(rev.into()).into())
+    }
+
+    /// Get the ciphertext of a secret at the given revision.
+    ///
+    /// The secret is given by name, it may have been removed from the store since. Returns the
+    /// path the secret had at that revision along with its ciphertext.
+    pub fn secret_at(&self, name: &str, rev: &str) -> Result<(PathBuf, Ciphertext)> {
+        let repo = self.path();
+        let hash = self.resolve_revision(rev)?;
+
+        for file in secret_files(name) {
+            if git::git_has_file(repo, &hash, &file)? {
+                let ciphertext = crypto::read_ciphertext(git::git_file_at(repo, &hash, &file)?)?;
+                return Ok((repo.join(file), ciphertext));
+            }
+        }
+        Err(Err::NoSecretAt(name.into(), rev.into()).into())
+    }
+
     /// Get a list of changed files as raw output.
     /// This output is directly from git, is not processed, and is not stable.
     ///
@@ -277,6 +337,32 @@ impl<'a> Sync<'a> {
     }
 }
 
+/// A revision of a secret in the store history.
+#[derive(Clone, Debug)]
+pub struct Revision {
+    /// Commit hash.
+    pub hash: String,
+
+    /// Commit author name and email.
+    pub author: String,
+
+    /// Commit date, in local time.
+    pub date: String,
+
+    /// Commit message subject.
+    pub message: String,
+
+    /// Whether the secret was removed in this revision.
+    pub removed: bool,
+}
+
+impl Revision {
+    /// Abbreviated commit hash.
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(7)]
+    }
+}
+
 /// Defines readyness of store sync.
 ///
 /// Some states block sync usage, including:
@@ -304,6 +390,22 @@ impl Readyness {
     }
 }
 
+/// Possible store relative file paths for a secret name, with forward slashes.
+fn secret_files(name: &str) -> Vec<String> {
+    let mut name = name.trim().replace('\\', "/");
+    for suffix in SECRET_SUFFIXES {
+        if let Some(stripped) = name.strip_suffix(suffix) {
+            name = stripped.into();
+            break;
+        }
+    }
+    let name = name.trim_matches('/');
+    SECRET_SUFFIXES
+        .iter()
+        .map(|suffix| format!("{name}{suffix}"))
+        .collect()
+}
+
 /// Check if repository is dirty.
 ///
 /// Repository is dirty if it has any uncommitted changed.
@@ -344,3 +446,12 @@ fn need_to_push(repo: &Path) -> Result<bool> {
     // Compare local and remote branch hashes
     Ok(git::git_ref_hash(repo, branch)? != git::git_ref_hash(repo, upstream)?)
 }
+
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("unknown revision: {0}")]
+    Revision(String),
+
+    #[error("secret '{0}' does not exist at revision {1}")]
+    NoSecretAt(String, String),
+}
@@ -39,6 +39,37 @@ pub fn systemd_cmd_timer(time: u32, description: &str, unit: &str, cmd: &[&str])
     systemd_run(&systemd_cmd)
 }
 
+/// Spawn transient systemd user service to run the given command.
+///
+/// This runs in the service manager of the current user, and does not require root privileges.
+pub fn systemd_user_cmd_service(description: &str, unit: &str, cmd: &[&str]) -> Result<()> {
+    // Reset unit first if it failed before
+    let _ = cmd_assert_status(
+        Command::new(SYSTEMCTL_BIN)
+            .args(["--quiet", "--user", "reset-failed", unit])
+            .stderr(Stdio::null())
+            .status()
+            .map_err(Err::Systemctl)?,
+    );
+
+    let mut systemd_cmd = vec![
+        "--quiet",
+        "--user",
+        "--description",
+        description,
+        "--unit",
+        unit,
+        "--",
+    ];
+    systemd_cmd.extend(cmd);
+    cmd_assert_status(
+        Command::new(SYSTEMD_RUN_BIN)
+            .args(systemd_cmd)
+            .status()
+            .map_err(Err::SystemdRun)?,
+    )
+}
+
 /// Reset a given failed unit.
 ///
 /// This errors if the given unit is unknown, or if it didn't fail.
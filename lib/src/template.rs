@@ -0,0 +1,281 @@
+//! Secret templates defined by the store.
+//!
+//! Templates are plain text files in `.prs/templates/<name>.tmpl`, describing the layout of new
+//! secrets. They may contain the following placeholders:
+//!
+//! - `{{password}}`: a generated password, `{{password:32}}` for a specific length
+//! - `{{passphrase}}`: a generated passphrase, `{{passphrase:6}}` for a specific number of words
+//! - `{{<field>}}`: a field value, such as `{{username}}`, given by the user
+//!
+//! For example:
+//!
+//! ```text
+//! {{password}}
+//! username: {{username}}
+//! url: {{url}}
+//! totp:
+//! notes:
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use thiserror::Error;
+
+use crate::{Plaintext, Store};
+
+/// Templates directory, relative to the store root.
+pub const TEMPLATES_DIR: &str = ".prs/templates";
+
+/// Template file suffix.
+pub const TEMPLATE_SUFFIX: &str = ".tmpl";
+
+/// Placeholder start delimiter.
+const OPEN: &str = "{{";
+
+/// Placeholder end delimiter.
+const CLOSE: &str = "}}";
+
+/// A secret template.
+#[derive(Clone, Debug)]
+pub struct Template {
+    /// Template name.
+    pub name: String,
+
+    /// Parsed template parts.
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Load the template with the given name from the store.
+    pub fn load(store: &Store, name: &str) -> Result<Self> {
+        if !is_valid_name(name) {
+            return Err(Err::Name(name.into()).into());
+        }
+
+        let path = template_path(store, name);
+        if !path.is_file() {
+            return Err(Err::NotFound(name.into()).into());
+        }
+
+        let data = fs::read_to_string(&path).map_err(Err::Read)?;
+        Ok(Self::parse(name, &data)?)
+    }
+
+    /// Parse a template from the given contents.
+    pub fn parse(name: &str, data: &str) -> Result<Self, Err> {
+        let mut parts = vec![];
+        let mut rest = data;
+        while let Some(start) = rest.find(OPEN) {
+            let end = match rest[start..].find(CLOSE) {
+                Some(end) => start + end,
+                None => break,
+            };
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].into()));
+            }
+            let placeholder = Placeholder::parse(&rest[start + OPEN.len()..end])?;
+            parts.push(Part::Placeholder(placeholder));
+            rest = &rest[end + CLOSE.len()..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.into()));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            parts,
+        })
+    }
+
+    /// List names of all templates in the given store, sorted.
+    pub fn list(store: &Store) -> Vec<String> {
+        let entries = match fs::read_dir(store.root.join(TEMPLATES_DIR)) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_suffix(TEMPLATE_SUFFIX))
+                    .map(|name| name.to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Get the unique placeholders in this template, in order of appearance.
+    pub fn placeholders(&self) -> Vec<&Placeholder> {
+        let mut placeholders: Vec<&Placeholder> = vec![];
+        for part in &self.parts {
+            if let Part::Placeholder(placeholder) = part {
+                if !placeholders.contains(&placeholder) {
+                    placeholders.push(placeholder);
+                }
+            }
+        }
+        placeholders
+    }
+
+    /// Render the template into a plaintext secret.
+    ///
+    /// The `value` function is called once for each unique placeholder, to get the value to fill
+    /// in. Placeholders that occur multiple times get the same value.
+    pub fn render<F>(&self, mut value: F) -> Result<Plaintext>
+    where
+        F: FnMut(&Placeholder) -> Result<Plaintext>,
+    {
+        let mut values: Vec<(&Placeholder, Plaintext)> = vec![];
+        for placeholder in self.placeholders() {
+            values.push((placeholder, value(placeholder)?));
+        }
+
+        let mut plaintext = Plaintext::empty();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => plaintext.append(Plaintext::from(text.as_str()), false),
+                Part::Placeholder(placeholder) => {
+                    let value = values
+                        .iter()
+                        .find(|(p, _)| p == &placeholder)
+                        .map(|(_, value)| value.clone())
+                        .unwrap();
+                    plaintext.append(value, false);
+                }
+            }
+        }
+        Ok(plaintext)
+    }
+}
+
+/// A template part.
+#[derive(Clone, Debug)]
+enum Part {
+    /// Literal text.
+    Text(String),
+
+    /// A placeholder to fill in.
+    Placeholder(Placeholder),
+}
+
+/// A template placeholder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Placeholder {
+    /// A generated password, with an optional length in characters.
+    Password(Option<u16>),
+
+    /// A generated passphrase, with an optional length in words.
+    Passphrase(Option<u16>),
+
+    /// A field value given by the user.
+    Field(String),
+}
+
+impl Placeholder {
+    /// Parse a placeholder from the text between its delimiters.
+    fn parse(text: &str) -> Result<Self, Err> {
+        let text = text.trim();
+        let (kind, length) = match text.split_once(':') {
+            Some((kind, length)) => {
+                let length = length
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|length| *length > 0)
+                    .ok_or_else(|| Err::Placeholder(text.into()))?;
+                (kind.trim(), Some(length))
+            }
+            None => (text, None),
+        };
+
+        match kind {
+            "password" => Ok(Self::Password(length)),
+            "passphrase" => Ok(Self::Passphrase(length)),
+            field if length.is_none() && is_valid_name(field) => Ok(Self::Field(field.into())),
+            _ => Err(Err::Placeholder(text.into())),
+        }
+    }
+}
+
+/// Get the path of a template file in the given store.
+fn template_path(store: &Store, name: &str) -> PathBuf {
+    store
+        .root
+        .join(TEMPLATES_DIR)
+        .join(format!("{name}{TEMPLATE_SUFFIX}"))
+}
+
+/// Check whether the given template or field name is valid.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Template error.
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("invalid template name: {0}")]
+    Name(String),
+
+    #[error("template does not exist: {0}")]
+    NotFound(String),
+
+    #[error("failed to read template")]
+    Read(#[source] std::io::Error),
+
+    #[error("invalid template placeholder: {{{{{0}}}}}")]
+    Placeholder(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_placeholders() {
+        let template = Template::parse(
+            "login",
+            "{{password:32}}\nuser: {{ username }}\n{{username}}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            template.placeholders(),
+            vec![
+                &Placeholder::Password(Some(32)),
+                &Placeholder::Field("username".into()),
+            ],
+        );
+
+        assert!(Template::parse("login", "{{password:x}}").is_err());
+        assert!(Template::parse("login", "{{user name}}").is_err());
+        assert!(Template::parse("login", "{{username:5}}").is_err());
+    }
+
+    #[test]
+    fn template_render() {
+        let template = Template::parse(
+            "login",
+            "{{password}}\nusername: {{username}}\nalias: {{username}}\nnotes: {{",
+        )
+        .unwrap();
+        let plaintext = template
+            .render(|placeholder| match placeholder {
+                Placeholder::Password(None) => Ok("secret".into()),
+                Placeholder::Field(field) if field == "username" => Ok("alice".into()),
+                _ => unreachable!(),
+            })
+            .unwrap();
+        assert_eq!(
+            plaintext.unsecure_to_str().unwrap(),
+            "secret\nusername: alice\nalias: alice\nnotes: {{",
+        );
+    }
+}
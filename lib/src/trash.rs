@@ -0,0 +1,480 @@
+//! Trash for removed secrets.
+//!
+//! Removed secrets are moved into the `.trash/` directory of the store, along with a small
+//! metadata file describing where the secret was removed from and when. Hidden directories are
+//! skipped by `SecretIter`, so trashed secrets don't show up when listing or selecting secrets.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use thiserror::Error;
+
+use crate::store::{Secret, Store, SECRET_SUFFIXES};
+
+/// Trash directory in the store root.
+pub const TRASH_DIR: &str = ".trash";
+
+/// Default number of days to keep removed secrets in the trash.
+pub const TRASH_RETENTION_DAYS: u64 = 30;
+
+/// A trash entry that failed to load, as its ID and the error.
+pub type InvalidEntry = (String, anyhow::Error);
+
+/// Suffix of trash metadata files.
+const INFO_SUFFIX: &str = ".info.toml";
+
+/// Trash helper for given store.
+pub struct Trash<'a> {
+    /// The store.
+    store: &'a Store,
+}
+
+impl<'a> Trash<'a> {
+    /// Construct new trash helper for given store.
+    pub fn new(store: &'a Store) -> Trash<'a> {
+        Self { store }
+    }
+
+    /// Get the trash directory path.
+    fn path(&self) -> PathBuf {
+        self.store.root.join(TRASH_DIR)
+    }
+
+    /// Move a secret into the trash.
+    ///
+    /// The secret file is moved as-is, it is not decrypted.
+    pub fn add(&self, secret: &Secret) -> Result<TrashedSecret> {
+        let dir = self.path();
+        fs::create_dir_all(&dir).map_err(Err::Write)?;
+
+        let file = secret
+            .path
+            .strip_prefix(&self.store.root)
+            .map_err(|_| Err::NotInStore(secret.path.clone()))?
+            .to_path_buf();
+        let removed = SystemTime::now();
+        let secs = removed
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let id = unused_id(&dir, secs);
+        let trashed = TrashedSecret {
+            path: dir.join(format!("{id}{}", file_suffix(&file))),
+            id,
+            name: secret.name.clone(),
+            file,
+            removed,
+        };
+
+        fs::rename(&secret.path, &trashed.path).map_err(Err::Move)?;
+        let info = format!(
+            "name = {}\nfile = {}\nremoved = {secs}\n",
+            toml::Value::String(trashed.name.clone()),
+            toml::Value::String(slash_path(&trashed.file)),
+        );
+        fs::write(self.info_path(&trashed.id), info).map_err(Err::Write)?;
+
+        Ok(trashed)
+    }
+
+    /// List secrets in the trash, most recently removed first.
+    ///
+    /// Entries with invalid metadata are skipped, see `list_checked`.
+    pub fn list(&self) -> Result<Vec<TrashedSecret>> {
+        Ok(self.list_checked()?.0)
+    }
+
+    /// List secrets in the trash, most recently removed first, along with invalid entries.
+    ///
+    /// Entries with metadata that fails to load are returned separately as ID and error, so a
+    /// single corrupt entry doesn't break using the rest of the trash.
+    pub fn list_checked(&self) -> Result<(Vec<TrashedSecret>, Vec<InvalidEntry>)> {
+        let dir = self.path();
+        if !dir.is_dir() {
+            return Ok((vec![], vec![]));
+        }
+
+        let mut secrets = vec![];
+        let mut invalid = vec![];
+        for entry in fs::read_dir(&dir).map_err(Err::Read)? {
+            let entry = entry.map_err(Err::Read)?;
+            let file_name = entry.file_name();
+            let id = match file_name.to_str().and_then(|f| f.strip_suffix(INFO_SUFFIX)) {
+                Some(id) => id,
+                None => continue,
+            };
+            match self.load(id) {
+                Ok(secret) => secrets.push(secret),
+                Err(err) => invalid.push((id.to_string(), err)),
+            }
+        }
+
+        secrets.sort_by(|a, b| b.removed.cmp(&a.removed).then_with(|| b.id.cmp(&a.id)));
+        invalid.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok((secrets, invalid))
+    }
+
+    /// List secrets that have been in the trash for longer than the given retention.
+    pub fn expired(&self, retention: Duration) -> Result<Vec<TrashedSecret>> {
+        let now = SystemTime::now();
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|secret| secret.is_expired(retention, now))
+            .collect())
+    }
+
+    /// Restore a secret from the trash to where it was removed from.
+    ///
+    /// Errors if a secret already exists at that path.
+    pub fn restore(&self, trashed: &TrashedSecret) -> Result<Secret> {
+        let path = self.store.root.join(&trashed.file);
+        if path.exists() {
+            return Err(Err::Exists(trashed.name.clone()).into());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Err::Write)?;
+        }
+
+        fs::rename(&trashed.path, &path).map_err(Err::Move)?;
+        fs::remove_file(self.info_path(&trashed.id)).map_err(Err::Write)?;
+        self.remove_if_empty();
+
+        Ok(Secret::from(self.store, path))
+    }
+
+    /// Permanently delete a secret from the trash.
+    pub fn delete(&self, trashed: &TrashedSecret) -> Result<()> {
+        if trashed.path.exists() {
+            fs::remove_file(&trashed.path).map_err(Err::Write)?;
+        }
+        fs::remove_file(self.info_path(&trashed.id)).map_err(Err::Write)?;
+        self.remove_if_empty();
+        Ok(())
+    }
+
+    /// Load a trashed secret by its ID.
+    fn load(&self, id: &str) -> Result<TrashedSecret> {
+        let data = fs::read_to_string(self.info_path(id)).map_err(Err::Read)?;
+        let table: toml::value::Table =
+            toml::from_str(&data).map_err(|err| Err::Parse(id.into(), err))?;
+        let (name, file, removed) = match (
+            table.get("name").and_then(|v| v.as_str()),
+            table.get("file").and_then(|v| v.as_str()),
+            table.get("removed").and_then(|v| v.as_integer()),
+        ) {
+            (Some(name), Some(file), Some(removed)) if removed >= 0 => (name, file, removed),
+            _ => return Err(Err::Invalid(id.into()).into()),
+        };
+
+        // Never trust the restore path, it must be a secret inside the store
+        let file = PathBuf::from(file);
+        let is_safe = file.components().all(|c| matches!(c, Component::Normal(_)));
+        if !is_safe || file_suffix(&file).is_empty() {
+            return Err(Err::Invalid(id.into()).into());
+        }
+
+        let removed = UNIX_EPOCH
+            .checked_add(Duration::from_secs(removed as u64))
+            .ok_or_else(|| Err::Invalid(id.into()))?;
+
+        Ok(TrashedSecret {
+            id: id.into(),
+            name: name.into(),
+            path: self.path().join(format!("{id}{}", file_suffix(&file))),
+            file,
+            removed,
+        })
+    }
+
+    /// Get the metadata file path for a trashed secret.
+    fn info_path(&self, id: &str) -> PathBuf {
+        self.path().join(format!("{id}{INFO_SUFFIX}"))
+    }
+
+    /// Remove the trash directory if it is empty, ignores errors.
+    fn remove_if_empty(&self) {
+        let _ = fs::remove_dir(self.path());
+    }
+}
+
+/// A secret in the trash.
+#[derive(Clone, Debug)]
+pub struct TrashedSecret {
+    /// Trash entry ID.
+    pub id: String,
+
+    /// Name of the removed secret.
+    pub name: String,
+
+    /// Store relative path the secret was removed from.
+    pub file: PathBuf,
+
+    /// Time the secret was removed.
+    pub removed: SystemTime,
+
+    /// Path of the secret file in the trash.
+    pub path: PathBuf,
+}
+
+/// Find an unused trash ID in the given directory, based on the removal time in seconds.
+fn unused_id(dir: &Path, secs: u64) -> String {
+    (0..)
+        .map(|n| match n {
+            0 => secs.to_string(),
+            n => format!("{secs}-{n}"),
+        })
+        .find(|id| !dir.join(format!("{id}{INFO_SUFFIX}")).exists())
+        .unwrap()
+}
+
+impl TrashedSecret {
+    /// Check whether this secret has been in the trash for longer than the given retention.
+    ///
+    /// Never expires if the retention is too large to represent.
+    pub fn is_expired(&self, retention: Duration, now: SystemTime) -> bool {
+        self.removed
+            .checked_add(retention)
+            .map(|expires| expires <= now)
+            .unwrap_or(false)
+    }
+
+    /// Get this trashed secret as store secret, to inspect or re-encrypt it in place.
+    ///
+    /// It is named after the secret it was removed as, prefixed with the trash directory. See
+    /// `recipients_path` for its recipients.
+    pub fn secret(&self) -> Secret {
+        Secret {
+            name: format!("{TRASH_DIR}/{}", self.name),
+            path: self.path.clone(),
+        }
+    }
+}
+
+/// Get the path the recipients of the given secret are resolved with.
+///
+/// Trashed secrets, see `TrashedSecret::secret`, keep the recipients of the path they were
+/// removed from. Other secrets use their own path.
+pub fn recipients_path(store: &Store, secret: &Secret) -> PathBuf {
+    let trashed = secret.path.starts_with(store.root.join(TRASH_DIR));
+    match secret.name.strip_prefix(&format!("{TRASH_DIR}/")) {
+        Some(name) if trashed => store.root.join(name),
+        _ => secret.path.clone(),
+    }
+}
+
+/// Get the secret suffix of a file, or an empty string if unknown.
+fn file_suffix(file: &Path) -> &'static str {
+    let file = file.to_string_lossy();
+    SECRET_SUFFIXES
+        .iter()
+        .find(|suffix| file.ends_with(*suffix))
+        .copied()
+        .unwrap_or("")
+}
+
+/// Format a relative path with forward slashes, so metadata is the same on all platforms.
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Trash error.
+#[derive(Debug, Error)]
+pub enum Err {
+    #[error("secret is not in the password store: {}", .0.display())]
+    NotInStore(PathBuf),
+
+    #[error("failed to move secret")]
+    Move(#[source] std::io::Error),
+
+    #[error("failed to read trash")]
+    Read(#[source] std::io::Error),
+
+    #[error("failed to write trash")]
+    Write(#[source] std::io::Error),
+
+    #[error("failed to parse trash metadata of {0}")]
+    Parse(String, #[source] toml::de::Error),
+
+    #[error("invalid trash metadata of {0}")]
+    Invalid(String),
+
+    #[error("cannot restore, secret already exists: {0}")]
+    Exists(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write trash metadata for a secret with the given ID.
+    fn write_info(store: &Store, id: &str, file: &str, removed: u64) {
+        let dir = store.root.join(TRASH_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("{id}{INFO_SUFFIX}")),
+            format!("name = \"{id}\"\nfile = \"{file}\"\nremoved = {removed}\n"),
+        )
+        .unwrap();
+    }
+
+    fn store(dir: &tempfile::TempDir) -> Store {
+        Store::open(dir.path().to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn trash_unused_id() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(unused_id(dir.path(), 100), "100");
+
+        fs::write(dir.path().join(format!("100{INFO_SUFFIX}")), "").unwrap();
+        assert_eq!(unused_id(dir.path(), 100), "100-1");
+
+        fs::write(dir.path().join(format!("100-1{INFO_SUFFIX}")), "").unwrap();
+        assert_eq!(unused_id(dir.path(), 100), "100-2");
+        assert_eq!(unused_id(dir.path(), 101), "101");
+    }
+
+    #[test]
+    fn trash_add_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+        let trash = Trash::new(&store);
+        fs::create_dir(dir.path().join("dir")).unwrap();
+        fs::write(dir.path().join("dir/a.gpg"), "a").unwrap();
+        fs::write(dir.path().join("dir/b.gpg"), "b").unwrap();
+
+        let a = trash
+            .add(&Secret::from(&store, store.root.join("dir/a.gpg")))
+            .unwrap();
+        let b = trash
+            .add(&Secret::from(&store, store.root.join("dir/b.gpg")))
+            .unwrap();
+        assert_ne!(a.id, b.id, "trash IDs must be unique");
+        assert!(!dir.path().join("dir/a.gpg").exists());
+        assert_eq!(trash.list().unwrap().len(), 2);
+
+        let restored = trash.restore(&a).unwrap();
+        assert_eq!(restored.name, "dir/a");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dir/a.gpg")).unwrap(),
+            "a"
+        );
+
+        trash.delete(&b).unwrap();
+        assert!(trash.list().unwrap().is_empty());
+        assert!(!dir.path().join(TRASH_DIR).exists());
+    }
+
+    #[test]
+    fn trash_list_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+        write_info(&store, "200", "b.gpg", 200);
+        write_info(&store, "100", "a.gpg", 100);
+        write_info(&store, "300", "c.gpg", 300);
+        write_info(&store, "200-1", "d.gpg", 200);
+
+        let ids: Vec<String> = Trash::new(&store)
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|secret| secret.id)
+            .collect();
+        assert_eq!(ids, vec!["300", "200-1", "200", "100"]);
+    }
+
+    #[test]
+    fn trash_expired() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let day = 24 * 60 * 60;
+        write_info(&store, "old", "old.gpg", now - 31 * day);
+        write_info(&store, "new", "new.gpg", now - day);
+
+        let expired = Trash::new(&store)
+            .expired(Duration::from_secs(TRASH_RETENTION_DAYS * day))
+            .unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, "old");
+    }
+
+    #[test]
+    fn trash_expired_overflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+        write_info(&store, "old", "old.gpg", 1);
+        write_info(&store, "max", "max.gpg", i64::MAX as u64);
+
+        // Must not panic, a timestamp that can't be represented is an invalid entry
+        let (secrets, invalid) = Trash::new(&store).list_checked().unwrap();
+        assert_eq!(secrets.len() + invalid.len(), 2);
+
+        // Retention overflowing the removal time never expires
+        let expired = Trash::new(&store)
+            .expired(Duration::from_secs(u64::MAX))
+            .unwrap();
+        assert!(expired.is_empty());
+        let expired = Trash::new(&store)
+            .expired(Duration::from_secs(TRASH_RETENTION_DAYS * 24 * 60 * 60))
+            .unwrap();
+        let ids: Vec<&str> = expired.iter().map(|secret| secret.id.as_str()).collect();
+        assert_eq!(ids, vec!["old"]);
+    }
+
+    #[test]
+    fn trash_invalid_file() {
+        for file in [
+            "../outside.gpg",
+            "/etc/passwd.gpg",
+            "dir/../../a.gpg",
+            "a.txt",
+            "",
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let store = store(&dir);
+            write_info(&store, "1", file, 1);
+            write_info(&store, "2", "valid.gpg", 2);
+
+            let (secrets, invalid) = Trash::new(&store).list_checked().unwrap();
+            assert!(
+                invalid.len() == 1 && invalid[0].0 == "1",
+                "file '{}' must be rejected",
+                file,
+            );
+            assert_eq!(secrets.len(), 1);
+            assert_eq!(secrets[0].id, "2");
+            assert_eq!(Trash::new(&store).list().unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn trash_recipients_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir);
+        fs::create_dir_all(store.root.join(TRASH_DIR)).unwrap();
+        fs::write(
+            store.root.join(TRASH_DIR).join(format!("1{INFO_SUFFIX}")),
+            "name = \"dir/a\"\nfile = \"dir/a.gpg\"\nremoved = 1\n",
+        )
+        .unwrap();
+
+        let trashed = Trash::new(&store).list().unwrap().remove(0);
+        let secret = trashed.secret();
+        assert_eq!(secret.name, ".trash/dir/a");
+        assert_eq!(recipients_path(&store, &secret), store.root.join("dir/a"));
+
+        let secret = Secret::from(&store, store.root.join("dir/b.gpg"));
+        assert_eq!(recipients_path(&store, &secret), secret.path);
+    }
+}
@@ -8,6 +8,9 @@ use zeroize::Zeroize;
 /// Delimiter for properties.
 const PROPERTY_DELIMITER: char = ':';
 
+/// Line separating free text from a YAML body in a secret, as used by gopass.
+const YAML_SEPARATOR: &str = "---";
+
 /// Newline character(s) on this platform.
 #[cfg(not(windows))]
 pub const NEWLINE: &str = "\n";
@@ -117,27 +120,16 @@ impl Plaintext {
             .into())
     }
 
-    /// Get line with the given property.
+    /// Get value of the given property.
     ///
-    /// Returns line with the given property. The property prefix is removed, and only the trimmed
-    /// value is returned. Returns an error if the property does not exist.
+    /// Reads the property as field of the parsed [`SecretDocument`], returning the trimmed value.
+    /// Returns an error if the property does not exist.
     ///
     /// This will never return the first line being the password.
     pub fn property(&self, property: &str) -> Result<Plaintext> {
-        let property = property.trim().to_uppercase();
-        self.unsecure_to_str()
-            .map_err(Err::Utf8)?
-            .lines()
-            .skip(1)
-            .find_map(|line| {
-                let mut parts = line.splitn(2, PROPERTY_DELIMITER);
-                if parts.next().unwrap().trim().to_uppercase() == property {
-                    Some(parts.next().map(|value| value.trim()).unwrap_or("").into())
-                } else {
-                    None
-                }
-            })
-            .ok_or_else(|| Err::Property(property.to_lowercase()).into())
+        SecretDocument::parse(self)?
+            .get(property)
+            .ok_or_else(|| Err::Property(property.trim().to_lowercase()).into())
     }
 
     /// Append other plaintext.
@@ -188,6 +180,495 @@ impl From<&str> for Plaintext {
     }
 }
 
+/// Format of fields in a secret body.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DocumentFormat {
+    /// Pass-style `key: value` properties.
+    Properties,
+
+    /// gopass-compatible YAML body, following a `---` line.
+    Yaml,
+}
+
+/// Structured secret document.
+///
+/// Parses secret plaintext into the password on the first line, and fields in the body. Fields
+/// are read from pass-style `key: value` properties, where indented lines continue the value of
+/// the previous property, or from a gopass-compatible YAML body following a `---` line. Field keys
+/// are matched case-insensitively.
+///
+/// Lines that aren't fields, such as comments and free text, are kept as-is. Serializing the
+/// document back preserves ordering and comments. Contents are zeroed on drop.
+pub struct SecretDocument {
+    /// The password, first line of the secret.
+    password: String,
+
+    /// Body format.
+    format: DocumentFormat,
+
+    /// Body lines, after the password.
+    lines: Vec<Line>,
+
+    /// Whether the secret ends with a newline.
+    trailing_newline: bool,
+}
+
+impl SecretDocument {
+    /// Parse secret document from plaintext.
+    pub fn parse(plaintext: &Plaintext) -> Result<Self> {
+        let text = plaintext.unsecure_to_str().map_err(Err::Utf8)?;
+        let mut lines = text.lines();
+        let password = lines.next().unwrap_or("").to_string();
+        let body: Vec<&str> = lines.collect();
+
+        // Text before YAML separator is read as properties
+        let separator = body
+            .iter()
+            .position(|line| line.trim_end() == YAML_SEPARATOR);
+        let mut document = Self {
+            password,
+            format: match separator {
+                Some(_) => DocumentFormat::Yaml,
+                None => DocumentFormat::Properties,
+            },
+            lines: Vec::with_capacity(body.len()),
+            trailing_newline: text.ends_with('\n'),
+        };
+        for (i, line) in body.iter().enumerate() {
+            match separator {
+                Some(separator) if i == separator => {
+                    document.lines.push(Line::Text(line.to_string()))
+                }
+                Some(separator) if i > separator => document.push_line(DocumentFormat::Yaml, line),
+                _ => document.push_line(DocumentFormat::Properties, line),
+            }
+        }
+        document.detach_blank_lines();
+
+        Ok(document)
+    }
+
+    /// Parse and push a body line in the given format.
+    fn push_line(&mut self, format: DocumentFormat, line: &str) {
+        // Continue previous field with indented lines, or blank lines in YAML
+        let continues = line.starts_with(char::is_whitespace)
+            || (format == DocumentFormat::Yaml && line.is_empty());
+        if continues {
+            if let Some(Line::Field(field)) = self.lines.last_mut() {
+                if field.format == format {
+                    field.lines.push(line.into());
+                    return;
+                }
+            }
+        }
+
+        self.lines.push(match parse_key(format, line) {
+            Some(key) => Line::Field(Field {
+                key,
+                format,
+                lines: vec![line.into()],
+            }),
+            None => Line::Text(line.into()),
+        });
+    }
+
+    /// Move trailing blank lines of fields out of them, they separate fields.
+    fn detach_blank_lines(&mut self) {
+        let mut lines = Vec::with_capacity(self.lines.len());
+        for mut line in self.lines.drain(..) {
+            let mut blank = 0;
+            if let Line::Field(field) = &mut line {
+                while field.lines.len() > 1 && field.lines.last().unwrap().trim().is_empty() {
+                    field.lines.pop().unwrap().zeroize();
+                    blank += 1;
+                }
+            }
+            lines.push(line);
+            lines.extend((0..blank).map(|_| Line::Text(String::new())));
+        }
+        self.lines = lines;
+    }
+
+    /// Body format.
+    ///
+    /// This is YAML if the body has a `---` separator line.
+    pub fn format(&self) -> DocumentFormat {
+        self.format
+    }
+
+    /// Get the password.
+    pub fn password(&self) -> Plaintext {
+        self.password.as_str().into()
+    }
+
+    /// Set the password.
+    ///
+    /// Errors if the password spans multiple lines.
+    pub fn set_password(&mut self, password: Plaintext) -> Result<()> {
+        let password = password.unsecure_to_str().map_err(Err::Utf8)?;
+        if password.contains('\n') {
+            return Err(Err::MultilinePassword.into());
+        }
+        self.password.zeroize();
+        self.password = password.into();
+        Ok(())
+    }
+
+    /// Keys of all fields in order, without duplicates.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = vec![];
+        for field in self.fields() {
+            if !keys.iter().any(|key| key_eq(key, &field.key)) {
+                keys.push(&field.key);
+            }
+        }
+        keys
+    }
+
+    /// Get value of the given field.
+    ///
+    /// Returns the first if the field is defined multiple times.
+    pub fn get(&self, key: &str) -> Option<Plaintext> {
+        self.fields()
+            .find(|field| key_eq(&field.key, key))
+            .map(|field| field.value().into())
+    }
+
+    /// Get values of all fields with the given key, in order.
+    pub fn get_all(&self, key: &str) -> Vec<Plaintext> {
+        self.fields()
+            .filter(|field| key_eq(&field.key, key))
+            .map(|field| field.value().into())
+            .collect()
+    }
+
+    /// Set value of the given field.
+    ///
+    /// Replaces the first field with the key in place, and removes any duplicates. New fields are
+    /// added at the end of the body, in the body format.
+    pub fn set(&mut self, key: &str, value: Plaintext) -> Result<()> {
+        let key = key.trim();
+        if key.is_empty() || key.starts_with(['#', '-']) || key.contains([':', '\n']) {
+            return Err(Err::Key(key.into()).into());
+        }
+        let value = value.unsecure_to_str().map_err(Err::Utf8)?;
+
+        match self.lines.iter().position(|line| line.is_key(key)) {
+            Some(i) => {
+                if let Line::Field(field) = &mut self.lines[i] {
+                    let lines = format_field(field.format, &field.key, value);
+                    field.lines.zeroize();
+                    field.lines = lines;
+                }
+                let mut j = 0;
+                self.lines.retain_mut(|line| {
+                    j += 1;
+                    j <= i + 1 || !line.is_key(key) || {
+                        line.zeroize();
+                        false
+                    }
+                });
+            }
+            None => {
+                // Insert before trailing blank lines
+                let i = self
+                    .lines
+                    .iter()
+                    .rposition(|line| !matches!(line, Line::Text(text) if text.trim().is_empty()))
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+                self.lines.insert(
+                    i,
+                    Line::Field(Field {
+                        key: key.into(),
+                        format: self.format,
+                        lines: format_field(self.format, key, value),
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove all fields with the given key.
+    ///
+    /// Returns whether any field was removed.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let len = self.lines.len();
+        self.lines.retain_mut(|line| {
+            !line.is_key(key) || {
+                line.zeroize();
+                false
+            }
+        });
+        self.lines.len() != len
+    }
+
+    /// Serialize document back into plaintext.
+    pub fn to_plaintext(&self) -> Plaintext {
+        let mut lines = vec![self.password.as_str()];
+        for line in &self.lines {
+            match line {
+                Line::Text(text) => lines.push(text),
+                Line::Field(field) => lines.extend(field.lines.iter().map(|l| l.as_str())),
+            }
+        }
+        let mut text = lines.join(NEWLINE);
+        if self.trailing_newline {
+            text.push_str(NEWLINE);
+        }
+        text.into()
+    }
+
+    /// Iterate over all fields.
+    fn fields(&self) -> impl Iterator<Item = &Field> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Field(field) => Some(field),
+            Line::Text(_) => None,
+        })
+    }
+}
+
+impl Drop for SecretDocument {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.lines.iter_mut().for_each(Line::zeroize);
+    }
+}
+
+/// A line in a secret document body.
+enum Line {
+    /// Free text, comment or separator, kept as-is.
+    Text(String),
+
+    /// A field, possibly spanning multiple lines.
+    Field(Field),
+}
+
+impl Line {
+    /// Check whether this is a field with the given key.
+    fn is_key(&self, key: &str) -> bool {
+        matches!(self, Line::Field(field) if key_eq(&field.key, key))
+    }
+
+    /// Zero line contents.
+    fn zeroize(&mut self) {
+        match self {
+            Line::Text(text) => text.zeroize(),
+            Line::Field(field) => {
+                field.key.zeroize();
+                field.lines.zeroize();
+            }
+        }
+    }
+}
+
+/// A field in a secret document body.
+struct Field {
+    /// Field key, as written.
+    key: String,
+
+    /// Format the field is written in.
+    format: DocumentFormat,
+
+    /// Raw lines of the field.
+    lines: Vec<String>,
+}
+
+impl Field {
+    /// Get the field value.
+    fn value(&self) -> String {
+        let first = self.lines[0]
+            .split_once(PROPERTY_DELIMITER)
+            .map(|(_, value)| value.trim())
+            .unwrap_or("");
+        let rest = &self.lines[1..];
+        match self.format {
+            DocumentFormat::Properties => std::iter::once(first)
+                .chain(rest.iter().map(|line| line.trim()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            DocumentFormat::Yaml => yaml_value(first, rest),
+        }
+    }
+}
+
+/// Compare field keys, case-insensitive.
+fn key_eq(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// Parse the field key from the given line, if it is a field.
+fn parse_key(format: DocumentFormat, line: &str) -> Option<String> {
+    let (key, rest) = line.split_once(PROPERTY_DELIMITER)?;
+    let key = key.trim();
+    if key.is_empty() || key.starts_with('#') {
+        return None;
+    }
+    match format {
+        DocumentFormat::Properties => Some(key.into()),
+        DocumentFormat::Yaml => {
+            // Top-level mapping keys only, the delimiter must be followed by a space
+            if line.starts_with(char::is_whitespace)
+                || key.starts_with('-')
+                || !(rest.is_empty() || rest.starts_with(' '))
+            {
+                return None;
+            }
+            Some(
+                yaml_unquote(key)
+                    .map(|(key, _)| key)
+                    .unwrap_or_else(|| key.into()),
+            )
+        }
+    }
+}
+
+/// Format field lines for the given key and value.
+fn format_field(format: DocumentFormat, key: &str, value: &str) -> Vec<String> {
+    let mut lines = value.lines();
+    let first = lines.next().unwrap_or("");
+    match format {
+        DocumentFormat::Properties => std::iter::once(format!("{key}: {first}"))
+            .chain(lines.map(|line| format!("  {line}")))
+            .collect(),
+        DocumentFormat::Yaml if value.trim_end().contains('\n') => {
+            // Literal block scalar, indentation indicator if first line is indented
+            let indicator = if first.starts_with(' ') { "2" } else { "" };
+            std::iter::once(format!("{key}: |{indicator}-"))
+                .chain(value.trim_end_matches('\n').lines().map(|line| {
+                    if line.is_empty() {
+                        String::new()
+                    } else {
+                        format!("  {line}")
+                    }
+                }))
+                .collect()
+        }
+        DocumentFormat::Yaml if yaml_plain_safe(value) => vec![format!("{key}: {value}")],
+        DocumentFormat::Yaml => vec![format!("{key}: {}", yaml_quote(value))],
+    }
+}
+
+/// Get a YAML value from the text after the key, and continuation lines.
+///
+/// Supports plain, quoted and block scalars. Nested data is returned as YAML text.
+fn yaml_value(first: &str, rest: &[String]) -> String {
+    let block = yaml_dedent(rest);
+    match first.chars().next() {
+        // Nested mapping or sequence
+        None => block.join("\n"),
+
+        // Block scalar
+        Some('|') => block.join("\n"),
+        Some('>') => {
+            let mut value = String::new();
+            for line in block {
+                if line.is_empty() || line.starts_with(' ') {
+                    value.push('\n');
+                } else if !value.is_empty() && !value.ends_with('\n') {
+                    value.push(' ');
+                }
+                value.push_str(line);
+            }
+            value
+        }
+
+        // Quoted or plain scalar, possibly spanning multiple lines
+        Some(_) => {
+            let text = std::iter::once(first)
+                .chain(rest.iter().map(|line| line.trim()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            match yaml_unquote(&text) {
+                Some((value, _)) => value,
+                None => match text.find(" #") {
+                    Some(i) => text[..i].trim_end().into(),
+                    None => text,
+                },
+            }
+        }
+    }
+}
+
+/// Remove common indentation from YAML block lines, and strip trailing blank lines.
+fn yaml_dedent(lines: &[String]) -> Vec<&str> {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect();
+    while lines.last().map(|line| line.trim().is_empty()) == Some(true) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Unquote a single or double quoted YAML scalar.
+///
+/// Returns the value and the remaining text after the closing quote, or `None` if not quoted.
+fn yaml_unquote(text: &str) -> Option<(String, &str)> {
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(c) => value.push(c),
+                None => return None,
+            },
+            '\'' if quote == '\'' && chars.peek().map(|(_, c)| *c) == Some('\'') => {
+                chars.next();
+                value.push('\'');
+            }
+            c if c == quote => return Some((value, &text[i + 1..])),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Double quote a YAML scalar.
+fn yaml_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\t' => quoted.push_str("\\t"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Check whether a value can be written as plain YAML scalar, and is read back as string.
+fn yaml_plain_safe(value: &str) -> bool {
+    !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.ends_with(':')
+        && !value.contains(|c: char| c.is_control())
+        && value.parse::<f64>().is_err()
+        && !["true", "false", "yes", "no", "on", "off", "null", "~"]
+            .contains(&value.to_lowercase().as_str())
+}
+
 /// A plaintext or ciphertext handling error.
 #[derive(Debug, Error)]
 pub enum Err {
@@ -196,6 +677,12 @@ pub enum Err {
 
     #[error("property '{}' does not exist in plaintext", _0)]
     Property(String),
+
+    #[error("invalid secret field key: '{}'", _0)]
+    Key(String),
+
+    #[error("password must be a single line")]
+    MultilinePassword,
 }
 
 #[cfg(test)]
@@ -389,6 +876,113 @@ mod tests {
         }
     }
 
+    #[test]
+    fn document_roundtrip() {
+        #[rustfmt::skip]
+        let set = vec![
+            "",
+            "\n",
+            "pass",
+            "pass\n",
+            "pass\nName: abc\n\nfree text\nNote: multi\n  line\n",
+            "pass\n---\n# comment\nuser: abc # inline\nnote: |\n  a\n\n  b\n\nlist:\n  - a\n  - b\n",
+        ];
+
+        for input in set {
+            let document = SecretDocument::parse(&Plaintext::from(input)).unwrap();
+            assert_eq!(
+                document.to_plaintext().unsecure_to_str().unwrap(),
+                input.replace('\n', NEWLINE),
+                "document should serialize back unchanged",
+            );
+        }
+    }
+
+    #[test]
+    fn document_get() {
+        let document = SecretDocument::parse(&Plaintext::from(
+            "pass\nName: abc\nNote: multi\n  line\nname: def",
+        ))
+        .unwrap();
+        assert_eq!(document.format(), DocumentFormat::Properties);
+        assert_eq!(document.password().unsecure_to_str().unwrap(), "pass");
+        assert_eq!(document.keys(), vec!["Name", "Note"]);
+        assert_eq!(
+            document.get("note").unwrap().unsecure_to_str().unwrap(),
+            "multi\nline"
+        );
+        assert_eq!(document.get_all("name").len(), 2);
+
+        let document = SecretDocument::parse(&Plaintext::from(
+            "pass\nurl: https://example.com\n---\n# comment\nuser: abc # inline\nquoted: \"a # b\\n\"\n\
+             single: 'it''s'\nliteral: |\n  a\n\n  b\nfolded: >-\n  a\n  b\nlist:\n  - a\n  - b\n",
+        ))
+        .unwrap();
+        assert_eq!(document.format(), DocumentFormat::Yaml);
+
+        // (property, output)
+        #[rustfmt::skip]
+        let set = vec![
+            ("url", Some("https://example.com")),
+            ("user", Some("abc")),
+            ("quoted", Some("a # b\n")),
+            ("single", Some("it's")),
+            ("literal", Some("a\n\nb")),
+            ("folded", Some("a b")),
+            ("list", Some("- a\n- b")),
+            ("comment", None),
+            ("missing", None),
+        ];
+
+        for (property, output) in set {
+            assert_eq!(
+                document
+                    .get(property)
+                    .map(|value| value.unsecure_to_str().unwrap().to_string()),
+                output.map(|output| output.to_string()),
+                "incorrect field value",
+            );
+        }
+    }
+
+    #[test]
+    fn document_set_remove() {
+        // Replace in place, remove duplicates, append new fields before trailing blank lines
+        let mut document = SecretDocument::parse(&Plaintext::from(
+            "pass\nName: abc\n# comment\nname: def\nMail: a@example.com\n\n",
+        ))
+        .unwrap();
+        document.set("name", "ghi".into()).unwrap();
+        document.set("Note", "multi\nline".into()).unwrap();
+        assert!(document.remove("mail"));
+        assert!(!document.remove("mail"));
+        assert_eq!(
+            document.to_plaintext().unsecure_to_str().unwrap(),
+            "pass\nName: ghi\n# comment\nNote: multi\n  line\n\n".replace('\n', NEWLINE),
+        );
+
+        // Fields are written as YAML in YAML body
+        let mut document =
+            SecretDocument::parse(&Plaintext::from("pass\n---\n# comment\nuser: abc")).unwrap();
+        document.set("user", "def".into()).unwrap();
+        document.set("pin", "0123".into()).unwrap();
+        document.set("note", "a\nb".into()).unwrap();
+        assert_eq!(
+            document.to_plaintext().unsecure_to_str().unwrap(),
+            "pass\n---\n# comment\nuser: def\npin: \"0123\"\nnote: |-\n  a\n  b"
+                .replace('\n', NEWLINE),
+        );
+        for (key, value) in [("user", "def"), ("pin", "0123"), ("note", "a\nb")] {
+            let parsed = SecretDocument::parse(&document.to_plaintext()).unwrap();
+            assert_eq!(parsed.get(key).unwrap().unsecure_to_str().unwrap(), value);
+        }
+
+        // Invalid keys and passwords
+        assert!(document.set("", "abc".into()).is_err());
+        assert!(document.set("a:b", "abc".into()).is_err());
+        assert!(document.set_password("a\nb".into()).is_err());
+    }
+
     #[quickcheck]
     fn plaintext_must_zero_on_drop(plaintext: String) -> bool {
         // Skip all-zero/empty because we cannot reliably test
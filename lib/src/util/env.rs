@@ -1,4 +1,5 @@
 use std::env;
+use std::path::PathBuf;
 
 /// Check whether we're in a Wayland environment
 #[cfg(all(feature = "tomb", target_os = "linux"))]
@@ -15,3 +16,25 @@ pub fn has_gpg_tty() -> bool {
 pub fn has_non_empty_env(env: &str) -> bool {
     env::var_os(env).map(|v| !v.is_empty()).unwrap_or(false)
 }
+
+/// Get the prs data directory.
+///
+/// Uses `$XDG_DATA_HOME/prs`, and falls back to `~/.local/share/prs`.
+pub fn data_dir() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(shellexpand::tilde("~/.local/share").as_ref()))
+        .join("prs")
+}
+
+/// Get the prs runtime directory, for files that must not outlive the user session.
+///
+/// Uses `$XDG_RUNTIME_DIR/prs`, and falls back to `prs-<uid>` in the temporary directory.
+#[cfg(unix)]
+pub fn runtime_dir() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("prs"))
+        .unwrap_or_else(|| env::temp_dir().join(format!("prs-{}", nix::unistd::getuid())))
+}
@@ -45,6 +45,26 @@ pub fn append_file_name(path: &Path, suffix: &str) -> Result<PathBuf> {
     )))
 }
 
+/// Create a directory that is only accessible by the current user, if it doesn't exist.
+///
+/// Parent directories are created as well.
+pub fn create_private_dir(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        return Ok(());
+    }
+
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(path)
+        .map_err(|err| Err::CreateDir(err).into())
+}
+
 /// Chown a path to the current process' with `sudo`.
 #[cfg(all(feature = "tomb", target_os = "linux"))]
 pub(crate) fn sudo_chown(path: &Path, uid: u32, gid: u32, recursive: bool) -> Result<()> {
@@ -97,6 +117,9 @@ pub enum Err {
     #[error("failed to append suffix to file path, unknown name")]
     UnknownName,
 
+    #[error("failed to create directory")]
+    CreateDir(#[source] std::io::Error),
+
     #[error("failed to invoke 'sudo chown' on path")]
     SudoChown(std::io::Error),
 